@@ -1,5 +1,6 @@
 mod big_map;
 mod entrypoints;
+mod permit;
 mod storage;
 
 use async_trait::async_trait;
@@ -37,6 +38,7 @@ use self::entrypoints::MappedEntrypoints;
 pub use self::{
     big_map::{BigMap, BigMapContainer},
     entrypoints::EntrypointPathComponent,
+    permit::{Permit, PermitPayload},
     storage::Storage,
 };
 
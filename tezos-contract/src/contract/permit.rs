@@ -0,0 +1,378 @@
+use tezos_core::{
+    internal::crypto::blake2b,
+    types::{
+        encoded::{ChainId, ContractHash, Encoded, PublicKey, SecretKey, Signature},
+        number::Nat,
+    },
+    Tezos,
+};
+use tezos_michelson::{
+    micheline::Micheline,
+    michelson::{
+        data::{self, Data},
+        types::{self, Type},
+        Michelson,
+    },
+};
+use tezos_operation::{
+    operations::{Entrypoint, Parameters},
+    signer::Signer,
+    signing_payload::SigningPayload,
+};
+
+use crate::{Error, Result};
+
+use super::{Contract, PartialTransaction};
+
+/// The payload a key holder signs to permit a contract call on their behalf, as specified by TZIP-17.
+///
+/// The payload binds the hash of the call parameters to the chain, the contract and the contract's
+/// current permit counter, so that a permit can't be replayed:
+/// `Pair (Pair chain_id contract) (Pair counter (blake2b (pack parameters)))`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PermitPayload {
+    pub chain_id: ChainId,
+    pub contract: ContractHash,
+    pub counter: Nat,
+    pub parameters_hash: Vec<u8>,
+}
+
+impl PermitPayload {
+    const PARAMETERS_HASH_SIZE: usize = 32;
+
+    pub fn new(
+        chain_id: ChainId,
+        contract: ContractHash,
+        counter: Nat,
+        parameters_hash: Vec<u8>,
+    ) -> Self {
+        Self {
+            chain_id,
+            contract,
+            counter,
+            parameters_hash,
+        }
+    }
+
+    /// Returns the type of the payload: `pair (pair chain_id address) (pair nat bytes)`.
+    pub fn schema() -> Type {
+        types::pair(vec![
+            types::pair(vec![types::chain_id(), types::address()]),
+            types::pair(vec![types::nat(), types::bytes()]),
+        ])
+    }
+
+    /// Returns the payload as a Michelson value.
+    pub fn value(&self) -> Result<Data> {
+        Ok(data::pair(vec![
+            data::pair(vec![
+                data::try_string(self.chain_id.value())?,
+                data::try_string(self.contract.value())?,
+            ]),
+            data::pair(vec![
+                self.counter.clone().into(),
+                data::bytes(self.parameters_hash.clone()),
+            ]),
+        ]))
+    }
+
+    /// Packs the payload into a [SigningPayload], see [Michelson::pack].
+    pub fn to_signing_payload(&self) -> Result<SigningPayload> {
+        let value: Michelson = self.value()?.into();
+        let bytes = value.pack(Some(&Self::schema()))?;
+
+        Ok(SigningPayload::from_bytes(&bytes)?)
+    }
+
+    /// Signs the payload with the given secret key and using the crypto providers configured in the given [Tezos] instance.
+    pub fn sign_with(&self, key: &SecretKey, tezos: &Tezos) -> Result<Signature> {
+        Ok(self.to_signing_payload()?.sign_with(key, tezos)?)
+    }
+
+    /// Signs the payload with the given secret key.
+    pub fn sign(&self, key: &SecretKey) -> Result<Signature> {
+        self.sign_with(key, &Default::default())
+    }
+
+    /// Signs the payload with the given [Signer] and collects the signature together with the signer's public key.
+    pub async fn sign_using<S>(&self, signer: &S) -> Result<Permit>
+    where
+        S: Signer + ?Sized,
+    {
+        let signature = self.to_signing_payload()?.sign_using(signer).await?;
+        let public_key = signer.public_key().await?;
+
+        Ok(Permit::new(
+            public_key,
+            signature,
+            self.parameters_hash.clone(),
+        ))
+    }
+
+    /// Verifies the `signature` of the payload with the given public key and using
+    /// the crypto providers configured in the given [Tezos] instance.
+    pub fn verify_with(
+        &self,
+        signature: &Signature,
+        key: &PublicKey,
+        tezos: &Tezos,
+    ) -> Result<bool> {
+        Ok(self
+            .to_signing_payload()?
+            .verify_with(signature, key, tezos)?)
+    }
+
+    /// Verifies the `signature` of the payload with the given public key.
+    pub fn verify(&self, signature: &Signature, key: &PublicKey) -> Result<bool> {
+        self.verify_with(signature, key, &Default::default())
+    }
+}
+
+/// A signed permit, i.e. a single element of the `permit` entrypoint argument:
+/// `pair key (pair signature bytes)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Permit {
+    pub public_key: PublicKey,
+    pub signature: Signature,
+    pub parameters_hash: Vec<u8>,
+}
+
+impl Permit {
+    pub fn new(public_key: PublicKey, signature: Signature, parameters_hash: Vec<u8>) -> Self {
+        Self {
+            public_key,
+            signature,
+            parameters_hash,
+        }
+    }
+
+    /// Returns the permit as a Michelson value.
+    pub fn value(&self) -> Result<Data> {
+        Ok(data::pair(vec![
+            data::try_string(self.public_key.value())?,
+            data::pair(vec![
+                data::try_string(self.signature.value())?,
+                data::bytes(self.parameters_hash.clone()),
+            ]),
+        ]))
+    }
+}
+
+impl Contract {
+    const PERMIT_ENTRYPOINT: &'static str = "permit";
+    const PERMIT_COUNTER: &'static str = "counter";
+
+    /// Returns the current permit counter, read from the `%counter` field of the contract's storage.
+    ///
+    /// The counter is read from the storage fetched with the contract, which has to be fetched again
+    /// once a permit has been submitted.
+    pub fn permit_counter(&self) -> Result<Nat> {
+        let counter = self
+            .storage()
+            .get_by_name(Self::PERMIT_COUNTER)
+            .ok_or(Error::PermitCounterNotFound)?;
+
+        match counter {
+            Data::Nat(value) => Ok(value.clone()),
+            Data::Int(value) => Ok(value.to_str().try_into()?),
+            _ => Err(Error::PermitCounterNotFound),
+        }
+    }
+
+    /// Computes the hash of the `parameters` of a call to the contract, which a permit is issued for.
+    pub fn parameters_hash(&self, parameters: &Parameters) -> Result<Vec<u8>> {
+        let schema: Micheline = self
+            .entrypoints
+            .get(&parameters.entrypoint)
+            .ok_or(Error::EntrypointNotFound)?
+            .into();
+        let packed = parameters.value.clone().pack(Some(&schema))?;
+
+        Ok(blake2b(&packed, PermitPayload::PARAMETERS_HASH_SIZE)?)
+    }
+
+    /// Creates the payload to sign to permit calling the `entrypoint` with the given `arguments`,
+    /// see [Contract::call].
+    ///
+    /// The `counter` is the contract's current permit counter, see [Contract::permit_counter].
+    pub fn permit_payload(
+        &self,
+        chain_id: ChainId,
+        counter: Nat,
+        entrypoint: Entrypoint,
+        arguments: Vec<(&str, Data)>,
+    ) -> Result<PermitPayload> {
+        let parameters = self
+            .call(entrypoint, arguments)?
+            .parameters
+            .ok_or(Error::EntrypointNotFound)?;
+
+        Ok(PermitPayload::new(
+            chain_id,
+            self.address().clone(),
+            counter,
+            self.parameters_hash(&parameters)?,
+        ))
+    }
+
+    /// Creates a call to the `permit` entrypoint submitting the collected `permits`.
+    pub fn permit(&self, permits: &[Permit]) -> Result<PartialTransaction> {
+        let permits = permits
+            .iter()
+            .map(|permit| permit.value())
+            .collect::<Result<Vec<_>>>()?;
+
+        self.call(
+            Self::PERMIT_ENTRYPOINT.into(),
+            vec![("", data::sequence(permits))],
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use httpmock::{Method::POST, MockServer};
+    use tezos_rpc::client::TezosRpc;
+
+    use super::*;
+    use crate::ContractFetcher;
+
+    async fn contract(server: &MockServer) -> Result<Contract> {
+        let contract_address: ContractHash = "KT1J4CiyWPmtFPXAjpgBezM5hoVHXHNzWBHK".try_into()?;
+        server.mock(|when, then| {
+            when.method(POST).path(format!(
+                "/chains/main/blocks/head/context/contracts/{}/script/normalized",
+                contract_address.value(),
+            ));
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(include_str!("../__TEST_DATA__/permit_contract.json"));
+        });
+        let rpc = TezosRpc::new(server.base_url());
+
+        rpc.contract_at(contract_address, None).await
+    }
+
+    fn transfer() -> Result<Vec<(&'static str, Data)>> {
+        Ok(vec![(
+            "",
+            data::sequence(vec![data::pair(vec![
+                data::try_string("tz1RFxSHjxxyaaXyAvJG3uRQeJUCCm4jk2LX")?,
+                data::sequence(vec![data::pair(vec![
+                    data::try_string("tz1agAtczEzZS8tV67KGF4urNqxfNCSPksiW")?,
+                    0u8.into(),
+                    100u8.into(),
+                ])]),
+            ])]),
+        )])
+    }
+
+    #[tokio::test]
+    async fn test_permit_counter() -> Result<()> {
+        let server = MockServer::start();
+        let contract = contract(&server).await?;
+
+        assert_eq!(contract.permit_counter()?, 7u8.into());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_permit_payload() -> Result<()> {
+        let server = MockServer::start();
+        let contract = contract(&server).await?;
+        let chain_id: ChainId = "NetXdQprcVkpaWU".try_into()?;
+
+        let payload = contract.permit_payload(
+            chain_id.clone(),
+            contract.permit_counter()?,
+            "transfer".into(),
+            transfer()?,
+        )?;
+        let parameters = contract
+            .call("transfer".into(), transfer()?)?
+            .parameters
+            .unwrap();
+        let schema: Micheline = contract.entrypoints.get(&"transfer".into()).unwrap().into();
+        let packed_parameters = parameters.value.pack(Some(&schema))?;
+        assert_eq!(
+            payload,
+            PermitPayload::new(
+                chain_id,
+                contract.address().clone(),
+                7u8.into(),
+                blake2b(&packed_parameters, 32)?,
+            )
+        );
+
+        let bytes = payload.to_signing_payload()?.to_bytes()?;
+        let expected = [
+            [0x05, 0x07, 0x07, 0x07, 0x07].as_slice(),
+            &[0x0a, 0x00, 0x00, 0x00, 0x04, 0x7a, 0x06, 0xa7, 0x70],
+            &[0x0a, 0x00, 0x00, 0x00, 0x16, 0x01],
+            &contract.address().to_bytes()?,
+            &[0x00, 0x07, 0x07, 0x00, 0x07],
+            &[0x0a, 0x00, 0x00, 0x00, 0x20],
+            &payload.parameters_hash,
+        ]
+        .concat();
+        assert_eq!(bytes, expected);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_permit() -> Result<()> {
+        let server = MockServer::start();
+        let contract = contract(&server).await?;
+
+        let permits = vec![Permit::new(
+            "edpkttZKC51wemRqL2QxwpMnEKxWnbd35pq47Y6xsCHp5M1f7LN8NP".try_into()?,
+            "sigQDoDViNfDQ5Pwt4bSopViWWfHbh4uGM9C4689VzJS7narQbtnDeqesJhH7z9MqX6FJhgf22fF5GfJ61wtDs5bq1WiN9Vj".try_into()?,
+            vec![0x01; 32],
+        )];
+        let partial_transaction = contract.permit(&permits)?;
+
+        let parameters = partial_transaction.parameters.unwrap();
+        assert_eq!(parameters.entrypoint, "permit".into());
+        let schema: Micheline = types::list::<Type>(types::pair(vec![
+            types::key(),
+            types::pair(vec![types::signature(), types::bytes()]),
+        ]))
+        .into();
+        let expected: Micheline = data::sequence::<Data>(vec![permits[0].value()?]).into();
+        assert_eq!(
+            parameters.value.pack(Some(&schema))?,
+            expected.pack(Some(&schema))?
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "ed25519")]
+    #[tokio::test]
+    async fn test_sign_permit() -> Result<()> {
+        use tezos_operation::signer::InMemorySigner;
+
+        let server = MockServer::start();
+        let contract = contract(&server).await?;
+        let secret_key: SecretKey = "edskRv7VyXGVZb8EsrR7D9XKUbbAQNQGtALP6QeB16ZCD7SmmJpzyeneJVg3Mq56YLbxRA1kSdAXiswwPiaVfR3NHGMCXCziuZ".try_into()?;
+        let public_key: PublicKey =
+            "edpkttZKC51wemRqL2QxwpMnEKxWnbd35pq47Y6xsCHp5M1f7LN8NP".try_into()?;
+
+        let payload = contract.permit_payload(
+            "NetXdQprcVkpaWU".try_into()?,
+            contract.permit_counter()?,
+            "transfer".into(),
+            transfer()?,
+        )?;
+        let signer = InMemorySigner::new(secret_key.clone(), public_key.clone());
+        let permit = payload.sign_using(&signer).await?;
+
+        assert_eq!(permit.signature, payload.sign(&secret_key)?);
+        assert_eq!(permit.public_key, public_key);
+        assert!(payload.verify(&permit.signature, &public_key)?);
+
+        Ok(())
+    }
+}
@@ -16,8 +16,15 @@ pub enum Error {
     Rpc {
         source: tezos_rpc::Error,
     },
+    Operation {
+        source: tezos_operation::Error,
+    },
     InvalidContractScript,
     EntrypointNotFound,
+    PermitCounterNotFound,
+    InvalidMultisigStorage,
+    UnknownMultisigKey,
+    NotEnoughSignatures,
     #[from(ignore)]
     IncompatibleValue {
         description: String,
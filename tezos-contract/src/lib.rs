@@ -202,9 +202,53 @@
 //!     Ok(())
 //! }
 //! ```
+//!
+//! ## Permit a Contract Call
+//!
+//! Contracts implementing TZIP-17 accept calls signed off-chain by the key holder and submitted by
+//! another account through the `permit` entrypoint:
+//!
+//! ```rust
+//! use tezos_core::types::encoded::{ChainId, Encoded, ImplicitAddress};
+//! use tezos_rpc::client::TezosRpc;
+//! use tezos_michelson::michelson::data::{sequence, pair, try_string, int};
+//! use tezos_operation::signer::Signer;
+//! use tezos_contract::{ContractFetcher, PartialTransaction, Result};
+//!
+//! async fn example<S: Signer>(signer: &S) -> Result<()> {
+//!     let rpc = TezosRpc::new("https://testnet-tezos.giganode.io".into());
+//!     let contract = rpc.contract_at("KT1HNqxFJxnmUcX8wF915wxxaAAU4ixDwWQ7".try_into()?, None).await?;
+//!     let chain_id: ChainId = rpc.get_chain_id().send().await?.try_into()?;
+//!     let owner: ImplicitAddress = signer.address().await?;
+//!     let payload = contract.permit_payload(
+//!         chain_id,
+//!         contract.permit_counter()?,
+//!         "transfer".into(),
+//!         vec![(
+//!             "",
+//!             sequence(vec![pair(vec![
+//!                 try_string(owner.value())?,
+//!                 sequence(vec![pair(vec![
+//!                     try_string("tz1PwXjsrgYBi9wpe3tFhazJpt7JMTVzBp5c")?,
+//!                     pair(vec![int(0i8), int(100i8)]),
+//!                 ])]),
+//!             ])]),
+//!         )],
+//!     )?;
+//!     let permit = payload.sign_using(signer).await?;
+//!     let partial_transaction: PartialTransaction = contract.permit(&[permit])?;
+//!     Ok(())
+//! }
+//! ```
+//!
+//! ## Operate a Generic Multisig
+//!
+//! The octez generic multisig contract can be operated with [multisig::GenericMultisig], which builds the payloads
+//! the key holders sign and the `main` entrypoint call executing the signed action.
 
 mod contract;
 mod error;
+pub mod multisig;
 mod utils;
 
 pub use contract::*;
@@ -221,12 +265,12 @@ mod test {
         micheline::{self, Micheline},
         michelson::{
             data::{pair, sequence, try_string},
-            ComparableTypePrimitive, DataPrimitive, Primitive, TypePrimitive,
+            ComparableTypePrimitive, DataPrimitive, Michelson, Primitive, TypePrimitive,
         },
         MichelinePacker,
     };
     use tezos_operation::operations::Parameters;
-    use tezos_rpc::client::TezosRpc;
+    use tezos_rpc::{client::TezosRpc, fake_node::FakeNode};
 
     use super::*;
 
@@ -281,6 +325,51 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_contract_big_map_value_with_fake_node() -> Result<()> {
+        let node = FakeNode::new();
+        let contract_address: ContractHash = "KT1J4CiyWPmtFPXAjpgBezM5hoVHXHNzWBHK".try_into()?;
+        node.add_contract(
+            &contract_address.value().try_into()?,
+            serde_json::from_str(include_str!("__TEST_DATA__/contract.json"))
+                .map_err(tezos_rpc::Error::from)?,
+            0u8.into(),
+        );
+
+        let rpc = TezosRpc::new_rpc_with_http_client(node.clone(), Default::default());
+        let contract = rpc.contract_at(contract_address, None).await?;
+        let ledger =
+            contract
+                .storage()
+                .big_maps()
+                .get_by_name("ledger")
+                .ok_or(Error::Internal {
+                    description: "Ledger not found".into(),
+                })?;
+
+        let key: Michelson = pair(vec![
+            try_string("tz1YY1LvD6TFH4z74pvxPQXBjAKHE5tB5Q8f")?,
+            0u8.into(),
+        ]);
+        node.add_big_map(
+            ledger.id,
+            serde_json::from_str(
+                r#"{ "prim": "pair", "args": [{ "prim": "address" }, { "prim": "nat" }] }"#,
+            )
+            .map_err(tezos_rpc::Error::from)?,
+        );
+        node.set_big_map_value(
+            ledger.id,
+            key.clone().into(),
+            Some(Nat::from_integer(42u8).into()),
+        )?;
+
+        let balance: Nat = ledger.get_value(&rpc, key, None).await?.try_into()?;
+
+        assert_eq!("42", balance.to_str());
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_contract_call() -> Result<()> {
         let server = MockServer::start();
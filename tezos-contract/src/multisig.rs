@@ -0,0 +1,489 @@
+pub mod lambda;
+
+use tezos_core::{
+    types::{
+        encoded::{ChainId, ContractHash, Encoded, PublicKey, SecretKey, Signature},
+        number::Nat,
+    },
+    Tezos,
+};
+use tezos_michelson::{
+    micheline::Micheline,
+    michelson::{
+        data::{
+            self,
+            instructions::{Instruction, Sequence},
+            Data,
+        },
+        types::{self, Type},
+        Michelson,
+    },
+};
+use tezos_operation::{signer::Signer, signing_payload::SigningPayload};
+
+use crate::{Contract, Error, PartialTransaction, Result};
+
+/// A client for the octez generic multisig contract.
+///
+/// The contract executes an action once it has been signed by at least `threshold` of its `keys`.
+/// An action is either a lambda producing the operations to run, or a rotation of the keys and threshold:
+///
+/// ```rust
+/// use tezos_contract::{ContractFetcher, Result, multisig::{lambda, GenericMultisig, MultisigAction}};
+/// use tezos_core::types::encoded::ChainId;
+/// use tezos_rpc::client::TezosRpc;
+///
+/// async fn example() -> Result<()> {
+///     let rpc = TezosRpc::new("https://testnet-tezos.giganode.io".into());
+///     let multisig = GenericMultisig::new(rpc.contract_at("KT1HNqxFJxnmUcX8wF915wxxaAAU4ixDwWQ7".try_into()?, None).await?);
+///     let chain_id: ChainId = rpc.get_chain_id().send().await?.try_into()?;
+///     let action = MultisigAction::Operation(lambda::transfer(&"tz1PwXjsrgYBi9wpe3tFhazJpt7JMTVzBp5c".try_into()?, 1000u16.into())?);
+///     let payload = multisig.payload(chain_id, action)?;
+///     // collect the signatures of the payload from the key holders...
+///     let partial_transaction = multisig.main(&payload, &[])?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct GenericMultisig {
+    contract: Contract,
+}
+
+impl GenericMultisig {
+    const MAIN_ENTRYPOINT: &'static str = "main";
+    const COUNTER_INDEX: usize = 0;
+    const THRESHOLD_INDEX: usize = 1;
+    const KEYS_INDEX: usize = 2;
+
+    pub fn new(contract: Contract) -> Self {
+        Self { contract }
+    }
+
+    pub fn contract(&self) -> &Contract {
+        &self.contract
+    }
+
+    /// Returns the counter the next action has to be signed with.
+    pub fn counter(&self) -> Result<Nat> {
+        self.nat_at_index(Self::COUNTER_INDEX)
+    }
+
+    /// Returns the number of signatures required to execute an action.
+    pub fn threshold(&self) -> Result<Nat> {
+        self.nat_at_index(Self::THRESHOLD_INDEX)
+    }
+
+    /// Returns the keys allowed to sign an action, in the order their signatures are expected in.
+    pub fn keys(&self) -> Result<Vec<PublicKey>> {
+        let keys = match self.contract.storage().get_at_index(Self::KEYS_INDEX) {
+            Some(Data::Sequence(keys)) => keys.into_values(),
+            _ => return Err(Error::InvalidMultisigStorage),
+        };
+
+        keys.into_iter()
+            .map(|key| match key {
+                Data::String(key) => Ok(key.to_str().try_into()?),
+                _ => Err(Error::InvalidMultisigStorage),
+            })
+            .collect()
+    }
+
+    /// Creates the payload to sign to execute the `action`, using the contract's current counter.
+    pub fn payload(&self, chain_id: ChainId, action: MultisigAction) -> Result<MultisigPayload> {
+        Ok(MultisigPayload::new(
+            chain_id,
+            self.contract.address().clone(),
+            self.counter()?,
+            action,
+        ))
+    }
+
+    /// Orders the collected `signatures` the way the contract expects them, i.e. following the order
+    /// of the contract's keys, with `None` for the keys which didn't sign.
+    pub fn signatures(
+        &self,
+        signatures: &[(PublicKey, Signature)],
+    ) -> Result<Vec<Option<Signature>>> {
+        let keys = self.keys()?;
+        if signatures.iter().any(|(key, _)| !keys.contains(key)) {
+            return Err(Error::UnknownMultisigKey);
+        }
+
+        Ok(keys
+            .iter()
+            .map(|key| {
+                signatures
+                    .iter()
+                    .find(|(signer, _)| signer == key)
+                    .map(|(_, signature)| signature.clone())
+            })
+            .collect())
+    }
+
+    /// Creates a call to the `main` entrypoint executing the action of the `payload` with the collected `signatures`.
+    pub fn main(
+        &self,
+        payload: &MultisigPayload,
+        signatures: &[(PublicKey, Signature)],
+    ) -> Result<PartialTransaction> {
+        let signatures = self.signatures(signatures)?;
+        let count = signatures.iter().filter(|value| value.is_some()).count();
+        if count < self.threshold()?.to_integer::<usize>()? {
+            return Err(Error::NotEnoughSignatures);
+        }
+
+        let signatures = signatures
+            .into_iter()
+            .map(|signature| match signature {
+                Some(signature) => Ok(data::some(data::try_string(signature.value())?)),
+                None => Ok(data::none()),
+            })
+            .collect::<Result<Vec<Data>>>()?;
+
+        self.contract.call(
+            Self::MAIN_ENTRYPOINT.into(),
+            vec![(
+                "",
+                data::pair(vec![
+                    data::pair(vec![
+                        payload.counter.clone().into(),
+                        payload.action.value()?,
+                    ]),
+                    data::sequence(signatures),
+                ]),
+            )],
+        )
+    }
+
+    fn nat_at_index(&self, index: usize) -> Result<Nat> {
+        let value: Micheline = self
+            .contract
+            .storage()
+            .get_at_index(index)
+            .ok_or(Error::InvalidMultisigStorage)?
+            .into();
+
+        value.try_into().map_err(|_| Error::InvalidMultisigStorage)
+    }
+}
+
+/// An action of the generic multisig contract.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MultisigAction {
+    /// A lambda of type `lambda unit (list operation)` returning the operations to run, see [lambda].
+    Operation(Sequence),
+    /// Replaces the keys and the threshold of the contract.
+    ChangeKeys {
+        threshold: Nat,
+        keys: Vec<PublicKey>,
+    },
+}
+
+impl MultisigAction {
+    /// Returns the type of the action: `or (lambda unit (list operation)) (pair nat (list key))`.
+    pub fn schema() -> Type {
+        types::or(
+            types::lambda(types::unit(), types::list(types::operation())),
+            types::pair(vec![types::nat(), types::list(types::key())]),
+        )
+    }
+
+    /// Returns the action as a Michelson value.
+    pub fn value(&self) -> Result<Data> {
+        match self {
+            Self::Operation(lambda) => Ok(data::left(Data::Instruction(Instruction::Sequence(
+                lambda.clone(),
+            )))),
+            Self::ChangeKeys { threshold, keys } => {
+                let keys = keys
+                    .iter()
+                    .map(|key| Ok(data::try_string(key.value())?))
+                    .collect::<Result<Vec<Data>>>()?;
+
+                Ok(data::right(data::pair(vec![
+                    threshold.clone().into(),
+                    data::sequence(keys),
+                ])))
+            }
+        }
+    }
+}
+
+/// The payload the key holders of a generic multisig contract sign to execute an action:
+/// `Pair (Pair chain_id contract) (Pair counter action)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultisigPayload {
+    pub chain_id: ChainId,
+    pub contract: ContractHash,
+    pub counter: Nat,
+    pub action: MultisigAction,
+}
+
+impl MultisigPayload {
+    pub fn new(
+        chain_id: ChainId,
+        contract: ContractHash,
+        counter: Nat,
+        action: MultisigAction,
+    ) -> Self {
+        Self {
+            chain_id,
+            contract,
+            counter,
+            action,
+        }
+    }
+
+    /// Returns the type of the payload: `pair (pair chain_id address) (pair nat action)`, see [MultisigAction::schema].
+    pub fn schema() -> Type {
+        types::pair(vec![
+            types::pair(vec![types::chain_id(), types::address()]),
+            types::pair(vec![types::nat(), MultisigAction::schema()]),
+        ])
+    }
+
+    /// Returns the payload as a Michelson value.
+    pub fn value(&self) -> Result<Data> {
+        Ok(data::pair(vec![
+            data::pair(vec![
+                data::try_string(self.chain_id.value())?,
+                data::try_string(self.contract.value())?,
+            ]),
+            data::pair(vec![self.counter.clone().into(), self.action.value()?]),
+        ]))
+    }
+
+    /// Packs the payload into a [SigningPayload], see [Michelson::pack].
+    pub fn to_signing_payload(&self) -> Result<SigningPayload> {
+        let value: Michelson = self.value()?.into();
+        let bytes = value.pack(Some(&Self::schema()))?;
+
+        Ok(SigningPayload::from_bytes(&bytes)?)
+    }
+
+    /// Signs the payload with the given secret key and using the crypto providers configured in the given [Tezos] instance.
+    pub fn sign_with(&self, key: &SecretKey, tezos: &Tezos) -> Result<Signature> {
+        Ok(self.to_signing_payload()?.sign_with(key, tezos)?)
+    }
+
+    /// Signs the payload with the given secret key.
+    pub fn sign(&self, key: &SecretKey) -> Result<Signature> {
+        self.sign_with(key, &Default::default())
+    }
+
+    /// Signs the payload with the given [Signer] and collects the signature together with the signer's public key.
+    pub async fn sign_using<S>(&self, signer: &S) -> Result<(PublicKey, Signature)>
+    where
+        S: Signer + ?Sized,
+    {
+        let signature = self.to_signing_payload()?.sign_using(signer).await?;
+        let public_key = signer.public_key().await?;
+
+        Ok((public_key, signature))
+    }
+
+    /// Verifies the `signature` of the payload with the given public key and using
+    /// the crypto providers configured in the given [Tezos] instance.
+    pub fn verify_with(
+        &self,
+        signature: &Signature,
+        key: &PublicKey,
+        tezos: &Tezos,
+    ) -> Result<bool> {
+        Ok(self
+            .to_signing_payload()?
+            .verify_with(signature, key, tezos)?)
+    }
+
+    /// Verifies the `signature` of the payload with the given public key.
+    pub fn verify(&self, signature: &Signature, key: &PublicKey) -> Result<bool> {
+        self.verify_with(signature, key, &Default::default())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use httpmock::{Method::POST, MockServer};
+    use tezos_rpc::client::TezosRpc;
+
+    use super::*;
+    use crate::ContractFetcher;
+
+    async fn multisig(server: &MockServer) -> Result<GenericMultisig> {
+        let contract_address: ContractHash = "KT1J4CiyWPmtFPXAjpgBezM5hoVHXHNzWBHK".try_into()?;
+        server.mock(|when, then| {
+            when.method(POST).path(format!(
+                "/chains/main/blocks/head/context/contracts/{}/script/normalized",
+                contract_address.value(),
+            ));
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(include_str!("__TEST_DATA__/generic_multisig.json"));
+        });
+        let rpc = TezosRpc::new(server.base_url());
+
+        Ok(GenericMultisig::new(
+            rpc.contract_at(contract_address, None).await?,
+        ))
+    }
+
+    fn keys() -> Result<Vec<PublicKey>> {
+        Ok(vec![
+            "edpkttZKC51wemRqL2QxwpMnEKxWnbd35pq47Y6xsCHp5M1f7LN8NP".try_into()?,
+            "sppk7aEFdrScsCDxdaQ7Ev1JxpWZESrEK6UsWRhr79JfGKkPYGTsudN".try_into()?,
+            "p2pk67L57Q7vcgLkMrKXctFRKs5JSLR6qjiw1riJaFyakWpTv9QSkRf".try_into()?,
+        ])
+    }
+
+    fn signature() -> Result<Signature> {
+        Ok("sigQDoDViNfDQ5Pwt4bSopViWWfHbh4uGM9C4689VzJS7narQbtnDeqesJhH7z9MqX6FJhgf22fF5GfJ61wtDs5bq1WiN9Vj".try_into()?)
+    }
+
+    #[tokio::test]
+    async fn test_storage() -> Result<()> {
+        let server = MockServer::start();
+        let multisig = multisig(&server).await?;
+
+        assert_eq!(multisig.counter()?, 3u8.into());
+        assert_eq!(multisig.threshold()?, 2u8.into());
+        assert_eq!(multisig.keys()?, keys()?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_payload() -> Result<()> {
+        let server = MockServer::start();
+        let multisig = multisig(&server).await?;
+        let action = MultisigAction::ChangeKeys {
+            threshold: 1u8.into(),
+            keys: keys()?[..1].to_vec(),
+        };
+
+        let payload = multisig.payload("NetXdQprcVkpaWU".try_into()?, action)?;
+        assert_eq!(payload.counter, 3u8.into());
+
+        let bytes = payload.to_signing_payload()?.to_bytes()?;
+        let expected = [
+            [0x05, 0x07, 0x07, 0x07, 0x07].as_slice(),
+            &[0x0a, 0x00, 0x00, 0x00, 0x04, 0x7a, 0x06, 0xa7, 0x70],
+            &[0x0a, 0x00, 0x00, 0x00, 0x16, 0x01],
+            &multisig.contract().address().to_bytes()?,
+            &[0x00, 0x07, 0x07, 0x00, 0x03],
+            &[0x05, 0x08, 0x07, 0x07, 0x00, 0x01],
+            &[0x02, 0x00, 0x00, 0x00, 0x26, 0x0a, 0x00, 0x00, 0x00, 0x21],
+            &keys()?[0].to_bytes()?,
+        ]
+        .concat();
+        assert_eq!(bytes, expected);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_signatures() -> Result<()> {
+        let server = MockServer::start();
+        let multisig = multisig(&server).await?;
+        let keys = keys()?;
+
+        let signatures = multisig.signatures(&[
+            (keys[2].clone(), signature()?),
+            (keys[0].clone(), signature()?),
+        ])?;
+        assert_eq!(
+            signatures,
+            vec![Some(signature()?), None, Some(signature()?)]
+        );
+
+        let unknown: PublicKey =
+            "edpkuBknW28nW72KG6RoHtYW7p12T6GKc7nAbwYX5m8Wd9sDVC9yav".try_into()?;
+        assert!(matches!(
+            multisig.signatures(&[(unknown, signature()?)]),
+            Err(Error::UnknownMultisigKey)
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_main() -> Result<()> {
+        let server = MockServer::start();
+        let multisig = multisig(&server).await?;
+        let keys = keys()?;
+        let lambda = lambda::set_delegate(None)?;
+        let payload = multisig.payload(
+            "NetXdQprcVkpaWU".try_into()?,
+            MultisigAction::Operation(lambda.clone()),
+        )?;
+
+        assert!(matches!(
+            multisig.main(&payload, &[(keys[1].clone(), signature()?)]),
+            Err(Error::NotEnoughSignatures)
+        ));
+
+        let partial_transaction = multisig.main(
+            &payload,
+            &[
+                (keys[1].clone(), signature()?),
+                (keys[0].clone(), signature()?),
+            ],
+        )?;
+        let parameters = partial_transaction.parameters.unwrap();
+        assert_eq!(parameters.entrypoint, "main".into());
+
+        let schema: Micheline = types::pair::<Type>(vec![
+            types::pair(vec![types::nat(), MultisigAction::schema()]),
+            types::list(types::option(types::signature())),
+        ])
+        .into();
+        let expected: Micheline = data::pair::<Data>(vec![
+            data::pair(vec![
+                3u8.into(),
+                data::left(Data::Instruction(Instruction::Sequence(lambda))),
+            ]),
+            data::sequence(vec![
+                data::some(data::try_string(signature()?.value())?),
+                data::some(data::try_string(signature()?.value())?),
+                data::none(),
+            ]),
+        ])
+        .into();
+        assert_eq!(
+            parameters.value.pack(Some(&schema))?,
+            expected.pack(Some(&schema))?
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "ed25519")]
+    #[tokio::test]
+    async fn test_sign() -> Result<()> {
+        use tezos_operation::signer::InMemorySigner;
+
+        let server = MockServer::start();
+        let multisig = multisig(&server).await?;
+        let secret_key: SecretKey = "edskRv7VyXGVZb8EsrR7D9XKUbbAQNQGtALP6QeB16ZCD7SmmJpzyeneJVg3Mq56YLbxRA1kSdAXiswwPiaVfR3NHGMCXCziuZ".try_into()?;
+        let public_key = keys()?[0].clone();
+
+        let payload = multisig.payload(
+            "NetXdQprcVkpaWU".try_into()?,
+            MultisigAction::Operation(lambda::transfer(
+                &"tz1PwXjsrgYBi9wpe3tFhazJpt7JMTVzBp5c".try_into()?,
+                1000u16.into(),
+            )?),
+        )?;
+        let signer = InMemorySigner::new(secret_key.clone(), public_key.clone());
+        let (signer_key, signature) = payload.sign_using(&signer).await?;
+
+        assert_eq!(signer_key, public_key);
+        assert_eq!(signature, payload.sign(&secret_key)?);
+        assert!(payload.verify(&signature, &public_key)?);
+        assert_eq!(
+            multisig.signatures(&[(signer_key, signature.clone())])?,
+            vec![Some(signature), None, None]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,231 @@
+//! Lambdas of type `lambda unit (list operation)` for common actions of a [GenericMultisig](super::GenericMultisig).
+
+use tezos_core::types::{
+    encoded::{Address, ContractHash, Encoded, ImplicitAddress},
+    mutez::Mutez,
+    number::Nat,
+};
+use tezos_michelson::michelson::{
+    data::{
+        self,
+        instructions::{self, Contract, Instruction, Sequence},
+        Data,
+    },
+    metadata::FieldMetadata,
+    types::{self, Type},
+};
+use tezos_operation::operations::Entrypoint;
+
+use crate::Result;
+
+/// Creates a lambda transferring `amount` to `destination`, which is either an implicit account or
+/// a contract accepting `unit` on its default entrypoint.
+pub fn transfer(destination: &Address, amount: Mutez) -> Result<Sequence> {
+    match destination {
+        Address::Implicit(destination) => Ok(sequence(vec![
+            instructions::drop(None),
+            instructions::nil(types::operation()),
+            instructions::push(types::key_hash(), data::try_string(destination.value())?),
+            instructions::implicit_account(),
+            instructions::push(types::mutez(), mutez(amount)),
+            instructions::unit(),
+            instructions::transfer_tokens(),
+            instructions::cons(),
+        ])),
+        Address::Originated(destination) => contract_call(
+            &destination.value().try_into()?,
+            &Entrypoint::Default,
+            types::unit(),
+            data::unit(),
+            amount,
+        ),
+    }
+}
+
+/// Creates a lambda calling the `entrypoint` of the `destination` contract with the given `parameter`
+/// of type `parameter_type`, and transferring `amount` along with the call.
+pub fn contract_call(
+    destination: &ContractHash,
+    entrypoint: &Entrypoint,
+    parameter_type: Type,
+    parameter: Data,
+    amount: Mutez,
+) -> Result<Sequence> {
+    let metadata = match entrypoint {
+        Entrypoint::Default => FieldMetadata::default(),
+        _ => FieldMetadata::default().with_field_name(entrypoint.to_str().into()),
+    };
+
+    Ok(sequence(vec![
+        instructions::drop(None),
+        instructions::nil(types::operation()),
+        instructions::push(types::address(), data::try_string(destination.value())?),
+        Contract::new(parameter_type.clone(), metadata).into(),
+        instructions::if_none(
+            vec![instructions::unit(), instructions::failwith()].into(),
+            vec![].into(),
+        ),
+        instructions::push(types::mutez(), mutez(amount)),
+        instructions::push(parameter_type, parameter),
+        instructions::transfer_tokens(),
+        instructions::cons(),
+    ]))
+}
+
+/// Creates a lambda transferring `value` tokens of the FA1.2 `token` contract from `from` to `to`.
+pub fn fa1_2_transfer(
+    token: &ContractHash,
+    from: &Address,
+    to: &Address,
+    value: Nat,
+) -> Result<Sequence> {
+    contract_call(
+        token,
+        &Entrypoint::from_str("transfer"),
+        types::pair(vec![
+            types::address(),
+            types::pair(vec![types::address(), types::nat()]),
+        ]),
+        data::pair(vec![
+            data::try_string(from.value())?,
+            data::pair(vec![data::try_string(to.value())?, value.into()]),
+        ]),
+        0u8.into(),
+    )
+}
+
+/// Creates a lambda transferring `amount` tokens of id `token_id` of the FA2 `token` contract from `from` to `to`.
+pub fn fa2_transfer(
+    token: &ContractHash,
+    from: &Address,
+    to: &Address,
+    token_id: Nat,
+    amount: Nat,
+) -> Result<Sequence> {
+    contract_call(
+        token,
+        &Entrypoint::from_str("transfer"),
+        types::list(types::pair(vec![
+            types::address(),
+            types::list(types::pair(vec![
+                types::address(),
+                types::pair(vec![types::nat(), types::nat()]),
+            ])),
+        ])),
+        data::sequence(vec![data::pair(vec![
+            data::try_string(from.value())?,
+            data::sequence(vec![data::pair(vec![
+                data::try_string(to.value())?,
+                data::pair(vec![token_id.into(), amount.into()]),
+            ])]),
+        ])]),
+        0u8.into(),
+    )
+}
+
+/// Creates a lambda setting the delegate of the multisig contract, or withdrawing it if `delegate` is `None`.
+pub fn set_delegate(delegate: Option<&ImplicitAddress>) -> Result<Sequence> {
+    let delegate = match delegate {
+        Some(delegate) => vec![
+            instructions::push(types::key_hash(), data::try_string(delegate.value())?),
+            instructions::some(),
+        ],
+        None => vec![instructions::none(types::key_hash())],
+    };
+
+    Ok(sequence(
+        [
+            vec![instructions::drop(None)],
+            vec![instructions::nil(types::operation())],
+            delegate,
+            vec![instructions::set_delegate(), instructions::cons()],
+        ]
+        .concat(),
+    ))
+}
+
+fn sequence(instructions: Vec<Instruction>) -> Sequence {
+    instructions.into()
+}
+
+fn mutez(amount: Mutez) -> Data {
+    let amount: Nat = (&amount).into();
+
+    amount.into()
+}
+
+#[cfg(test)]
+mod test {
+    use tezos_michelson::micheline::Micheline;
+
+    use super::*;
+
+    fn packed(lambda: Sequence) -> Result<String> {
+        let schema: Micheline =
+            types::lambda::<Type>(types::unit(), types::list(types::operation())).into();
+        let value: Micheline = lambda.into();
+
+        Ok(hex::encode(value.pack(Some(&schema))?))
+    }
+
+    #[test]
+    fn test_transfer() -> Result<()> {
+        let implicit = transfer(
+            &"tz1PwXjsrgYBi9wpe3tFhazJpt7JMTVzBp5c".try_into()?,
+            1000u16.into(),
+        )?;
+        assert_eq!(
+            packed(implicit)?,
+            "0502000000330320053d036d0743035d0a00000015002f2fd2798d6b000eabbbbc234648465aa2d762d9031e0743036a00a80f034f034d031b"
+        );
+
+        let originated = transfer(
+            &"KT1J4CiyWPmtFPXAjpgBezM5hoVHXHNzWBHK".try_into()?,
+            1000u16.into(),
+        )?;
+        assert_eq!(
+            packed(originated)?,
+            "05020000004a0320053d036d0743036e0a000000160167e95d5134a75dc563f51fcdfed7e1de44424af1000555036c072f0200000004034f032702000000000743036a00a80f0743036c030b034d031b"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_token_transfers() -> Result<()> {
+        let token: ContractHash = "KT1J4CiyWPmtFPXAjpgBezM5hoVHXHNzWBHK".try_into()?;
+        let from: Address = "tz1RFxSHjxxyaaXyAvJG3uRQeJUCCm4jk2LX".try_into()?;
+        let to: Address = "tz1PwXjsrgYBi9wpe3tFhazJpt7JMTVzBp5c".try_into()?;
+
+        let fa1_2 = fa1_2_transfer(&token, &from, &to, 100u8.into())?;
+        assert_eq!(
+            packed(fa1_2)?,
+            "0502000000a10320053d036d0743036e0a000000160167e95d5134a75dc563f51fcdfed7e1de44424af10006550765036e0765036e036200000009257472616e73666572072f0200000004034f032702000000000743036a000007430765036e0765036e036207070a0000001600003da40d13544066187889f864f9edc86733847a1c07070a0000001600002f2fd2798d6b000eabbbbc234648465aa2d762d900a401034d031b"
+        );
+
+        let fa2 = fa2_transfer(&token, &from, &to, 0u8.into(), 100u8.into())?;
+        assert_eq!(
+            packed(fa2)?,
+            "0502000000bf0320053d036d0743036e0a000000160167e95d5134a75dc563f51fcdfed7e1de44424af1000655055f0765036e055f0765036e07650362036200000009257472616e73666572072f0200000004034f032702000000000743036a00000743055f0765036e055f0765036e076503620362020000004607070a0000001600003da40d13544066187889f864f9edc86733847a1c020000002407070a0000001600002f2fd2798d6b000eabbbbc234648465aa2d762d90707000000a401034d031b"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_delegate() -> Result<()> {
+        let set = set_delegate(Some(&"tz1PwXjsrgYBi9wpe3tFhazJpt7JMTVzBp5c".try_into()?))?;
+        assert_eq!(
+            packed(set)?,
+            "05020000002a0320053d036d0743035d0a00000015002f2fd2798d6b000eabbbbc234648465aa2d762d90346034e031b"
+        );
+
+        let withdraw = set_delegate(None)?;
+        assert_eq!(
+            packed(withdraw)?,
+            "05020000000e0320053d036d053e035d034e031b"
+        );
+
+        Ok(())
+    }
+}
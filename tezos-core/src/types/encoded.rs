@@ -3,12 +3,14 @@
 mod address;
 mod key;
 mod macros;
+mod merkle;
 mod meta_encoded;
 mod signature;
 
 pub use self::{
     address::{Address, ContractAddress, ImplicitAddress},
     key::{Key, PublicKey, SecretKey},
+    merkle::{MerklePath, MerkleStep, OperationInclusionProof},
     meta_encoded::{MetaEncoded, TraitMetaEncoded},
     signature::Signature,
 };
@@ -68,10 +68,10 @@ impl Encoded for Key {
         if SecretKey::is_valid_bytes(bytes) {
             return Ok(Self::Secret(SecretKey::from_bytes(bytes)?));
         }
-        if PublicKey::is_valid_bytes(bytes) {
-            return Ok(Self::Public(PublicKey::from_bytes(bytes)?));
-        }
-        Err(Error::InvalidBytes)
+        // public keys are prefixed with the tag of their curve, see [PublicKeyBytesCoder]
+        PublicKey::from_bytes(bytes)
+            .map(Self::Public)
+            .map_err(|_| Error::InvalidBytes)
     }
 }
 
@@ -495,6 +495,21 @@ mod test {
         Err(Error::InvalidConversion)
     }
 
+    #[test]
+    fn test_public_key_from_bytes() -> Result<()> {
+        let keys = [
+            "edpkttZKC51wemRqL2QxwpMnEKxWnbd35pq47Y6xsCHp5M1f7LN8NP",
+            "sppk7aEFdrScsCDxdaQ7Ev1JxpWZESrEK6UsWRhr79JfGKkPYGTsudN",
+            "p2pk67L57Q7vcgLkMrKXctFRKs5JSLR6qjiw1riJaFyakWpTv9QSkRf",
+        ];
+        for value in keys {
+            let key: PublicKey = value.try_into()?;
+            let decoded = Key::from_bytes(&key.to_bytes()?)?;
+            assert_eq!(decoded.value(), value);
+        }
+        Ok(())
+    }
+
     #[test]
     fn test_secp_256_k1_public_key() -> Result<()> {
         let key: PublicKey =
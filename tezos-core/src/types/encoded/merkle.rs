@@ -0,0 +1,281 @@
+use crate::{
+    internal::crypto::blake2b,
+    types::encoded::{Encoded, OperationHash, OperationListHash, OperationListListHash},
+    Error, Result,
+};
+
+const HASH_SIZE: usize = 32;
+
+/// A single step of a [MerklePath], from a leaf towards the root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MerkleStep {
+    /// The current node is the left child, the value is the hash of its right sibling.
+    Left(Vec<u8>),
+    /// The current node is the right child, the value is the hash of its left sibling.
+    Right(Vec<u8>),
+}
+
+/// An inclusion path in a Tezos Merkle tree, ordered from the leaf to the root.
+///
+/// The tree is the one used by [OperationListHash] and [OperationListListHash]: leaves are the blake2b hashes of the elements,
+/// the list of leaves is padded to a power of two by repeating the last leaf
+/// and every node is the blake2b hash of the concatenation of its children.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct MerklePath {
+    pub steps: Vec<MerkleStep>,
+}
+
+impl MerklePath {
+    /// Computes the root the path leads to when starting from `element`, together with the position of the element in the list.
+    pub fn check(&self, element: &[u8]) -> Result<(Vec<u8>, usize)> {
+        self.steps.iter().enumerate().try_fold(
+            (hash_element(element)?, 0usize),
+            |(hash, position), (depth, step)| match step {
+                MerkleStep::Left(right) => Ok((hash_node(&hash, right)?, position)),
+                MerkleStep::Right(left) => Ok((hash_node(left, &hash)?, position | (1 << depth))),
+            },
+        )
+    }
+}
+
+fn hash_element(element: &[u8]) -> Result<Vec<u8>> {
+    blake2b(element, HASH_SIZE)
+}
+
+fn hash_node(left: &[u8], right: &[u8]) -> Result<Vec<u8>> {
+    blake2b(&[left, right].concat(), HASH_SIZE)
+}
+
+/// Hashes the `elements`, padding the result to a power of two by repeating the last leaf.
+fn leaves(elements: &[Vec<u8>]) -> Result<Vec<Vec<u8>>> {
+    let mut leaves = elements
+        .iter()
+        .map(|element| hash_element(element))
+        .collect::<Result<Vec<_>>>()?;
+    if let Some(last) = leaves.last().cloned() {
+        leaves.resize(leaves.len().next_power_of_two(), last);
+    }
+
+    Ok(leaves)
+}
+
+fn parents(nodes: &[Vec<u8>]) -> Result<Vec<Vec<u8>>> {
+    nodes
+        .chunks(2)
+        .map(|pair| hash_node(&pair[0], &pair[1]))
+        .collect()
+}
+
+fn compute_root(elements: &[Vec<u8>]) -> Result<Vec<u8>> {
+    if elements.is_empty() {
+        return hash_element(&[]);
+    }
+    let mut nodes = leaves(elements)?;
+    while nodes.len() > 1 {
+        nodes = parents(&nodes)?;
+    }
+
+    Ok(nodes.remove(0))
+}
+
+fn compute_path(elements: &[Vec<u8>], index: usize) -> Result<MerklePath> {
+    if index >= elements.len() {
+        return Err(Error::InvalidConversion);
+    }
+    let mut nodes = leaves(elements)?;
+    let mut position = index;
+    let mut steps = vec![];
+    while nodes.len() > 1 {
+        let step = if position & 1 == 0 {
+            MerkleStep::Left(nodes[position + 1].clone())
+        } else {
+            MerkleStep::Right(nodes[position - 1].clone())
+        };
+        steps.push(step);
+        nodes = parents(&nodes)?;
+        position /= 2;
+    }
+
+    Ok(MerklePath { steps })
+}
+
+fn to_bytes<E: Encoded>(values: &[E]) -> Result<Vec<Vec<u8>>> {
+    values.iter().map(|value| value.to_bytes()).collect()
+}
+
+impl OperationListHash {
+    /// Computes the hash of the list of `operations`.
+    pub fn compute(operations: &[OperationHash]) -> Result<Self> {
+        Self::from_bytes(&compute_root(&to_bytes(operations)?)?)
+    }
+
+    /// Computes the inclusion path of the operation at `index` in the list of `operations`.
+    pub fn compute_path(operations: &[OperationHash], index: usize) -> Result<MerklePath> {
+        compute_path(&to_bytes(operations)?, index)
+    }
+
+    /// Computes the list hash the `path` leads to when starting from `operation`, together with the operation's position.
+    pub fn check_path(path: &MerklePath, operation: &OperationHash) -> Result<(Self, usize)> {
+        let (root, position) = path.check(&operation.to_bytes()?)?;
+
+        Ok((Self::from_bytes(&root)?, position))
+    }
+}
+
+impl OperationListListHash {
+    /// Computes the hash of the list of operation lists.
+    pub fn compute(lists: &[OperationListHash]) -> Result<Self> {
+        Self::from_bytes(&compute_root(&to_bytes(lists)?)?)
+    }
+
+    /// Computes the hash of the operations of a block grouped by validation pass, i.e. the `operations_hash` of a block header.
+    pub fn from_validation_passes<T: AsRef<[OperationHash]>>(passes: &[T]) -> Result<Self> {
+        let lists = passes
+            .iter()
+            .map(|operations| OperationListHash::compute(operations.as_ref()))
+            .collect::<Result<Vec<_>>>()?;
+
+        Self::compute(&lists)
+    }
+
+    /// Computes the inclusion path of the list at `index` in the list of `lists`.
+    pub fn compute_path(lists: &[OperationListHash], index: usize) -> Result<MerklePath> {
+        compute_path(&to_bytes(lists)?, index)
+    }
+
+    /// Computes the hash the `path` leads to when starting from `list`, together with the list's position.
+    pub fn check_path(path: &MerklePath, list: &OperationListHash) -> Result<(Self, usize)> {
+        let (root, position) = path.check(&list.to_bytes()?)?;
+
+        Ok((Self::from_bytes(&root)?, position))
+    }
+}
+
+/// A proof that an operation is part of a block, checked against the block header's `operations_hash`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperationInclusionProof {
+    /// The path of the operation in the list of its validation pass.
+    pub operation_path: MerklePath,
+    /// The path of the validation pass list in the list of all the passes.
+    pub validation_pass_path: MerklePath,
+}
+
+impl OperationInclusionProof {
+    /// Creates the proof for the operation at `index` in the validation pass `validation_pass` of the block operations `passes`.
+    pub fn new<T: AsRef<[OperationHash]>>(
+        passes: &[T],
+        validation_pass: usize,
+        index: usize,
+    ) -> Result<Self> {
+        let operations = passes
+            .get(validation_pass)
+            .ok_or(Error::InvalidConversion)?
+            .as_ref();
+        let lists = passes
+            .iter()
+            .map(|operations| OperationListHash::compute(operations.as_ref()))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            operation_path: OperationListHash::compute_path(operations, index)?,
+            validation_pass_path: OperationListListHash::compute_path(&lists, validation_pass)?,
+        })
+    }
+
+    /// Computes the block `operations_hash` the proof leads to when starting from `operation`,
+    /// together with the operation's validation pass and position in that pass.
+    pub fn check(
+        &self,
+        operation: &OperationHash,
+    ) -> Result<(OperationListListHash, usize, usize)> {
+        let (list, index) = OperationListHash::check_path(&self.operation_path, operation)?;
+        let (operations_hash, validation_pass) =
+            OperationListListHash::check_path(&self.validation_pass_path, &list)?;
+
+        Ok((operations_hash, validation_pass, index))
+    }
+
+    /// Verifies that `operation` is included in the block whose header has the `operations_hash`.
+    pub fn verify(
+        &self,
+        operation: &OperationHash,
+        operations_hash: &OperationListListHash,
+    ) -> Result<bool> {
+        let (computed, _, _) = self.check(operation)?;
+
+        Ok(&computed == operations_hash)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn operations(count: usize) -> Vec<OperationHash> {
+        (0..count)
+            .map(|index| {
+                OperationHash::from_bytes(&blake2b(&[index as u8], HASH_SIZE).unwrap()).unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_compute_empty_and_single() -> Result<()> {
+        let empty = OperationListHash::compute(&[])?;
+        assert_eq!(empty.to_bytes()?, blake2b(&[], HASH_SIZE)?);
+
+        let operation = operations(1).remove(0);
+        let single = OperationListHash::compute(std::slice::from_ref(&operation))?;
+        assert_eq!(
+            single.to_bytes()?,
+            blake2b(&operation.to_bytes()?, HASH_SIZE)?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_compute_pads_with_last_element() -> Result<()> {
+        let operations = operations(3);
+        let padded = [operations.clone(), vec![operations[2].clone()]].concat();
+
+        assert_eq!(
+            OperationListHash::compute(&operations)?,
+            OperationListHash::compute(&padded)?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_paths() -> Result<()> {
+        for count in 1..10 {
+            let operations = operations(count);
+            let expected = OperationListHash::compute(&operations)?;
+            for (index, operation) in operations.iter().enumerate() {
+                let path = OperationListHash::compute_path(&operations, index)?;
+                assert_eq!(
+                    OperationListHash::check_path(&path, operation)?,
+                    (expected.clone(), index)
+                );
+            }
+        }
+        assert!(OperationListHash::compute_path(&operations(3), 3).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_inclusion_proof() -> Result<()> {
+        let passes = vec![operations(5), vec![], operations(1), operations(3)];
+        let operations_hash = OperationListListHash::from_validation_passes(&passes)?;
+
+        let proof = OperationInclusionProof::new(&passes, 3, 2)?;
+        assert_eq!(proof.check(&passes[3][2])?, (operations_hash.clone(), 3, 2));
+        assert!(proof.verify(&passes[3][2], &operations_hash)?);
+        assert!(!proof.verify(&passes[3][1], &operations_hash)?);
+        assert!(OperationInclusionProof::new(&passes, 1, 0).is_err());
+
+        Ok(())
+    }
+}
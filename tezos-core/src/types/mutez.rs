@@ -21,6 +21,8 @@ use crate::{Error, Result};
 
 use super::number::Nat;
 
+const MUTEZ_PER_TEZ: i64 = 1_000_000;
+
 lazy_static! {
     static ref REGEX: Regex = Regex::new(r"^[0-9]+$").unwrap();
 }
@@ -103,6 +105,23 @@ impl Mutez {
     pub fn from_consumable_bytes<CL: ConsumableList<u8>>(bytes: &mut CL) -> Result<Self> {
         MutezBytesCoder::decode_consuming(bytes)
     }
+
+    /// Adds `other` to the value, returning `None` on overflow.
+    pub fn checked_add(self, other: Mutez) -> Option<Mutez> {
+        self.0.checked_add(other.0).map(Self)
+    }
+
+    /// Returns the value expressed in tez, without trailing zeros (e.g. `"1.5"` for 1500000 mutez).
+    pub fn to_tez_string(&self) -> String {
+        let tez = self.0 / MUTEZ_PER_TEZ;
+        let mutez = self.0 % MUTEZ_PER_TEZ;
+        if mutez == 0 {
+            return tez.to_string();
+        }
+        let fraction = format!("{:06}", mutez);
+
+        format!("{}.{}", tez, fraction.trim_end_matches('0'))
+    }
 }
 
 #[cfg(feature = "serde")]
@@ -328,6 +347,16 @@ impl Default for Mutez {
 mod test {
     use super::*;
 
+    #[test]
+    fn test_to_tez_string() -> Result<()> {
+        assert_eq!(Mutez::from(0u8).to_tez_string(), "0");
+        assert_eq!(Mutez::from(1u8).to_tez_string(), "0.000001");
+        assert_eq!(Mutez::from(1_500_000u32).to_tez_string(), "1.5");
+        assert_eq!(Mutez::from(42_000_000u32).to_tez_string(), "42");
+
+        Ok(())
+    }
+
     #[test]
     fn test_add_1() -> Result<()> {
         let v1: Mutez = 1u8.into();
@@ -1,12 +1,41 @@
 use chrono::NaiveDateTime;
 use num_derive::{FromPrimitive, ToPrimitive};
-use tezos_core::types::{
-    encoded::{
-        BlockHash, BlockPayloadHash, ContextHash, NonceHash, OperationListListHash, Signature,
+use tezos_core::{
+    internal::{
+        coder::{ConsumingDecoder, Encoder},
+        consumable_list::ConsumableBytes,
+        crypto::blake2b,
     },
-    hex_string::HexString,
+    types::{
+        encoded::{
+            BlockHash, BlockPayloadHash, ChainId, ContextHash, Encoded, NonceHash,
+            OperationListListHash, PublicKey, SecretKey, Signature,
+        },
+        hex_string::HexString,
+    },
+    Tezos,
+};
+
+use crate::{
+    internal::{
+        coder::operation_content_bytes_coder::OperationContentBytesCoder, signer::OperationSigner,
+    },
+    signing_payload::watermark,
+    Result,
 };
 
+/// A Tezos block header, as defined since the Tenderbake protocols (Ithaca and later).
+///
+/// The header can be forged and unforged, hashed into its [BlockHash] and its baker signature can be verified:
+///
+/// ```rust
+/// use tezos_core::types::encoded::Encoded;
+/// use tezos_operation::block_header::BlockHeader;
+///
+/// let header = BlockHeader::from_forged_bytes(hex::decode("0026370d0d9444f40d9c1824d30918fa20dcbc28261a8973d9a544f33f5cc56a13830189af0000000062c28ab804d7bfc960df8ce93f0b9356181c5651bf90ebcae5b2a2776b66aeeda0000f4229000000210000000102000000040026370d0000000000000004ffffffff000000040000000083e9c04c6f3c610e6b5c65b33805c24a6c1902b58bec02a4cc482ffca828b77ac4451b4e45ee2eae5d15652c8826ee011f22915ae1387dd605766c5ad1b5cf8c00000000cb9f439e02840100000214a92861740b16b11c8bb6791abdae138bf18f18a85915896a7d5da0b59fc0c3a16494f7832b05164f63f1d5797d3103256e5c2e1f0ddf3fca57e71ab8c4a56a").unwrap()).expect("valid block header bytes");
+/// assert_eq!(header.level, 2504461);
+/// assert_eq!(header.hash().expect("valid hash").value(), "BKvBmAJVpJ8drHMTzDZmFKEYc45xzeyHo1MUNtcomm6FMZkpXDW");
+/// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct BlockHeader {
     pub level: i32,
@@ -22,9 +51,84 @@ pub struct BlockHeader {
     pub proof_of_work_nonce: HexString,
     pub seed_nonce_hash: Option<NonceHash>,
     pub liquidity_baking_toggle_vote: LiquidityBakingToggleVote,
+    /// The adaptive issuance vote, introduced in the Oxford protocol.
+    ///
+    /// It's packed together with `liquidity_baking_toggle_vote` into a single byte,
+    /// the headers produced before Oxford forge it the same way as [AdaptiveIssuanceVote::On].
+    pub adaptive_issuance_vote: AdaptiveIssuanceVote,
     pub signature: Signature,
 }
 
+impl BlockHeader {
+    const HASH_SIZE: usize = 32;
+
+    /// Forges the header, including its signature.
+    pub fn to_forged_bytes(&self) -> Result<Vec<u8>> {
+        OperationContentBytesCoder::encode(self)
+    }
+
+    /// Forges the header without its signature, i.e. the bytes that are signed by the baker.
+    pub fn to_unsigned_forged_bytes(&self) -> Result<Vec<u8>> {
+        let bytes = self.to_forged_bytes()?;
+        let signature_length = self.signature.to_bytes()?.len();
+
+        Ok(bytes[..bytes.len() - signature_length].to_vec())
+    }
+
+    /// Unforges a header, including its signature.
+    pub fn from_forged_bytes<T: AsRef<[u8]>>(bytes: T) -> Result<Self> {
+        OperationContentBytesCoder::decode_consuming(&mut ConsumableBytes::new(bytes.as_ref()))
+    }
+
+    /// Computes the hash of the header.
+    pub fn hash(&self) -> Result<BlockHash> {
+        let bytes = blake2b(&self.to_forged_bytes()?, Self::HASH_SIZE)?;
+
+        Ok(BlockHash::from_bytes(&bytes)?)
+    }
+
+    /// Signs the header as a baker of the chain identified by `chain_id`.
+    pub fn sign(&self, chain_id: &ChainId, key: &SecretKey) -> Result<Signature> {
+        self.sign_with(chain_id, key, &Default::default())
+    }
+
+    /// Signs the header as a baker of the chain identified by `chain_id`, using the crypto primitives configured in `tezos`.
+    pub fn sign_with(
+        &self,
+        chain_id: &ChainId,
+        key: &SecretKey,
+        tezos: &Tezos,
+    ) -> Result<Signature> {
+        let signer = OperationSigner::new(tezos.get_crypto());
+        signer.sign_watermarked(
+            &Self::watermark(chain_id)?,
+            &self.to_unsigned_forged_bytes()?,
+            key,
+        )
+    }
+
+    /// Verifies the baker signature of the header produced on the chain identified by `chain_id`.
+    pub fn verify(&self, chain_id: &ChainId, key: &PublicKey) -> Result<bool> {
+        self.verify_with(chain_id, key, &Default::default())
+    }
+
+    /// Verifies the baker signature of the header produced on the chain identified by `chain_id`,
+    /// using the crypto primitives configured in `tezos`.
+    pub fn verify_with(&self, chain_id: &ChainId, key: &PublicKey, tezos: &Tezos) -> Result<bool> {
+        let signer = OperationSigner::new(tezos.get_crypto());
+        signer.verify_watermarked(
+            &Self::watermark(chain_id)?,
+            &self.to_unsigned_forged_bytes()?,
+            &self.signature,
+            key,
+        )
+    }
+
+    fn watermark(chain_id: &ChainId) -> Result<Vec<u8>> {
+        Ok([[watermark::BLOCK].as_slice(), &chain_id.to_bytes()?].concat())
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, FromPrimitive, ToPrimitive)]
 #[repr(u8)]
 pub enum LiquidityBakingToggleVote {
@@ -32,3 +136,108 @@ pub enum LiquidityBakingToggleVote {
     Off = 1,
     Pass = 2,
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromPrimitive, ToPrimitive)]
+#[repr(u8)]
+pub enum AdaptiveIssuanceVote {
+    On = 0,
+    Off = 1,
+    Pass = 2,
+}
+
+impl Default for AdaptiveIssuanceVote {
+    fn default() -> Self {
+        Self::On
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const JAKARTA_HEADER: &str = "0026370d0d9444f40d9c1824d30918fa20dcbc28261a8973d9a544f33f5cc56a13830189af0000000062c28ab804d7bfc960df8ce93f0b9356181c5651bf90ebcae5b2a2776b66aeeda0000f4229000000210000000102000000040026370d0000000000000004ffffffff000000040000000083e9c04c6f3c610e6b5c65b33805c24a6c1902b58bec02a4cc482ffca828b77ac4451b4e45ee2eae5d15652c8826ee011f22915ae1387dd605766c5ad1b5cf8c00000000cb9f439e02840100000214a92861740b16b11c8bb6791abdae138bf18f18a85915896a7d5da0b59fc0c3a16494f7832b05164f63f1d5797d3103256e5c2e1f0ddf3fca57e71ab8c4a56a";
+
+    #[test]
+    fn test_hash() -> Result<()> {
+        let header = BlockHeader::from_forged_bytes(hex::decode(JAKARTA_HEADER).unwrap())?;
+        let expected: BlockHash = "BKvBmAJVpJ8drHMTzDZmFKEYc45xzeyHo1MUNtcomm6FMZkpXDW"
+            .try_into()
+            .unwrap();
+
+        assert_eq!(header.level, 2504461);
+        assert_eq!(
+            header.timestamp,
+            NaiveDateTime::parse_from_str("2022-07-04T06:37:44", "%Y-%m-%dT%H:%M:%S").unwrap()
+        );
+        assert_eq!(
+            header.liquidity_baking_toggle_vote,
+            LiquidityBakingToggleVote::Pass
+        );
+        assert_eq!(header.adaptive_issuance_vote, AdaptiveIssuanceVote::On);
+        assert_eq!(header.hash()?, expected);
+        assert_eq!(hex::encode(header.to_forged_bytes()?), JAKARTA_HEADER);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_per_block_votes() -> Result<()> {
+        let mut header = BlockHeader::from_forged_bytes(hex::decode(JAKARTA_HEADER).unwrap())?;
+        header.liquidity_baking_toggle_vote = LiquidityBakingToggleVote::Off;
+        header.adaptive_issuance_vote = AdaptiveIssuanceVote::Pass;
+
+        let bytes = header.to_unsigned_forged_bytes()?;
+        assert_eq!(bytes.last(), Some(&0b1001));
+        assert_eq!(
+            BlockHeader::from_forged_bytes(header.to_forged_bytes()?)?,
+            header
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_per_block_votes_round_trip() -> Result<()> {
+        let mut header = BlockHeader::from_forged_bytes(hex::decode(JAKARTA_HEADER).unwrap())?;
+        for liquidity_baking_toggle_vote in [
+            LiquidityBakingToggleVote::On,
+            LiquidityBakingToggleVote::Off,
+            LiquidityBakingToggleVote::Pass,
+        ] {
+            for adaptive_issuance_vote in [
+                AdaptiveIssuanceVote::On,
+                AdaptiveIssuanceVote::Off,
+                AdaptiveIssuanceVote::Pass,
+            ] {
+                header.liquidity_baking_toggle_vote = liquidity_baking_toggle_vote;
+                header.adaptive_issuance_vote = adaptive_issuance_vote;
+
+                assert_eq!(
+                    BlockHeader::from_forged_bytes(header.to_forged_bytes()?)?,
+                    header
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "ed25519")]
+    #[test]
+    fn test_sign_and_verify() -> Result<()> {
+        let mut header = BlockHeader::from_forged_bytes(hex::decode(JAKARTA_HEADER).unwrap())?;
+        let chain_id: ChainId = "NetXdQprcVkpaWU".try_into().unwrap();
+        let secret_key: SecretKey = "edskRv7VyXGVZb8EsrR7D9XKUbbAQNQGtALP6QeB16ZCD7SmmJpzyeneJVg3Mq56YLbxRA1kSdAXiswwPiaVfR3NHGMCXCziuZ".try_into().unwrap();
+        let public_key: PublicKey = "edpkttZKC51wemRqL2QxwpMnEKxWnbd35pq47Y6xsCHp5M1f7LN8NP"
+            .try_into()
+            .unwrap();
+
+        header.signature = header.sign(&chain_id, &secret_key)?;
+        assert!(header.verify(&chain_id, &public_key)?);
+
+        let other_chain_id: ChainId = "NetXnHfVqm9iesp".try_into().unwrap();
+        assert!(!header.verify(&other_chain_id, &public_key)?);
+
+        Ok(())
+    }
+}
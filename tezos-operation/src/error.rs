@@ -2,6 +2,8 @@ use std::string::FromUtf8Error;
 
 use derive_more::{Display, Error as DError, From};
 
+use crate::validator::ValidationErrors;
+
 #[derive(DError, Display, Debug, From)]
 pub enum Error {
     Core { source: tezos_core::Error },
@@ -9,6 +11,10 @@ pub enum Error {
     InvalidOperationContentTag,
     InvalidBytes,
     InvalidStringConversion { source: FromUtf8Error },
+    Validation(#[error(not(source))] ValidationErrors),
+    Signer { description: String },
+    InvalidSignInMessage,
+    AmountOverflow,
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
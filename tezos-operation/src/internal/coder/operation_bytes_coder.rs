@@ -3,11 +3,14 @@ use tezos_core::{
         coder::{ConsumingDecoder, Decoder, Encoder},
         consumable_list::{ConsumableBytes, ConsumableList},
     },
-    types::encoded::{BlockHash, Encoded},
+    types::encoded::{
+        BlockHash, Ed25519Signature, Encoded, GenericSignature, ImplicitAddress, P256Signature,
+        Secp256K1Signature, Signature,
+    },
 };
 
 use crate::{
-    operations::{Operation, OperationContent, UnsignedOperation},
+    operations::{Operation, OperationContent, SignedOperation, UnsignedOperation},
     Error, Result,
 };
 
@@ -15,6 +18,21 @@ use super::operation_content_bytes_coder::OperationContentBytesCoder;
 
 pub struct OperationBytesCoder;
 
+impl OperationBytesCoder {
+    const SIGNATURE_BYTES_LENGTH: usize = 64;
+
+    fn decode_signature(value: &[u8], operation: &UnsignedOperation) -> Result<Signature> {
+        let signature: Signature = match operation.source() {
+            Some(ImplicitAddress::TZ1(_)) => Ed25519Signature::from_bytes(value)?.into(),
+            Some(ImplicitAddress::TZ2(_)) => Secp256K1Signature::from_bytes(value)?.into(),
+            Some(ImplicitAddress::TZ3(_)) => P256Signature::from_bytes(value)?.into(),
+            None => GenericSignature::from_bytes(value)?.into(),
+        };
+
+        Ok(signature)
+    }
+}
+
 impl<O: Operation> Encoder<O, Vec<u8>, Error> for OperationBytesCoder {
     fn encode(value: &O) -> std::result::Result<Vec<u8>, Error> {
         let branch_bytes = value.branch().to_bytes()?;
@@ -45,3 +63,17 @@ impl ConsumingDecoder<UnsignedOperation, u8, Error> for OperationBytesCoder {
         Ok(UnsignedOperation::new(branch, contents))
     }
 }
+
+impl Decoder<SignedOperation, [u8], Error> for OperationBytesCoder {
+    fn decode(value: &[u8]) -> Result<SignedOperation> {
+        if value.len() < Self::SIGNATURE_BYTES_LENGTH {
+            return Err(Error::InvalidBytes);
+        }
+        let (operation_bytes, signature_bytes) =
+            value.split_at(value.len() - Self::SIGNATURE_BYTES_LENGTH);
+        let operation: UnsignedOperation = Self::decode(operation_bytes)?;
+        let signature = Self::decode_signature(signature_bytes, &operation)?;
+
+        Ok(SignedOperation::from(operation, signature))
+    }
+}
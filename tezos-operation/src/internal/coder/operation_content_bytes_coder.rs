@@ -20,7 +20,7 @@ use tezos_core::{
 use tezos_michelson::micheline::Micheline;
 
 use crate::{
-    block_header::{BlockHeader, LiquidityBakingToggleVote},
+    block_header::{AdaptiveIssuanceVote, BlockHeader, LiquidityBakingToggleVote},
     operations::{
         ActivateAccount, Ballot, BallotType, Delegation, DoubleBakingEvidence,
         DoubleEndorsementEvidence, DoublePreendorsementEvidence, Endorsement, Entrypoint,
@@ -169,7 +169,7 @@ impl Encoder<BlockHeader, Vec<u8>, Error> for OperationContentBytesCoder {
         let level_bytes = utils::encode_i32(value.level);
         let proto_bytes = [value.proto];
         let predecessor_bytes = value.predecessor.to_bytes()?;
-        let timestamp_bytes = utils::encode_i64(value.timestamp.timestamp_millis());
+        let timestamp_bytes = utils::encode_i64(value.timestamp.timestamp());
         let validation_pass_bytes = [value.validation_pass];
         let operation_hash_bytes = value.operations_hash.to_bytes()?;
         let fitness_bytes = utils::encode_bytes(&value.fitness.iter().fold(
@@ -189,8 +189,8 @@ impl Encoder<BlockHeader, Vec<u8>, Error> for OperationContentBytesCoder {
             vec![]
         };
         let seed_nonce_hash_presence = utils::encode_bool(!seed_nonce_hash_bytes.is_empty());
-        let liquidity_baking_escape_vote_bytes =
-            [value.liquidity_baking_toggle_vote.to_u8().unwrap()];
+        let per_block_votes_bytes = [value.liquidity_baking_toggle_vote.to_u8().unwrap()
+            | value.adaptive_issuance_vote.to_u8().unwrap() << 2];
         let signature_bytes = value.signature.to_bytes()?;
 
         Ok([
@@ -207,7 +207,7 @@ impl Encoder<BlockHeader, Vec<u8>, Error> for OperationContentBytesCoder {
             proof_of_work_nonce_bytes.as_slice(),
             seed_nonce_hash_presence.as_slice(),
             seed_nonce_hash_bytes.as_slice(),
-            liquidity_baking_escape_vote_bytes.as_slice(),
+            per_block_votes_bytes.as_slice(),
             signature_bytes.as_slice(),
         ]
         .concat())
@@ -599,12 +599,8 @@ impl ConsumingDecoder<BlockHeader, u8, Error> for OperationContentBytesCoder {
         let level = utils::decode_consuming_i32(value)?;
         let proto = value.consume_first()?;
         let predecessor = BlockHash::from_consumable_bytes(value)?;
-        let timestamp_millis = utils::decode_consuming_i64(value)?;
-
-        let ts_secs = timestamp_millis / 1000;
-        let ts_ns = (timestamp_millis % 1000) * 1_000_000;
-        let timestamp = NaiveDateTime::from_timestamp_opt(ts_secs, ts_ns as u32)
-            .expect("out-of-range number of seconds and/or invalid nanosecond");
+        let timestamp = NaiveDateTime::from_timestamp_opt(utils::decode_consuming_i64(value)?, 0)
+            .ok_or(Error::InvalidBytes)?;
 
         let validation_pass = value.consume_first()?;
         let operations_hash = OperationListListHash::from_consumable_bytes(value)?;
@@ -629,9 +625,12 @@ impl ConsumingDecoder<BlockHeader, u8, Error> for OperationContentBytesCoder {
             None
         };
 
+        let per_block_votes = value.consume_first()?;
         let liquidity_baking_toggle_vote =
-            LiquidityBakingToggleVote::from_u8(value.consume_first()?)
+            LiquidityBakingToggleVote::from_u8(per_block_votes & 0b11)
                 .ok_or(Error::InvalidBytes)?;
+        let adaptive_issuance_vote =
+            AdaptiveIssuanceVote::from_u8(per_block_votes >> 2).ok_or(Error::InvalidBytes)?;
         let signature = Signature::from_consumable_bytes(value)?;
 
         Ok(BlockHeader {
@@ -648,6 +647,7 @@ impl ConsumingDecoder<BlockHeader, u8, Error> for OperationContentBytesCoder {
             proof_of_work_nonce,
             seed_nonce_hash,
             liquidity_baking_toggle_vote,
+            adaptive_issuance_vote,
             signature,
         })
     }
@@ -9,6 +9,7 @@ use tezos_core::{
 
 use crate::{
     operations::{Operation, SignedOperation, UnsignedOperation},
+    signing_payload::watermark,
     Error, Result,
 };
 
@@ -41,34 +42,114 @@ pub struct OperationSigner {
 }
 
 impl OperationSigner {
-    const WATERMARK: u8 = 3;
+    pub(crate) const WATERMARK: u8 = watermark::OPERATION;
     const MESSAGE_HASH_SIZE: usize = 32;
 
     pub fn new(crypto: Crypto) -> Self {
         Self { crypto }
     }
 
-    fn sign_raw<F>(&self, operation: &UnsignedOperation, key: &[u8], signer: F) -> Result<Vec<u8>>
-    where
-        F: FnOnce(&[u8], &[u8]) -> Result<Vec<u8>>,
-    {
-        signer(&self.hash(operation)?, key)
+    fn hash<O: Operation>(&self, operation: &O) -> Result<Vec<u8>> {
+        self.hash_watermarked(&[Self::WATERMARK], &operation.to_forged_bytes()?)
     }
 
-    fn hash<O: Operation>(&self, operation: &O) -> Result<Vec<u8>> {
-        Ok(self.crypto.blake2b(
-            &[&[Self::WATERMARK], operation.to_forged_bytes()?.as_slice()].concat(),
-            Self::MESSAGE_HASH_SIZE,
-        )?)
+    fn hash_watermarked(&self, watermark: &[u8], bytes: &[u8]) -> Result<Vec<u8>> {
+        Ok(self
+            .crypto
+            .blake2b(&[watermark, bytes].concat(), Self::MESSAGE_HASH_SIZE)?)
+    }
+
+    /// Signs the `bytes` prefixed with the `watermark`.
+    pub fn sign_watermarked(
+        &self,
+        watermark: &[u8],
+        bytes: &[u8],
+        secret: &SecretKey,
+    ) -> Result<Signature> {
+        let message = self.hash_watermarked(watermark, bytes)?;
+        let signature: Signature = match secret {
+            SecretKey::Ed25519(key) => self.sign_ed25519(&message, key)?.into(),
+            SecretKey::Secp256K1(key) => self.sign_secp256_k1(&message, key)?.into(),
+            SecretKey::P256(key) => self.sign_p256(&message, key)?.into(),
+        };
+
+        Ok(signature)
+    }
+
+    /// Verifies the `signature` of the `bytes` prefixed with the `watermark`.
+    pub fn verify_watermarked(
+        &self,
+        watermark: &[u8],
+        bytes: &[u8],
+        signature: &Signature,
+        key: &PublicKey,
+    ) -> Result<bool> {
+        let message = self.hash_watermarked(watermark, bytes)?;
+        let signature = signature.to_bytes()?;
+        match key {
+            PublicKey::Ed25519(key) => self.verify_ed25519(&message, &signature, key),
+            PublicKey::Secp256K1(key) => self.verify_secp256_k1(&message, &signature, key),
+            PublicKey::P256(key) => self.verify_p256(&message, &signature, key),
+        }
     }
 
-    fn verify_raw<F>(&self, operation: &SignedOperation, key: &[u8], verifier: F) -> Result<bool>
+    fn verify_raw<F>(&self, operation: &SignedOperation, verifier: F) -> Result<bool>
     where
-        F: FnOnce(&[u8], &[u8], &[u8]) -> Result<bool>,
+        F: FnOnce(&[u8], &[u8]) -> Result<bool>,
     {
         let signature = operation.signature.to_bytes()?;
         self.hash(operation)
-            .map_or(Ok(false), |message| verifier(&message, &signature, key))
+            .map_or(Ok(false), |message| verifier(&message, &signature))
+    }
+
+    fn sign_ed25519(&self, message: &[u8], secret: &Ed25519SecretKey) -> Result<Ed25519Signature> {
+        let signature = self.crypto.sign_ed25519(message, &secret.to_bytes()?)?;
+
+        Ok((&signature).try_into()?)
+    }
+
+    fn verify_ed25519(
+        &self,
+        message: &[u8],
+        signature: &[u8],
+        key: &Ed25519PublicKey,
+    ) -> Result<bool> {
+        Ok(self
+            .crypto
+            .verify_ed25519(message, signature, &key.to_bytes()?)?)
+    }
+
+    fn sign_secp256_k1(
+        &self,
+        message: &[u8],
+        secret: &Secp256K1SecretKey,
+    ) -> Result<Secp256K1Signature> {
+        let signature = self.crypto.sign_secp256_k1(message, &secret.to_bytes()?)?;
+
+        Ok((&signature).try_into()?)
+    }
+
+    fn verify_secp256_k1(
+        &self,
+        message: &[u8],
+        signature: &[u8],
+        key: &Secp256K1PublicKey,
+    ) -> Result<bool> {
+        Ok(self
+            .crypto
+            .verify_secp256_k1(message, signature, &key.to_bytes()?)?)
+    }
+
+    fn sign_p256(&self, message: &[u8], secret: &P256SecretKey) -> Result<P256Signature> {
+        let signature = self.crypto.sign_p256(message, &secret.to_bytes()?)?;
+
+        Ok((&signature).try_into()?)
+    }
+
+    fn verify_p256(&self, message: &[u8], signature: &[u8], key: &P256PublicKey) -> Result<bool> {
+        Ok(self
+            .crypto
+            .verify_p256(message, signature, &key.to_bytes()?)?)
     }
 }
 
@@ -104,12 +185,7 @@ impl Signer<Ed25519SecretKey> for OperationSigner {
     type Error = Error;
 
     fn sign(&self, message: &Self::Message, secret: &Ed25519SecretKey) -> Result<Self::Output> {
-        let key = secret.to_bytes()?;
-        let signature = self.sign_raw(message, &key, |message, secret| {
-            Ok(self.crypto.sign_ed25519(message, secret)?)
-        })?;
-
-        Ok((&signature).try_into()?)
+        self.sign_ed25519(&self.hash(message)?, secret)
     }
 }
 
@@ -117,9 +193,8 @@ impl Verifier<Ed25519PublicKey> for OperationSigner {
     type Message = SignedOperation;
 
     fn verify(&self, message: &Self::Message, key: &Ed25519PublicKey) -> Result<bool> {
-        let key = key.to_bytes()?;
-        self.verify_raw(message, &key, |message, signature, key| {
-            Ok(self.crypto.verify_ed25519(message, signature, key)?)
+        self.verify_raw(message, |message, signature| {
+            self.verify_ed25519(message, signature, key)
         })
     }
 }
@@ -130,12 +205,7 @@ impl Signer<Secp256K1SecretKey> for OperationSigner {
     type Error = Error;
 
     fn sign(&self, message: &Self::Message, secret: &Secp256K1SecretKey) -> Result<Self::Output> {
-        let key = secret.to_bytes()?;
-        let signature = self.sign_raw(message, &key, |message, secret| {
-            Ok(self.crypto.sign_secp256_k1(message, secret)?)
-        })?;
-
-        Ok((&signature).try_into()?)
+        self.sign_secp256_k1(&self.hash(message)?, secret)
     }
 }
 
@@ -143,9 +213,8 @@ impl Verifier<Secp256K1PublicKey> for OperationSigner {
     type Message = SignedOperation;
 
     fn verify(&self, message: &Self::Message, key: &Secp256K1PublicKey) -> Result<bool> {
-        let key = key.to_bytes()?;
-        self.verify_raw(message, &key, |message, signature, key| {
-            Ok(self.crypto.verify_secp256_k1(message, signature, key)?)
+        self.verify_raw(message, |message, signature| {
+            self.verify_secp256_k1(message, signature, key)
         })
     }
 }
@@ -156,12 +225,7 @@ impl Signer<P256SecretKey> for OperationSigner {
     type Error = Error;
 
     fn sign(&self, message: &Self::Message, secret: &P256SecretKey) -> Result<Self::Output> {
-        let key = secret.to_bytes()?;
-        let signature = self.sign_raw(message, &key, |message, secret| {
-            Ok(self.crypto.sign_p256(message, secret)?)
-        })?;
-
-        Ok((&signature).try_into()?)
+        self.sign_p256(&self.hash(message)?, secret)
     }
 }
 
@@ -169,9 +233,8 @@ impl Verifier<P256PublicKey> for OperationSigner {
     type Message = SignedOperation;
 
     fn verify(&self, message: &Self::Message, key: &P256PublicKey) -> Result<bool> {
-        let key = key.to_bytes()?;
-        self.verify_raw(message, &key, |message, signature, key| {
-            Ok(self.crypto.verify_p256(message, signature, key)?)
+        self.verify_raw(message, |message, signature| {
+            self.verify_p256(message, signature, key)
         })
     }
 }
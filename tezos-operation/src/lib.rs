@@ -139,10 +139,31 @@
 //! # #[cfg(feature = "ed25519")]
 //! let is_signature_valid = signed.verify(&public_key.into()).expect("verification completed without errors");
 //! ```
+//!
+//! An injectable string can be parsed back into a [SignedOperation](crate::operations::SignedOperation) with
+//! [SignedOperation::from_injectable_string](crate::operations::SignedOperation::from_injectable_string).
+//! The signature is typed according to the curve of the operation's source:
+//!
+//! ```rust
+//! use tezos_core::types::encoded::{Encoded, Signature};
+//! use tezos_operation::operations::{Operation, SignedOperation};
+//!
+//! let signed = SignedOperation::from_injectable_string(
+//!     "fcfe60b37e1a5a9f71c616218facf0aa6a60856d3550674ae7911fc059ba007e6c002f2fd2798d6b000eabbbbc234648465aa2d762d900000000e80700011a868789216112194b3bb003e8384c8f7217b28800c6fa7ae466e68a2f8845d98909a80fdd40855edb37aafc0b99b740bb8b5d7ad2a9fad54120d09373445fb70ed280c59ebeb9871a40be5013622ee3e795ed190a"
+//! ).expect("valid injectable string");
+//! assert_eq!(signed.source().expect("source").value(), "tz1PwXjsrgYBi9wpe3tFhazJpt7JMTVzBp5c");
+//! assert!(matches!(signed.signature, Signature::Ed25519(_)));
+//! assert_eq!(signed.hash().expect("valid hash").value(), "oo5scXpFA4JnBRshdoRsfd9LtU9E4khS9ohZBAzEbWTrHNE3YxX");
+//! ```
 
 pub mod block_header;
 mod error;
 mod internal;
 pub mod operations;
+pub mod sign_in;
+pub mod signer;
+pub mod signing_payload;
+pub mod summary;
+pub mod validator;
 
 pub use error::{Error, Result};
@@ -22,8 +22,11 @@ use tezos_core::{
         crypto::Crypto,
     },
     types::{
-        encoded::{BlockHash, Encoded, OperationHash, PublicKey, SecretKey, Signature},
+        encoded::{
+            BlockHash, Encoded, ImplicitAddress, OperationHash, PublicKey, SecretKey, Signature,
+        },
         mutez::Mutez,
+        number::Nat,
     },
     Tezos,
 };
@@ -36,7 +39,7 @@ use crate::{
         },
         signer::{OperationSigner, Signer, Verifier},
     },
-    Result,
+    Error, Result,
 };
 
 pub use self::{
@@ -70,6 +73,11 @@ pub trait Operation {
     /// Returns the operation's content.
     fn contents(&self) -> &[OperationContent];
 
+    /// Returns the source of the operation's first manager content, if any.
+    fn source(&self) -> Option<&ImplicitAddress> {
+        self.contents().iter().find_map(|content| content.source())
+    }
+
     /// Returns the operation forged bytes.
     fn to_forged_bytes(&self) -> Result<Vec<u8>>
     where
@@ -146,6 +154,26 @@ impl UnsignedOperation {
 
         self.sign_with(key, &tezos)
     }
+
+    /// Creates a [SignedOperation] by signing the [UnsignedOperation] with the given [Signer](crate::signer::Signer).
+    pub async fn into_signed_operation_using<S>(self, signer: &S) -> Result<SignedOperation>
+    where
+        S: crate::signer::Signer + ?Sized,
+    {
+        let signature = self.sign_using(signer).await?;
+
+        Ok(SignedOperation::new(self.branch, self.contents, signature))
+    }
+
+    /// Creates a [Signature] by signing the [UnsignedOperation] with the given [Signer](crate::signer::Signer).
+    pub async fn sign_using<S>(&self, signer: &S) -> Result<Signature>
+    where
+        S: crate::signer::Signer + ?Sized,
+    {
+        signer
+            .sign(&[OperationSigner::WATERMARK], &self.to_forged_bytes()?)
+            .await
+    }
 }
 
 impl Operation for UnsignedOperation {
@@ -166,7 +194,7 @@ impl From<SignedOperation> for UnsignedOperation {
 
 /// The [SignedOperation] struct represent a signed tezos operation. It can be torned to an injectable string,
 /// which can be injected into the Tezos blockchain using the `tezos-rpc` crate.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SignedOperation {
     pub branch: BlockHash,
     pub contents: Vec<OperationContent>,
@@ -196,6 +224,21 @@ impl SignedOperation {
         Ok(hex::encode([forged_bytes, signature_bytes].concat()))
     }
 
+    /// Creates a new [SignedOperation] from an injectable string, as returned by [SignedOperation::to_injectable_string].
+    pub fn from_injectable_string(value: &str) -> Result<Self> {
+        let bytes = hex::decode(value).map_err(|_error| Error::InvalidBytes)?;
+
+        Self::from_injectable_bytes(bytes)
+    }
+
+    /// Creates a new [SignedOperation] from the injectable bytes, i.e. the forged operation followed by its signature.
+    ///
+    /// The signature is typed according to the curve of the operation's source (see [Operation::source]).
+    /// If the operation has no manager content, a generic signature is returned.
+    pub fn from_injectable_bytes<T: AsRef<[u8]>>(bytes: T) -> Result<Self> {
+        OperationBytesCoder::decode(bytes.as_ref())
+    }
+
     /// Creates a new instance of [SignedOperation].
     pub fn new(branch: BlockHash, contents: Vec<OperationContent>, signature: Signature) -> Self {
         Self {
@@ -256,6 +299,27 @@ pub enum OperationContent {
 }
 
 impl OperationContent {
+    pub fn tag(&self) -> OperationContentTag {
+        match self {
+            Self::SeedNonceRevelation(_) => SeedNonceRevelation::tag(),
+            Self::DoubleEndorsementEvidence(_) => DoubleEndorsementEvidence::tag(),
+            Self::DoubleBakingEvidence(_) => DoubleBakingEvidence::tag(),
+            Self::ActivateAccount(_) => ActivateAccount::tag(),
+            Self::Proposals(_) => Proposals::tag(),
+            Self::Ballot(_) => Ballot::tag(),
+            Self::DoublePreendorsementEvidence(_) => DoublePreendorsementEvidence::tag(),
+            Self::FailingNoop(_) => FailingNoop::tag(),
+            Self::Preendorsement(_) => Preendorsement::tag(),
+            Self::Endorsement(_) => Endorsement::tag(),
+            Self::Reveal(_) => Reveal::tag(),
+            Self::Transaction(_) => Transaction::tag(),
+            Self::Origination(_) => Origination::tag(),
+            Self::Delegation(_) => Delegation::tag(),
+            Self::RegisterGlobalConstant(_) => RegisterGlobalConstant::tag(),
+            Self::SetDepositsLimit(_) => SetDepositsLimit::tag(),
+        }
+    }
+
     pub fn to_forged_bytes(&self) -> Result<Vec<u8>> {
         OperationContentBytesCoder::encode(self)
     }
@@ -279,6 +343,54 @@ impl OperationContent {
     pub fn has_fee(&self) -> bool {
         self.fee() != 0u8.into()
     }
+
+    pub fn source(&self) -> Option<&ImplicitAddress> {
+        match self {
+            Self::Reveal(value) => Some(&value.source),
+            Self::Transaction(value) => Some(&value.source),
+            Self::Origination(value) => Some(&value.source),
+            Self::Delegation(value) => Some(&value.source),
+            Self::RegisterGlobalConstant(value) => Some(&value.source),
+            Self::SetDepositsLimit(value) => Some(&value.source),
+            _ => None,
+        }
+    }
+
+    pub fn counter(&self) -> Option<&Nat> {
+        match self {
+            Self::Reveal(value) => Some(&value.counter),
+            Self::Transaction(value) => Some(&value.counter),
+            Self::Origination(value) => Some(&value.counter),
+            Self::Delegation(value) => Some(&value.counter),
+            Self::RegisterGlobalConstant(value) => Some(&value.counter),
+            Self::SetDepositsLimit(value) => Some(&value.counter),
+            _ => None,
+        }
+    }
+
+    pub fn gas_limit(&self) -> Option<&Nat> {
+        match self {
+            Self::Reveal(value) => Some(&value.gas_limit),
+            Self::Transaction(value) => Some(&value.gas_limit),
+            Self::Origination(value) => Some(&value.gas_limit),
+            Self::Delegation(value) => Some(&value.gas_limit),
+            Self::RegisterGlobalConstant(value) => Some(&value.gas_limit),
+            Self::SetDepositsLimit(value) => Some(&value.gas_limit),
+            _ => None,
+        }
+    }
+
+    pub fn storage_limit(&self) -> Option<&Nat> {
+        match self {
+            Self::Reveal(value) => Some(&value.storage_limit),
+            Self::Transaction(value) => Some(&value.storage_limit),
+            Self::Origination(value) => Some(&value.storage_limit),
+            Self::Delegation(value) => Some(&value.storage_limit),
+            Self::RegisterGlobalConstant(value) => Some(&value.storage_limit),
+            Self::SetDepositsLimit(value) => Some(&value.storage_limit),
+            _ => None,
+        }
+    }
 }
 
 impl From<SeedNonceRevelation> for OperationContent {
@@ -377,7 +489,7 @@ impl From<SetDepositsLimit> for OperationContent {
     }
 }
 
-#[derive(Debug, Clone, Copy, FromPrimitive)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, FromPrimitive)]
 #[repr(u8)]
 pub enum OperationContentTag {
     SeedNonceRevelation = 1,
@@ -402,6 +514,28 @@ impl OperationContentTag {
     pub fn to_bytes(&self) -> [u8; 1] {
         [*self as u8]
     }
+
+    /// Returns the kind of the operation content as named in the Tezos RPC (e.g. `transaction`).
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::SeedNonceRevelation => "seed_nonce_revelation",
+            Self::DoubleEndorsementEvidence => "double_endorsement_evidence",
+            Self::DoubleBakingEvidence => "double_baking_evidence",
+            Self::ActivateAccount => "activate_account",
+            Self::Proposals => "proposals",
+            Self::Ballot => "ballot",
+            Self::DoublePreendorsementEvidence => "double_preendorsement_evidence",
+            Self::FailingNoop => "failing_noop",
+            Self::Preendorsement => "preendorsement",
+            Self::Endorsement => "endorsement",
+            Self::Reveal => "reveal",
+            Self::Transaction => "transaction",
+            Self::Origination => "origination",
+            Self::Delegation => "delegation",
+            Self::RegisterGlobalConstant => "register_global_constant",
+            Self::SetDepositsLimit => "set_deposits_limit",
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -469,7 +603,7 @@ mod traits {
 
 #[cfg(test)]
 mod test {
-    use crate::block_header::{BlockHeader, LiquidityBakingToggleVote};
+    use crate::block_header::{AdaptiveIssuanceVote, BlockHeader, LiquidityBakingToggleVote};
 
     use super::*;
     use chrono::DateTime;
@@ -581,7 +715,7 @@ mod test {
                         level: 1,
                         proto: 1,
                         predecessor: "BKsP8FYgikDmqbUiVxfgXVjWuay5LQZY6LP4EvcsFK8uuqj4wQD".try_into().unwrap(),
-                        timestamp: DateTime::parse_from_rfc3339("1970-01-01T00:00:00.001Z").unwrap().naive_utc(),
+                        timestamp: DateTime::parse_from_rfc3339("1970-01-01T00:00:01Z").unwrap().naive_utc(),
                         validation_pass: 1,
                         operations_hash: "LLoaLP6mc6nVzG2Rp3fSrHFvvGpUvkbHCjLASVduN7GzQAKnPctrR".try_into().unwrap(),
                         fitness: vec![],
@@ -591,13 +725,14 @@ mod test {
                         proof_of_work_nonce: "d4d34b5686c98ae1".try_into().unwrap(),
                         seed_nonce_hash: None,
                         liquidity_baking_toggle_vote: LiquidityBakingToggleVote::On,
+                        adaptive_issuance_vote: AdaptiveIssuanceVote::On,
                         signature: "sigiaEd9dHEGKgccx3JBBDw4eb6WVxGH3MvyziYbQqWQRMmyecdo5VuSkYWkgZvcQXshB4vV2qkTb6AxbKruaNPfnMg4u2EA".try_into().unwrap()
                     },
                     BlockHeader {
                         level: 2,
                         proto: 2,
                         predecessor: "BMaBxGyVhtTiMKd7KA8HXJnbTK4e1TzffNc94G18op55HGQYVRk".try_into().unwrap(),
-                        timestamp: DateTime::parse_from_rfc3339("1970-01-01T00:00:00.002Z").unwrap().naive_utc(),
+                        timestamp: DateTime::parse_from_rfc3339("1970-01-01T00:00:02Z").unwrap().naive_utc(),
                         validation_pass: 2,
                         operations_hash: "LLoaNF9sd5z2SZtSmUopYNX6qs77QAUJqrnd5ei378H4bcJhQcPt5".try_into().unwrap(),
                         fitness: vec![],
@@ -607,6 +742,7 @@ mod test {
                         proof_of_work_nonce: "336ebf95efce0475".try_into().unwrap(),
                         seed_nonce_hash: Some("nceUeUCJRZ4M7FCSBsAUZU6dmxePdH7irje9Gfj9zWwCdfWd5B4Ee".try_into().unwrap()),
                         liquidity_baking_toggle_vote: LiquidityBakingToggleVote::Off,
+                        adaptive_issuance_vote: AdaptiveIssuanceVote::On,
                         signature: "sigRsUhHqaFVBeV4qzyCZ6Y9TvoKajyNwyPQQCW3SbgPYY99MrpTqR2FopjzZEHMWoJG7LaTaHu7bnieKQRKqCRLA7hB7Ekp".try_into().unwrap()
                     },
                 )
@@ -619,7 +755,7 @@ mod test {
                         level: 1,
                         proto: 1,
                         predecessor: "BKsP8FYgikDmqbUiVxfgXVjWuay5LQZY6LP4EvcsFK8uuqj4wQD".try_into().unwrap(),
-                        timestamp: DateTime::parse_from_rfc3339("1970-01-01T00:00:00.001Z").unwrap().naive_utc(),
+                        timestamp: DateTime::parse_from_rfc3339("1970-01-01T00:00:01Z").unwrap().naive_utc(),
                         validation_pass: 1,
                         operations_hash: "LLoaLP6mc6nVzG2Rp3fSrHFvvGpUvkbHCjLASVduN7GzQAKnPctrR".try_into().unwrap(),
                         fitness: vec!["00000001000000000100000001".try_into().unwrap()],
@@ -629,13 +765,14 @@ mod test {
                         proof_of_work_nonce: "d4d34b5686c98ae1".try_into().unwrap(),
                         seed_nonce_hash: None,
                         liquidity_baking_toggle_vote: LiquidityBakingToggleVote::Off,
+                        adaptive_issuance_vote: AdaptiveIssuanceVote::On,
                         signature: "sigiaEd9dHEGKgccx3JBBDw4eb6WVxGH3MvyziYbQqWQRMmyecdo5VuSkYWkgZvcQXshB4vV2qkTb6AxbKruaNPfnMg4u2EA".try_into().unwrap()
                     },
                     BlockHeader {
                         level: 2,
                         proto: 2,
                         predecessor: "BMaBxGyVhtTiMKd7KA8HXJnbTK4e1TzffNc94G18op55HGQYVRk".try_into().unwrap(),
-                        timestamp: DateTime::parse_from_rfc3339("1970-01-01T00:00:00.002Z").unwrap().naive_utc(),
+                        timestamp: DateTime::parse_from_rfc3339("1970-01-01T00:00:02Z").unwrap().naive_utc(),
                         validation_pass: 2,
                         operations_hash: "LLoaNF9sd5z2SZtSmUopYNX6qs77QAUJqrnd5ei378H4bcJhQcPt5".try_into().unwrap(),
                         fitness: vec!["00000002ff000000020000000200000002".try_into().unwrap(), "00000002000000000200000002".try_into().unwrap()],
@@ -645,6 +782,7 @@ mod test {
                         proof_of_work_nonce: "336ebf95efce0475".try_into().unwrap(),
                         seed_nonce_hash: Some("nceUeUCJRZ4M7FCSBsAUZU6dmxePdH7irje9Gfj9zWwCdfWd5B4Ee".try_into().unwrap()),
                         liquidity_baking_toggle_vote: LiquidityBakingToggleVote::Pass,
+                        adaptive_issuance_vote: AdaptiveIssuanceVote::On,
                         signature: "sigRsUhHqaFVBeV4qzyCZ6Y9TvoKajyNwyPQQCW3SbgPYY99MrpTqR2FopjzZEHMWoJG7LaTaHu7bnieKQRKqCRLA7hB7Ekp".try_into().unwrap()
                     },
                 )
@@ -790,4 +928,93 @@ mod test {
         assert_eq!(expected, actual);
         Ok(())
     }
+
+    #[test]
+    fn test_from_injectable_string() -> Result<()> {
+        let expected = SignedOperation::new(
+            "BMNvSHmWUkdonkG2oFwwQKxHUdrYQhUXqxLaSRX9wjMGfLddURC".try_into().unwrap(),
+            vec![
+                Transaction::new(
+                    "tz1V3dHSCJnWPRdzDmZGCZaTMuiTmbtPakmU".try_into().unwrap(),
+                    417u32.into(),
+                    2336132u32.into(),
+                    1527u32.into(),
+                    357u32.into(),
+                    498719u32.into(),
+                    "tz1d5Dr3gjsxQo5XNbjAj558mLy3nGGQgMFA".try_into().unwrap(),
+                    None
+                ).into()
+            ],
+            "edsigtyqcyfipEAqFVexKahDjtQkzFgAkd9MroyYHnxxUVHAi4oSBJSezwiKaoNpH9NkY34cNuR4nrL6s8oPWVstQp9h2f7iGQF".try_into().unwrap()
+        );
+        let injectable = expected.to_injectable_string()?;
+        let actual = SignedOperation::from_injectable_string(&injectable)?;
+        assert_eq!(expected, actual);
+        assert!(matches!(actual.signature, Signature::Ed25519(_)));
+        assert_eq!(expected.hash()?, actual.hash()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_injectable_bytes_without_source() -> Result<()> {
+        let expected = SignedOperation::new(
+            "BMNvSHmWUkdonkG2oFwwQKxHUdrYQhUXqxLaSRX9wjMGfLddURC".try_into().unwrap(),
+            vec![SeedNonceRevelation::new(
+                1,
+                "6cdaf9367e551995a670a5c642a9396290f8c9d17e6bc3c1555bfaa910d92214"
+                    .try_into()
+                    .unwrap(),
+            )
+            .into()],
+            "sigw1WNdYweqz1c7zKcvZFHQ18swSv4HBWje5quRmixxitPk7z8jtY63qXgKLPVfTM6XGxExPatBWJP44Bknyu3hDHDKJZgY".try_into().unwrap()
+        );
+        let mut bytes = expected.to_forged_bytes()?;
+        bytes.extend(expected.signature.to_bytes()?);
+        let actual = SignedOperation::from_injectable_bytes(bytes)?;
+        assert_eq!(expected, actual);
+        assert!(matches!(actual.signature, Signature::Generic(_)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_injectable_string_fails_on_invalid_input() {
+        assert!(SignedOperation::from_injectable_string("not hex").is_err());
+        assert!(SignedOperation::from_injectable_bytes([0u8; 63]).is_err());
+    }
+
+    #[cfg(feature = "ed25519")]
+    #[tokio::test]
+    async fn test_into_signed_operation_using() -> Result<()> {
+        use crate::signer::InMemorySigner;
+
+        let secret_key: SecretKey = "edskRv7VyXGVZb8EsrR7D9XKUbbAQNQGtALP6QeB16ZCD7SmmJpzyeneJVg3Mq56YLbxRA1kSdAXiswwPiaVfR3NHGMCXCziuZ".try_into().unwrap();
+        let public_key: PublicKey = "edpkttZKC51wemRqL2QxwpMnEKxWnbd35pq47Y6xsCHp5M1f7LN8NP"
+            .try_into()
+            .unwrap();
+        let unsigned = UnsignedOperation::new(
+            "BMNvSHmWUkdonkG2oFwwQKxHUdrYQhUXqxLaSRX9wjMGfLddURC"
+                .try_into()
+                .unwrap(),
+            vec![Transaction::new(
+                "tz1RFxSHjxxyaaXyAvJG3uRQeJUCCm4jk2LX".try_into().unwrap(),
+                417u32.into(),
+                2336132u32.into(),
+                1527u32.into(),
+                357u32.into(),
+                498719u32.into(),
+                "tz1d5Dr3gjsxQo5XNbjAj558mLy3nGGQgMFA".try_into().unwrap(),
+                None,
+            )
+            .into()],
+        );
+        let signer = InMemorySigner::new(secret_key.clone(), public_key.clone());
+
+        let signed = unsigned
+            .clone()
+            .into_signed_operation_using(&signer)
+            .await?;
+        assert_eq!(signed, unsigned.into_signed_operation(&secret_key)?);
+        assert!(signed.verify(&public_key)?);
+        Ok(())
+    }
 }
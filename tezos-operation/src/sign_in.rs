@@ -0,0 +1,259 @@
+use chrono::{DateTime, NaiveDateTime};
+use tezos_core::{
+    types::encoded::{ImplicitAddress, PublicKey, SecretKey, Signature},
+    Tezos,
+};
+use tezos_michelson::micheline::literals::String as MichelineString;
+
+use crate::{signing_payload::SigningPayload, Error, Result};
+
+/// A sign-in message, which a user signs with their wallet to authenticate to a dApp.
+///
+/// The message follows the format expected by wallets for Micheline signing requests:
+/// `Tezos Signed Message: <dApp URL> <ISO 8601 timestamp> <statement>`, packed as a Micheline string
+/// and signed with the `0x05` watermark. The same message can be built by a client to request
+/// the signature and by a server to verify it:
+///
+/// ```rust
+/// use chrono::NaiveDateTime;
+/// use tezos_operation::sign_in::SignInMessage;
+///
+/// let message = SignInMessage::new(
+///     "https://example.com".into(),
+///     NaiveDateTime::from_timestamp_opt(1640167546, 0).unwrap(),
+///     "I accept the Terms of Service".into(),
+/// );
+/// assert_eq!(
+///     message.to_string(),
+///     "Tezos Signed Message: https://example.com 2021-12-22T10:05:46.000Z I accept the Terms of Service"
+/// );
+/// let payload = message.to_hex().expect("valid message");
+/// assert_eq!(SignInMessage::from_hex(&payload).expect("valid payload"), message);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignInMessage {
+    /// The URL of the dApp the user signs in to.
+    pub dapp_url: String,
+    /// The time at which the message has been created, in UTC.
+    pub timestamp: NaiveDateTime,
+    /// The statement the user agrees to by signing in.
+    pub statement: String,
+}
+
+impl SignInMessage {
+    pub const HEADER: &'static str = "Tezos Signed Message:";
+    const TIMESTAMP_FORMAT: &'static str = "%Y-%m-%dT%H:%M:%S%.3fZ";
+
+    pub fn new(dapp_url: String, timestamp: NaiveDateTime, statement: String) -> Self {
+        Self {
+            dapp_url,
+            timestamp,
+            statement,
+        }
+    }
+
+    /// Parses a formatted sign-in message, as returned by [SignInMessage::to_string].
+    pub fn parse(message: &str) -> Result<Self> {
+        let message = message
+            .strip_prefix(Self::HEADER)
+            .and_then(|message| message.strip_prefix(' '))
+            .ok_or(Error::InvalidSignInMessage)?;
+        let mut parts = message.splitn(3, ' ');
+        let (dapp_url, timestamp, statement) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(dapp_url), Some(timestamp), statement) if !dapp_url.is_empty() => {
+                (dapp_url, timestamp, statement.unwrap_or_default())
+            }
+            _ => return Err(Error::InvalidSignInMessage),
+        };
+        let timestamp = DateTime::parse_from_rfc3339(timestamp)
+            .map_err(|_| Error::InvalidSignInMessage)?
+            .naive_utc();
+
+        Ok(Self::new(dapp_url.into(), timestamp, statement.into()))
+    }
+
+    /// Creates the signing payload of the message, i.e. the message packed as a Micheline string.
+    pub fn to_payload(&self) -> Result<SigningPayload> {
+        let message: MichelineString = self.to_string().try_into()?;
+
+        SigningPayload::pack(message.into(), None)
+    }
+
+    /// Reads the message from a signing payload, as created by [SignInMessage::to_payload].
+    pub fn from_payload(payload: &SigningPayload) -> Result<Self> {
+        let message = payload
+            .unpack(None)?
+            .into_literal()
+            .and_then(|literal| literal.into_micheline_string())
+            .ok_or(Error::InvalidSignInMessage)?;
+
+        Self::parse(message.to_str())
+    }
+
+    /// Encodes the signing payload of the message, prefixed with the `0x05` watermark.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        self.to_payload()?.to_bytes()
+    }
+
+    /// Decodes the message from the bytes returned by [SignInMessage::to_bytes].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        Self::from_payload(&SigningPayload::from_bytes(bytes)?)
+    }
+
+    /// Encodes the signing payload of the message as a hex string, the way wallets expect it to be sent.
+    pub fn to_hex(&self) -> Result<String> {
+        Ok(hex::encode(self.to_bytes()?))
+    }
+
+    /// Decodes the message from the hex string returned by [SignInMessage::to_hex].
+    pub fn from_hex(value: &str) -> Result<Self> {
+        let bytes = hex::decode(value).map_err(|_| Error::InvalidBytes)?;
+
+        Self::from_bytes(&bytes)
+    }
+
+    /// Signs the message with the given secret key and using the crypto providers configured in the given [Tezos] instance.
+    pub fn sign_with(&self, key: &SecretKey, tezos: &Tezos) -> Result<Signature> {
+        self.to_payload()?.sign_with(key, tezos)
+    }
+
+    /// Signs the message with the given secret key.
+    pub fn sign(&self, key: &SecretKey) -> Result<Signature> {
+        self.sign_with(key, &Default::default())
+    }
+
+    /// Signs the message with the given [Signer](crate::signer::Signer).
+    pub async fn sign_using<S>(&self, signer: &S) -> Result<Signature>
+    where
+        S: crate::signer::Signer + ?Sized,
+    {
+        self.to_payload()?.sign_using(signer).await
+    }
+
+    /// Verifies that the message has been signed by the owner of `address`, using
+    /// the crypto providers configured in the given [Tezos] instance.
+    ///
+    /// The signature is only valid if `key` is the public key of `address`.
+    pub fn verify_with(
+        &self,
+        signature: &Signature,
+        key: &PublicKey,
+        address: &ImplicitAddress,
+        tezos: &Tezos,
+    ) -> Result<bool> {
+        let key_address: ImplicitAddress = key.bs58_address()?.try_into()?;
+        if &key_address != address {
+            return Ok(false);
+        }
+
+        self.to_payload()?.verify_with(signature, key, tezos)
+    }
+
+    /// Verifies that the message has been signed by the owner of `address`.
+    ///
+    /// The signature is only valid if `key` is the public key of `address`.
+    pub fn verify(
+        &self,
+        signature: &Signature,
+        key: &PublicKey,
+        address: &ImplicitAddress,
+    ) -> Result<bool> {
+        self.verify_with(signature, key, address, &Default::default())
+    }
+}
+
+impl std::fmt::Display for SignInMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {} {}",
+            Self::HEADER,
+            self.dapp_url,
+            self.timestamp.format(Self::TIMESTAMP_FORMAT),
+            self.statement
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn message() -> SignInMessage {
+        SignInMessage::new(
+            "https://example.com".into(),
+            NaiveDateTime::from_timestamp_opt(1640167546, 123_000_000).unwrap(),
+            "I accept the Terms of Service".into(),
+        )
+    }
+
+    #[test]
+    fn test_format_and_parse() -> Result<()> {
+        let formatted = "Tezos Signed Message: https://example.com 2021-12-22T10:05:46.123Z I accept the Terms of Service";
+        assert_eq!(message().to_string(), formatted);
+        assert_eq!(SignInMessage::parse(formatted)?, message());
+        // timestamps with an offset are converted to UTC
+        assert_eq!(
+            SignInMessage::parse("Tezos Signed Message: https://example.com 2021-12-22T11:05:46.123+01:00 I accept the Terms of Service")?,
+            message()
+        );
+
+        let invalid = vec![
+            "https://example.com 2021-12-22T10:05:46.123Z I accept the Terms of Service",
+            "Tezos Signed Message: https://example.com yesterday I accept the Terms of Service",
+            "Tezos Signed Message: https://example.com",
+        ];
+        for message in invalid {
+            assert!(matches!(
+                SignInMessage::parse(message),
+                Err(Error::InvalidSignInMessage)
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_payload() -> Result<()> {
+        let message = message();
+        let formatted = message.to_string();
+        let length = (formatted.len() as u32).to_be_bytes();
+        let expected = [[0x05, 0x01].as_slice(), &length, formatted.as_bytes()].concat();
+
+        assert_eq!(message.to_bytes()?, expected);
+        assert_eq!(message.to_hex()?, hex::encode(&expected));
+        assert_eq!(SignInMessage::from_bytes(&expected)?, message);
+        assert!(matches!(
+            SignInMessage::from_bytes(&[0x05, 0x00, 0x0a]),
+            Err(Error::InvalidSignInMessage)
+        ));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "ed25519")]
+    #[test]
+    fn test_sign_verify() -> Result<()> {
+        let secret_key: SecretKey = "edskRv7VyXGVZb8EsrR7D9XKUbbAQNQGtALP6QeB16ZCD7SmmJpzyeneJVg3Mq56YLbxRA1kSdAXiswwPiaVfR3NHGMCXCziuZ".try_into()?;
+        let public_key: PublicKey =
+            "edpkttZKC51wemRqL2QxwpMnEKxWnbd35pq47Y6xsCHp5M1f7LN8NP".try_into()?;
+        let address: ImplicitAddress = "tz1RFxSHjxxyaaXyAvJG3uRQeJUCCm4jk2LX".try_into()?;
+
+        let message = message();
+        let signature = message.sign(&secret_key)?;
+        assert!(message.verify(&signature, &public_key, &address)?);
+
+        // the public key has to match the address
+        let other_address: ImplicitAddress = "tz1PwXjsrgYBi9wpe3tFhazJpt7JMTVzBp5c".try_into()?;
+        assert!(!message.verify(&signature, &public_key, &other_address)?);
+
+        // the signature has to match the message
+        let other_message = SignInMessage {
+            statement: "I accept the Privacy Policy".into(),
+            ..message
+        };
+        assert!(!other_message.verify(&signature, &public_key, &address)?);
+
+        Ok(())
+    }
+}
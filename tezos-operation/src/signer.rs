@@ -0,0 +1,158 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tezos_core::{
+    internal::crypto::Crypto,
+    types::encoded::{ImplicitAddress, PublicKey, SecretKey, Signature},
+    CryptoConfig, DefaultCryptoConfig,
+};
+
+use crate::{internal::signer::OperationSigner, Result};
+
+/// A signer holding a key pair, which may live outside of the process (e.g. a remote signer, an HSM or a wallet).
+///
+/// The signer signs the `blake2b` hash of the watermarked bytes, the way the Tezos protocol expects it.
+#[async_trait]
+pub trait Signer: Send + Sync {
+    /// Returns the public key of the signer.
+    async fn public_key(&self) -> Result<PublicKey>;
+
+    /// Returns the address of the signer, derived from its public key.
+    async fn address(&self) -> Result<ImplicitAddress> {
+        let public_key = self.public_key().await?;
+
+        Ok(public_key.bs58_address()?.try_into()?)
+    }
+
+    /// Signs the `bytes` prefixed with the `watermark`.
+    async fn sign(&self, watermark: &[u8], bytes: &[u8]) -> Result<Signature>;
+}
+
+/// A [Signer] backed by a [SecretKey] kept in memory.
+///
+/// ```rust
+/// use tezos_operation::signer::{InMemorySigner, Signer};
+///
+/// async fn example() -> tezos_operation::Result<()> {
+///     let signer = InMemorySigner::new(
+///         "edskRv7VyXGVZb8EsrR7D9XKUbbAQNQGtALP6QeB16ZCD7SmmJpzyeneJVg3Mq56YLbxRA1kSdAXiswwPiaVfR3NHGMCXCziuZ".try_into()?,
+///         "edpkttZKC51wemRqL2QxwpMnEKxWnbd35pq47Y6xsCHp5M1f7LN8NP".try_into()?,
+///     );
+///     let signature = signer.sign(&[0x05], &[0x01, 0x00]).await?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+pub struct InMemorySigner {
+    secret_key: SecretKey,
+    public_key: PublicKey,
+    crypto_config: Arc<dyn CryptoConfig + Send + Sync>,
+}
+
+impl InMemorySigner {
+    /// Creates a signer for the given key pair. The `public_key` has to match the `secret_key`.
+    pub fn new(secret_key: SecretKey, public_key: PublicKey) -> Self {
+        Self::new_with(secret_key, public_key, DefaultCryptoConfig)
+    }
+
+    /// Creates a signer for the given key pair, using the crypto providers of the `crypto_config`.
+    pub fn new_with<C>(secret_key: SecretKey, public_key: PublicKey, crypto_config: C) -> Self
+    where
+        C: CryptoConfig + Send + Sync + 'static,
+    {
+        Self {
+            secret_key,
+            public_key,
+            crypto_config: Arc::new(crypto_config),
+        }
+    }
+
+    fn operation_signer(&self) -> OperationSigner {
+        OperationSigner::new(Crypto::new(
+            self.crypto_config.get_ed25519_crypto_provider(),
+            self.crypto_config.get_secp256_k1_crypto_provider(),
+            self.crypto_config.get_p256_crypto_provider(),
+        ))
+    }
+}
+
+impl std::fmt::Debug for InMemorySigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InMemorySigner")
+            .field("secret_key", &"<redacted>")
+            .field("public_key", &self.public_key)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl Signer for InMemorySigner {
+    async fn public_key(&self) -> Result<PublicKey> {
+        Ok(self.public_key.clone())
+    }
+
+    async fn sign(&self, watermark: &[u8], bytes: &[u8]) -> Result<Signature> {
+        self.operation_signer()
+            .sign_watermarked(watermark, bytes, &self.secret_key)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tezos_core::Tezos;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_address() -> Result<()> {
+        let signer = InMemorySigner::new(
+            "edskRv7VyXGVZb8EsrR7D9XKUbbAQNQGtALP6QeB16ZCD7SmmJpzyeneJVg3Mq56YLbxRA1kSdAXiswwPiaVfR3NHGMCXCziuZ".try_into()?,
+            "edpkttZKC51wemRqL2QxwpMnEKxWnbd35pq47Y6xsCHp5M1f7LN8NP".try_into()?,
+        );
+        let expected: ImplicitAddress = "tz1RFxSHjxxyaaXyAvJG3uRQeJUCCm4jk2LX".try_into()?;
+
+        assert_eq!(signer.address().await?, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_debug_redacts_secret_key() -> Result<()> {
+        let signer = InMemorySigner::new(
+            "edskRv7VyXGVZb8EsrR7D9XKUbbAQNQGtALP6QeB16ZCD7SmmJpzyeneJVg3Mq56YLbxRA1kSdAXiswwPiaVfR3NHGMCXCziuZ".try_into()?,
+            "edpkttZKC51wemRqL2QxwpMnEKxWnbd35pq47Y6xsCHp5M1f7LN8NP".try_into()?,
+        );
+        let debug = format!("{:?}", signer);
+
+        assert!(!debug.contains("edsk"));
+        assert!(debug.contains("edpkttZKC51wemRqL2QxwpMnEKxWnbd35pq47Y6xsCHp5M1f7LN8NP"));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "ed25519")]
+    #[tokio::test]
+    async fn test_sign() -> Result<()> {
+        let signer = InMemorySigner::new(
+            "edskRv7VyXGVZb8EsrR7D9XKUbbAQNQGtALP6QeB16ZCD7SmmJpzyeneJVg3Mq56YLbxRA1kSdAXiswwPiaVfR3NHGMCXCziuZ".try_into()?,
+            "edpkttZKC51wemRqL2QxwpMnEKxWnbd35pq47Y6xsCHp5M1f7LN8NP".try_into()?,
+        );
+        let signature = signer.sign(&[0x05], &[0x01, 0x00]).await?;
+        let verifier = OperationSigner::new(Tezos::default().get_crypto());
+
+        assert!(verifier.verify_watermarked(
+            &[0x05],
+            &[0x01, 0x00],
+            &signature,
+            &signer.public_key().await?
+        )?);
+        assert!(!verifier.verify_watermarked(
+            &[0x03],
+            &[0x01, 0x00],
+            &signature,
+            &signer.public_key().await?
+        )?);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,446 @@
+use tezos_core::{
+    types::encoded::{ChainId, Encoded, PublicKey, SecretKey, Signature},
+    Tezos,
+};
+use tezos_michelson::micheline::Micheline;
+
+use crate::{
+    block_header::BlockHeader,
+    internal::signer::OperationSigner,
+    operations::{Operation, OperationContent, UnsignedOperation},
+    Error, Result,
+};
+
+/// The watermarks (magic bytes) prefixing the signed payloads, which prevent a signature
+/// of one kind of payload from being replayed as another.
+///
+/// The legacy `0x01` (block) and `0x02` (endorsement) watermarks of the protocols before Tenderbake
+/// are not supported.
+pub mod watermark {
+    pub const BLOCK: u8 = 0x11;
+    pub const PREATTESTATION: u8 = 0x12;
+    pub const ATTESTATION: u8 = 0x13;
+    pub const OPERATION: u8 = 0x03;
+    pub const MICHELINE: u8 = 0x05;
+}
+
+/// A payload that can be signed, together with its watermark.
+///
+/// Blocks and consensus operations are additionally bound to the chain they're produced on
+/// by the chain id following their watermark.
+///
+/// ```rust
+/// use tezos_michelson::micheline::{primitive_application, int, Micheline};
+/// use tezos_michelson::michelson::ComparableTypePrimitive;
+/// use tezos_operation::signing_payload::SigningPayload;
+///
+/// let schema: Micheline = primitive_application(ComparableTypePrimitive::Nat).into();
+/// let payload = SigningPayload::pack(int(10), Some(&schema)).expect("valid Micheline value");
+/// assert_eq!(payload.to_bytes().expect("valid payload"), vec![0x05, 0x00, 0x0a]);
+/// assert_eq!(SigningPayload::from_bytes(&[0x05, 0x00, 0x0a]).expect("valid bytes"), payload);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SigningPayload {
+    /// A block header, signed without its `signature`.
+    Block {
+        chain_id: ChainId,
+        header: BlockHeader,
+    },
+    /// A preattestation (preendorsement) operation.
+    Preattestation {
+        chain_id: ChainId,
+        operation: UnsignedOperation,
+    },
+    /// An attestation (endorsement) operation.
+    Attestation {
+        chain_id: ChainId,
+        operation: UnsignedOperation,
+    },
+    /// A manager or any other operation, e.g. a double baking evidence.
+    Operation(UnsignedOperation),
+    /// A packed Micheline value, without its watermark.
+    Micheline(Vec<u8>),
+}
+
+impl SigningPayload {
+    const CHAIN_ID_SIZE: usize = 4;
+    const SIGNATURE_SIZE: usize = 64;
+
+    /// Creates a [SigningPayload::Micheline] payload by packing the `value`, see [Micheline::pack].
+    pub fn pack(value: Micheline, schema: Option<&Micheline>) -> Result<Self> {
+        let bytes = value.pack(schema)?;
+
+        Ok(Self::Micheline(bytes[1..].to_vec()))
+    }
+
+    /// Decodes the payload from the `bytes` prefixed with their watermark.
+    ///
+    /// A decoded [SigningPayload::Block] header gets a zeroed signature, as the signed bytes don't contain one.
+    /// A preattestation or an attestation has to contain a single preendorsement or endorsement.
+    /// The legacy watermarks of the protocols before Tenderbake are rejected with [Error::InvalidBytes].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let (magic_byte, payload) = bytes.split_first().ok_or(Error::InvalidBytes)?;
+        match *magic_byte {
+            watermark::BLOCK => {
+                let (chain_id, header) = Self::split_chain_id(payload)?;
+                let header = BlockHeader::from_forged_bytes(
+                    [header, [0u8; Self::SIGNATURE_SIZE].as_slice()].concat(),
+                )?;
+
+                Ok(Self::Block { chain_id, header })
+            }
+            watermark::PREATTESTATION => {
+                let (chain_id, operation) = Self::split_chain_id(payload)?;
+                let operation = UnsignedOperation::from_forged_bytes(operation)?;
+                match operation.contents.as_slice() {
+                    [OperationContent::Preendorsement(_)] => Ok(Self::Preattestation {
+                        chain_id,
+                        operation,
+                    }),
+                    _ => Err(Error::InvalidBytes),
+                }
+            }
+            watermark::ATTESTATION => {
+                let (chain_id, operation) = Self::split_chain_id(payload)?;
+                let operation = UnsignedOperation::from_forged_bytes(operation)?;
+                match operation.contents.as_slice() {
+                    [OperationContent::Endorsement(_)] => Ok(Self::Attestation {
+                        chain_id,
+                        operation,
+                    }),
+                    _ => Err(Error::InvalidBytes),
+                }
+            }
+            watermark::OPERATION => Ok(Self::Operation(UnsignedOperation::from_forged_bytes(
+                payload,
+            )?)),
+            watermark::MICHELINE => Ok(Self::Micheline(payload.to_vec())),
+            _ => Err(Error::InvalidBytes),
+        }
+    }
+
+    /// Encodes the payload prefixed with its watermark, i.e. the bytes which are hashed and signed.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        Ok([self.watermark()?, self.payload_bytes()?].concat())
+    }
+
+    /// The watermark of the payload, followed by the chain id for blocks and consensus operations.
+    pub fn watermark(&self) -> Result<Vec<u8>> {
+        let watermark = [self.magic_byte()];
+        let bytes = match self.chain_id() {
+            Some(chain_id) => [watermark.as_slice(), &chain_id.to_bytes()?].concat(),
+            None => watermark.to_vec(),
+        };
+
+        Ok(bytes)
+    }
+
+    /// The first byte of the watermark, see the [watermark] constants.
+    pub fn magic_byte(&self) -> u8 {
+        match self {
+            Self::Block { .. } => watermark::BLOCK,
+            Self::Preattestation { .. } => watermark::PREATTESTATION,
+            Self::Attestation { .. } => watermark::ATTESTATION,
+            Self::Operation(_) => watermark::OPERATION,
+            Self::Micheline(_) => watermark::MICHELINE,
+        }
+    }
+
+    /// The chain id of a block or a consensus operation.
+    pub fn chain_id(&self) -> Option<&ChainId> {
+        match self {
+            Self::Block { chain_id, .. }
+            | Self::Preattestation { chain_id, .. }
+            | Self::Attestation { chain_id, .. } => Some(chain_id),
+            _ => None,
+        }
+    }
+
+    /// The level and the round of a block or a consensus operation.
+    ///
+    /// The round of a block is the last component of its Tenderbake fitness,
+    /// `None` is returned when it's missing or isn't a 4 bytes integer.
+    pub fn level_and_round(&self) -> Option<(i32, i32)> {
+        match self {
+            Self::Block { header, .. } => {
+                let round = header
+                    .fitness
+                    .last()
+                    .and_then(|round| <[u8; 4]>::try_from(round.to_bytes().as_slice()).ok())
+                    .map(i32::from_be_bytes)?;
+
+                Some((header.level, round))
+            }
+            Self::Preattestation { operation, .. } | Self::Attestation { operation, .. } => {
+                match operation.contents() {
+                    [OperationContent::Preendorsement(content)] => {
+                        Some((content.level, content.round))
+                    }
+                    [OperationContent::Endorsement(content)] => {
+                        Some((content.level, content.round))
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Unpacks a [SigningPayload::Micheline] payload, see [Micheline::unpack].
+    pub fn unpack(&self, schema: Option<&Micheline>) -> Result<Micheline> {
+        match self {
+            Self::Micheline(bytes) => Ok(Micheline::unpack(
+                &[[watermark::MICHELINE].as_slice(), bytes].concat(),
+                schema,
+            )?),
+            _ => Err(Error::InvalidBytes),
+        }
+    }
+
+    /// Signs the payload with the given secret key and using the crypto providers configured in the given [Tezos] instance.
+    pub fn sign_with(&self, key: &SecretKey, tezos: &Tezos) -> Result<Signature> {
+        let signer = OperationSigner::new(tezos.get_crypto());
+
+        signer.sign_watermarked(&self.watermark()?, &self.payload_bytes()?, key)
+    }
+
+    /// Signs the payload with the given secret key.
+    pub fn sign(&self, key: &SecretKey) -> Result<Signature> {
+        self.sign_with(key, &Default::default())
+    }
+
+    /// Signs the payload with the given [Signer](crate::signer::Signer).
+    pub async fn sign_using<S>(&self, signer: &S) -> Result<Signature>
+    where
+        S: crate::signer::Signer + ?Sized,
+    {
+        signer
+            .sign(&self.watermark()?, &self.payload_bytes()?)
+            .await
+    }
+
+    /// Verifies the `signature` of the payload with the given public key and using
+    /// the crypto providers configured in the given [Tezos] instance.
+    pub fn verify_with(
+        &self,
+        signature: &Signature,
+        key: &PublicKey,
+        tezos: &Tezos,
+    ) -> Result<bool> {
+        let signer = OperationSigner::new(tezos.get_crypto());
+
+        signer.verify_watermarked(&self.watermark()?, &self.payload_bytes()?, signature, key)
+    }
+
+    /// Verifies the `signature` of the payload with the given public key.
+    pub fn verify(&self, signature: &Signature, key: &PublicKey) -> Result<bool> {
+        self.verify_with(signature, key, &Default::default())
+    }
+
+    fn payload_bytes(&self) -> Result<Vec<u8>> {
+        match self {
+            Self::Block { header, .. } => header.to_unsigned_forged_bytes(),
+            Self::Preattestation { operation, .. }
+            | Self::Attestation { operation, .. }
+            | Self::Operation(operation) => operation.to_forged_bytes(),
+            Self::Micheline(bytes) => Ok(bytes.clone()),
+        }
+    }
+
+    fn split_chain_id(payload: &[u8]) -> Result<(ChainId, &[u8])> {
+        if payload.len() < Self::CHAIN_ID_SIZE {
+            return Err(Error::InvalidBytes);
+        }
+        let (chain_id, rest) = payload.split_at(Self::CHAIN_ID_SIZE);
+
+        Ok((ChainId::from_bytes(chain_id)?, rest))
+    }
+}
+
+impl From<UnsignedOperation> for SigningPayload {
+    fn from(value: UnsignedOperation) -> Self {
+        Self::Operation(value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tezos_michelson::micheline::{primitive_application, try_string};
+    use tezos_michelson::michelson::ComparableTypePrimitive;
+
+    use super::*;
+    use crate::operations::{Endorsement, Preendorsement, Transaction};
+
+    const CHAIN_ID: &str = "NetXdQprcVkpaWU";
+    const HEADER: &str = "0026370d0d9444f40d9c1824d30918fa20dcbc28261a8973d9a544f33f5cc56a13830189af0000000062c28ab804d7bfc960df8ce93f0b9356181c5651bf90ebcae5b2a2776b66aeeda0000f4229000000210000000102000000040026370d0000000000000004ffffffff000000040000000083e9c04c6f3c610e6b5c65b33805c24a6c1902b58bec02a4cc482ffca828b77ac4451b4e45ee2eae5d15652c8826ee011f22915ae1387dd605766c5ad1b5cf8c00000000cb9f439e02840100000214a92861740b16b11c8bb6791abdae138bf18f18a85915896a7d5da0b59fc0c3a16494f7832b05164f63f1d5797d3103256e5c2e1f0ddf3fca57e71ab8c4a56a";
+
+    fn message() -> Micheline {
+        try_string("Tezos Signed Message").unwrap()
+    }
+
+    fn consensus_operation(content: OperationContent) -> UnsignedOperation {
+        UnsignedOperation::new(
+            "BLyKu3tnc9NCuiFfCqfeVGPCoZTyW63dYh2XAYxkM7fQYKCqsju"
+                .try_into()
+                .unwrap(),
+            vec![content],
+        )
+    }
+
+    fn payloads() -> Vec<SigningPayload> {
+        let chain_id: ChainId = CHAIN_ID.try_into().unwrap();
+        let payload_hash = "vh32fG1tMNPtzZiKPHinfLPSAU3m2piFSgud4jBdaGSKJQH6q7Xd";
+        vec![
+            SigningPayload::Preattestation {
+                chain_id: chain_id.clone(),
+                operation: consensus_operation(
+                    Preendorsement::new(1, 2504461, 2, payload_hash.try_into().unwrap()).into(),
+                ),
+            },
+            SigningPayload::Attestation {
+                chain_id,
+                operation: consensus_operation(
+                    Endorsement::new(1, 2504461, 2, payload_hash.try_into().unwrap()).into(),
+                ),
+            },
+            UnsignedOperation::new(
+                "BLyKu3tnc9NCuiFfCqfeVGPCoZTyW63dYh2XAYxkM7fQYKCqsju"
+                    .try_into()
+                    .unwrap(),
+                vec![Transaction::new(
+                    "tz1PwXjsrgYBi9wpe3tFhazJpt7JMTVzBp5c".try_into().unwrap(),
+                    417u32.into(),
+                    2336132u32.into(),
+                    1527u32.into(),
+                    357u32.into(),
+                    1000u32.into(),
+                    "tz2AjVPbMHdDF1XwHVhUrTg6ZvqY83AYhJEy".try_into().unwrap(),
+                    None,
+                )
+                .into()],
+            )
+            .into(),
+            SigningPayload::pack(message(), None).unwrap(),
+        ]
+    }
+
+    #[test]
+    fn test_encode_decode() -> Result<()> {
+        for payload in payloads() {
+            let bytes = payload.to_bytes()?;
+            assert_eq!(bytes[0], payload.magic_byte());
+            assert_eq!(SigningPayload::from_bytes(&bytes)?, payload);
+        }
+
+        let chain_id: ChainId = CHAIN_ID.try_into()?;
+        let header = hex::decode(HEADER).unwrap();
+        let bytes = [
+            [watermark::BLOCK].as_slice(),
+            &chain_id.to_bytes()?,
+            &header[..header.len() - 64],
+        ]
+        .concat();
+        let payload = SigningPayload::from_bytes(&bytes)?;
+        assert_eq!(payload.chain_id(), Some(&chain_id));
+        assert_eq!(payload.level_and_round(), Some((2504461, 0)));
+        assert_eq!(payload.to_bytes()?, bytes);
+
+        let mut header = BlockHeader::from_forged_bytes(&header)?;
+        header.fitness.clear();
+        let payload = SigningPayload::Block { chain_id, header };
+        assert_eq!(payload.level_and_round(), None);
+
+        assert!(matches!(
+            SigningPayload::from_bytes(&[0x42, 0x00]),
+            Err(Error::InvalidBytes)
+        ));
+        assert!(matches!(
+            SigningPayload::from_bytes(&[]),
+            Err(Error::InvalidBytes)
+        ));
+        // the legacy block and endorsement watermarks
+        for magic_byte in [0x01, 0x02] {
+            assert!(matches!(
+                SigningPayload::from_bytes(&[magic_byte, 0x7a, 0x06, 0xa7, 0x70]),
+                Err(Error::InvalidBytes)
+            ));
+        }
+        // an attestation watermark with a preendorsement
+        let mut bytes = payloads()[0].to_bytes()?;
+        bytes[0] = watermark::ATTESTATION;
+        assert!(matches!(
+            SigningPayload::from_bytes(&bytes),
+            Err(Error::InvalidBytes)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_watermark() -> Result<()> {
+        let payloads = payloads();
+        assert_eq!(payloads[1].watermark()?, vec![0x13, 0x7a, 0x06, 0xa7, 0x70]);
+        assert_eq!(payloads[1].level_and_round(), Some((2504461, 2)));
+        assert_eq!(payloads[2].watermark()?, vec![0x03]);
+        assert_eq!(payloads[2].level_and_round(), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_pack_unpack() -> Result<()> {
+        let schema: Micheline = primitive_application(ComparableTypePrimitive::String).into();
+        let payload = SigningPayload::pack(message(), Some(&schema))?;
+
+        assert_eq!(payload.unpack(Some(&schema))?, message());
+        assert!(matches!(
+            payloads()[2].unpack(None),
+            Err(Error::InvalidBytes)
+        ));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "ed25519")]
+    #[test]
+    fn test_sign_verify() -> Result<()> {
+        let secret_key: SecretKey = "edskRv7VyXGVZb8EsrR7D9XKUbbAQNQGtALP6QeB16ZCD7SmmJpzyeneJVg3Mq56YLbxRA1kSdAXiswwPiaVfR3NHGMCXCziuZ".try_into()?;
+        let public_key: PublicKey =
+            "edpkttZKC51wemRqL2QxwpMnEKxWnbd35pq47Y6xsCHp5M1f7LN8NP".try_into()?;
+
+        let payloads = payloads();
+        for payload in &payloads {
+            let signature = payload.sign(&secret_key)?;
+            assert!(payload.verify(&signature, &public_key)?);
+        }
+
+        // a signature is bound to the watermark of its payload
+        let signature = payloads[1].sign(&secret_key)?;
+        assert!(!payloads[0].verify(&signature, &public_key)?);
+
+        // an operation payload is signed the same way as the operation itself
+        if let SigningPayload::Operation(operation) = &payloads[2] {
+            assert_eq!(payloads[2].sign(&secret_key)?, operation.sign(&secret_key)?);
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "ed25519")]
+    #[tokio::test]
+    async fn test_sign_using() -> Result<()> {
+        let signer = crate::signer::InMemorySigner::new(
+            "edskRv7VyXGVZb8EsrR7D9XKUbbAQNQGtALP6QeB16ZCD7SmmJpzyeneJVg3Mq56YLbxRA1kSdAXiswwPiaVfR3NHGMCXCziuZ".try_into()?,
+            "edpkttZKC51wemRqL2QxwpMnEKxWnbd35pq47Y6xsCHp5M1f7LN8NP".try_into()?,
+        );
+        let payload = &payloads()[1];
+        let signature = payload.sign_using(&signer).await?;
+
+        assert!(payload.verify(
+            &signature,
+            &crate::signer::Signer::public_key(&signer).await?
+        )?);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,299 @@
+use num_bigint::BigUint;
+use tezos_core::types::{
+    encoded::{Address, BlockHash, ImplicitAddress},
+    mutez::Mutez,
+    number::Nat,
+};
+use tezos_michelson::micheline::Micheline;
+
+use crate::{
+    operations::{Operation, OperationContent, OperationContentTag, UnsignedOperation},
+    Error, Result,
+};
+
+pub const COST_PER_BYTE: u32 = 250; // mutez
+
+/// A structured description of an [UnsignedOperation], meant to be presented to the user before signing.
+///
+/// The summary contains raw values only, the presentation (e.g. [Mutez::to_tez_string] for amounts
+/// or a translation of [OperationContentTag::name] for the kind) is left to the caller.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperationSummary {
+    pub branch: BlockHash,
+    pub contents: Vec<ContentSummary>,
+    /// The sum of the fees of all the contents.
+    pub total_fee: Mutez,
+    /// The sum of the amounts transferred or originated by all the contents.
+    pub total_amount: Mutez,
+    /// The maximum storage burn implied by the storage limits of all the contents.
+    pub total_storage_burn: Mutez,
+}
+
+impl OperationSummary {
+    /// The maximum amount the source can spend with the operation, i.e. the fees, amounts and maximum storage burn.
+    pub fn max_spend(&self) -> Result<Mutez> {
+        sum([self.total_fee, self.total_amount, self.total_storage_burn])
+    }
+}
+
+/// A structured description of a single [OperationContent].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContentSummary {
+    pub kind: OperationContentTag,
+    pub source: Option<ImplicitAddress>,
+    pub destination: Option<Address>,
+    /// The amount of a transaction or the initial balance of an origination.
+    pub amount: Option<Mutez>,
+    pub fee: Option<Mutez>,
+    pub gas_limit: Option<Nat>,
+    pub storage_limit: Option<Nat>,
+    pub entrypoint: Option<String>,
+    pub parameters: Option<Micheline>,
+    pub delegate: Option<ImplicitAddress>,
+    /// The maximum storage burn implied by `storage_limit`.
+    pub storage_burn: Option<Mutez>,
+}
+
+impl ContentSummary {
+    /// The maximum amount the source can spend with the content, i.e. its fee, amount and maximum storage burn.
+    pub fn max_spend(&self) -> Result<Mutez> {
+        sum([self.fee, self.amount, self.storage_burn]
+            .into_iter()
+            .flatten())
+    }
+}
+
+/// Creates an [OperationSummary] of an [UnsignedOperation].
+///
+/// ```rust
+/// use tezos_operation::{operations::{Transaction, UnsignedOperation}, summary::OperationSummarizer};
+///
+/// let operation = UnsignedOperation::new(
+///     "BMdhifZkcb5i9D6FnBi19SSBjft3sYaeKDAsEBgbsRLPTihQQJU".try_into().expect("valid conversion to BlockHash"),
+///     vec![
+///         Transaction::new(
+///             "tz1PwXjsrgYBi9wpe3tFhazJpt7JMTVzBp5c".try_into().expect("valid conversion to ImplicitAddress"),
+///             500u16.into(),
+///             1u8.into(),
+///             1500u16.into(),
+///             257u16.into(),
+///             1_000_000u32.into(),
+///             "tz2AjVPbMHdDF1XwHVhUrTg6ZvqY83AYhJEy".try_into().expect("valid conversion to ImplicitAddress"),
+///             None,
+///         ).into(),
+///     ]
+/// );
+/// let summary = OperationSummarizer::default().summarize(&operation).expect("valid summary");
+/// assert_eq!(summary.max_spend().expect("valid max spend").to_tez_string(), "1.06475");
+/// ```
+#[derive(Debug, Clone)]
+pub struct OperationSummarizer {
+    cost_per_byte: Mutez,
+}
+
+impl OperationSummarizer {
+    /// Creates a summarizer that uses the given storage `cost_per_byte` to compute the storage burn.
+    pub fn new(cost_per_byte: Mutez) -> Self {
+        Self { cost_per_byte }
+    }
+
+    pub fn summarize(&self, operation: &UnsignedOperation) -> Result<OperationSummary> {
+        let contents = operation
+            .contents()
+            .iter()
+            .map(|content| self.summarize_content(content))
+            .collect::<Result<Vec<_>>>()?;
+
+        let total_fee = sum(contents.iter().filter_map(|content| content.fee))?;
+        let total_amount = sum(contents.iter().filter_map(|content| content.amount))?;
+        let total_storage_burn = sum(contents.iter().filter_map(|content| content.storage_burn))?;
+
+        Ok(OperationSummary {
+            branch: operation.branch().clone(),
+            contents,
+            total_fee,
+            total_amount,
+            total_storage_burn,
+        })
+    }
+
+    pub fn summarize_content(&self, content: &OperationContent) -> Result<ContentSummary> {
+        let mut summary = ContentSummary {
+            kind: content.tag(),
+            source: content.source().cloned(),
+            destination: None,
+            amount: None,
+            fee: content.source().map(|_| content.fee()),
+            gas_limit: content.gas_limit().cloned(),
+            storage_limit: content.storage_limit().cloned(),
+            entrypoint: None,
+            parameters: None,
+            delegate: None,
+            storage_burn: content
+                .storage_limit()
+                .map(|storage_limit| self.storage_burn(storage_limit))
+                .transpose()?,
+        };
+
+        match content {
+            OperationContent::Transaction(value) => {
+                summary.destination = Some(value.destination.clone());
+                summary.amount = Some(value.amount);
+                if let Some(parameters) = &value.parameters {
+                    summary.entrypoint = Some(parameters.entrypoint.to_str().into());
+                    summary.parameters = Some(parameters.value.clone());
+                }
+            }
+            OperationContent::Origination(value) => {
+                summary.amount = Some(value.balance);
+                summary.delegate = value.delegate.clone();
+            }
+            OperationContent::Delegation(value) => {
+                summary.delegate = value.delegate.clone();
+            }
+            _ => {}
+        }
+
+        Ok(summary)
+    }
+
+    fn storage_burn(&self, storage_limit: &Nat) -> Result<Mutez> {
+        let cost_per_byte = BigUint::from(u64::try_from(self.cost_per_byte)?);
+        let burn = BigUint::from(storage_limit.clone()) * cost_per_byte;
+
+        Ok(burn.try_into()?)
+    }
+}
+
+impl Default for OperationSummarizer {
+    fn default() -> Self {
+        Self::new(COST_PER_BYTE.into())
+    }
+}
+
+fn sum<I: IntoIterator<Item = Mutez>>(values: I) -> Result<Mutez> {
+    values
+        .into_iter()
+        .try_fold(Mutez::default(), |total, value| {
+            total.checked_add(value).ok_or(Error::AmountOverflow)
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use tezos_michelson::michelson::data;
+
+    use super::*;
+    use crate::operations::{Delegation, Entrypoint, Parameters, Reveal, Transaction};
+
+    #[test]
+    fn test_summarize() -> Result<()> {
+        let operation = UnsignedOperation::new(
+            "BMdhifZkcb5i9D6FnBi19SSBjft3sYaeKDAsEBgbsRLPTihQQJU"
+                .try_into()
+                .unwrap(),
+            vec![
+                Reveal::new(
+                    "tz1PwXjsrgYBi9wpe3tFhazJpt7JMTVzBp5c".try_into().unwrap(),
+                    300u16.into(),
+                    1u8.into(),
+                    1000u16.into(),
+                    0u8.into(),
+                    "edpkttZKC51wemRqL2QxwpMnEKxWnbd35pq47Y6xsCHp5M1f7LN8NP"
+                        .try_into()
+                        .unwrap(),
+                )
+                .into(),
+                Transaction::new(
+                    "tz1PwXjsrgYBi9wpe3tFhazJpt7JMTVzBp5c".try_into().unwrap(),
+                    1000u16.into(),
+                    2u8.into(),
+                    5000u16.into(),
+                    100u8.into(),
+                    2_500_000u32.into(),
+                    "KT1HNqxFJxnmUcX8wF915wxxaAAU4ixYwVwe".try_into().unwrap(),
+                    Some(Parameters::new(
+                        Entrypoint::Named("mint".into()),
+                        data::int::<_, Micheline>(10),
+                    )),
+                )
+                .into(),
+                Delegation::new(
+                    "tz1PwXjsrgYBi9wpe3tFhazJpt7JMTVzBp5c".try_into().unwrap(),
+                    400u16.into(),
+                    3u8.into(),
+                    1000u16.into(),
+                    0u8.into(),
+                    Some("tz1gru9Tsz1X7GaYnsKR2YeGJLTVm4NwMhvb".try_into().unwrap()),
+                )
+                .into(),
+            ],
+        );
+
+        let summary = OperationSummarizer::default().summarize(&operation)?;
+
+        assert_eq!(summary.contents.len(), 3);
+        assert_eq!(summary.contents[0].kind, OperationContentTag::Reveal);
+        assert_eq!(summary.contents[0].kind.name(), "reveal");
+
+        let transaction = &summary.contents[1];
+        assert_eq!(transaction.kind, OperationContentTag::Transaction);
+        assert_eq!(
+            transaction.destination,
+            Some("KT1HNqxFJxnmUcX8wF915wxxaAAU4ixYwVwe".try_into().unwrap())
+        );
+        assert_eq!(transaction.amount, Some(2_500_000u32.into()));
+        assert_eq!(transaction.entrypoint.as_deref(), Some("mint"));
+        assert_eq!(transaction.parameters, Some(data::int::<_, Micheline>(10)));
+        assert_eq!(transaction.storage_burn, Some(25_000u16.into()));
+        assert_eq!(transaction.max_spend()?, 2_526_000u32.into());
+
+        assert_eq!(
+            summary.contents[2].delegate,
+            Some("tz1gru9Tsz1X7GaYnsKR2YeGJLTVm4NwMhvb".try_into().unwrap())
+        );
+
+        assert_eq!(summary.total_fee, 1700u16.into());
+        assert_eq!(summary.total_amount, 2_500_000u32.into());
+        assert_eq!(summary.total_storage_burn, 25_000u16.into());
+        assert_eq!(summary.max_spend()?, 2_526_700u32.into());
+        assert_eq!(summary.max_spend()?.to_tez_string(), "2.5267");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_summarize_overflow() -> Result<()> {
+        let transaction = |amount: Mutez| -> OperationContent {
+            Transaction::new(
+                "tz1PwXjsrgYBi9wpe3tFhazJpt7JMTVzBp5c".try_into().unwrap(),
+                1000u16.into(),
+                1u8.into(),
+                1500u16.into(),
+                0u8.into(),
+                amount,
+                "tz2AjVPbMHdDF1XwHVhUrTg6ZvqY83AYhJEy".try_into().unwrap(),
+                None,
+            )
+            .into()
+        };
+        let max = Mutez::try_from(i64::MAX as u64)?;
+        let summarizer = OperationSummarizer::default();
+
+        let operation = UnsignedOperation::new(
+            "BMdhifZkcb5i9D6FnBi19SSBjft3sYaeKDAsEBgbsRLPTihQQJU"
+                .try_into()
+                .unwrap(),
+            vec![transaction(max), transaction(max)],
+        );
+        assert!(matches!(
+            summarizer.summarize(&operation),
+            Err(Error::AmountOverflow)
+        ));
+
+        let content = summarizer.summarize_content(&transaction(max))?;
+        assert!(matches!(content.max_spend(), Err(Error::AmountOverflow)));
+
+        Ok(())
+    }
+}
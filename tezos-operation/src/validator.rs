@@ -0,0 +1,535 @@
+use std::fmt::Display;
+
+use num_bigint::BigUint;
+use tezos_core::types::{
+    encoded::{Encoded, ImplicitAddress},
+    mutez::Mutez,
+    number::Nat,
+};
+
+use crate::{
+    operations::{Operation, OperationContent, UnsignedOperation},
+    Error, Result,
+};
+
+pub const HARD_GAS_LIMIT_PER_OPERATION: u64 = 1040000;
+pub const HARD_GAS_LIMIT_PER_BLOCK: u64 = 5200000;
+pub const HARD_STORAGE_LIMIT_PER_OPERATION: u64 = 60000;
+pub const MAX_OPERATION_DATA_LENGTH: usize = 32768;
+pub const MINIMAL_FEES: u64 = 100; // mutez
+pub const MINIMAL_NANOTEZ_PER_BYTE: u64 = 1000;
+pub const MINIMAL_NANOTEZ_PER_GAS_UNIT: u64 = 100;
+
+const NANOTEZ_PER_MUTEZ: u64 = 1000;
+const SIGNATURE_BYTES_LENGTH: usize = 64;
+
+/// The protocol constants and mempool fee parameters an operation is validated against.
+///
+/// The default values match the constants of the Tezos mainnet and the default `octez-node` mempool filter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationLimits {
+    pub hard_gas_limit_per_operation: u64,
+    pub hard_gas_limit_per_block: u64,
+    pub hard_storage_limit_per_operation: u64,
+    pub max_operation_data_length: usize,
+    pub minimal_fees: u64,
+    pub minimal_nanotez_per_byte: u64,
+    pub minimal_nanotez_per_gas_unit: u64,
+}
+
+impl Default for ValidationLimits {
+    fn default() -> Self {
+        Self {
+            hard_gas_limit_per_operation: HARD_GAS_LIMIT_PER_OPERATION,
+            hard_gas_limit_per_block: HARD_GAS_LIMIT_PER_BLOCK,
+            hard_storage_limit_per_operation: HARD_STORAGE_LIMIT_PER_OPERATION,
+            max_operation_data_length: MAX_OPERATION_DATA_LENGTH,
+            minimal_fees: MINIMAL_FEES,
+            minimal_nanotez_per_byte: MINIMAL_NANOTEZ_PER_BYTE,
+            minimal_nanotez_per_gas_unit: MINIMAL_NANOTEZ_PER_GAS_UNIT,
+        }
+    }
+}
+
+/// A protocol rule violated by an operation. `index` refers to the position of the offending content.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationError {
+    SourceMismatch {
+        index: usize,
+        expected: ImplicitAddress,
+        found: ImplicitAddress,
+    },
+    CounterNotConsecutive {
+        index: usize,
+        expected: Nat,
+        found: Nat,
+    },
+    GasLimitExceeded {
+        index: usize,
+        gas_limit: Nat,
+        limit: u64,
+    },
+    StorageLimitExceeded {
+        index: usize,
+        storage_limit: Nat,
+        limit: u64,
+    },
+    BlockGasLimitExceeded {
+        gas_limit: Nat,
+        limit: u64,
+    },
+    InsufficientFee {
+        fee: Mutez,
+        minimal_fee: Mutez,
+    },
+    RevealNotFirst {
+        index: usize,
+    },
+    OperationDataTooLarge {
+        size: usize,
+        limit: usize,
+    },
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SourceMismatch {
+                index,
+                expected,
+                found,
+            } => write!(
+                f,
+                "content {}: source {} differs from {}",
+                index,
+                found.value(),
+                expected.value()
+            ),
+            Self::CounterNotConsecutive {
+                index,
+                expected,
+                found,
+            } => write!(
+                f,
+                "content {}: counter {} is not consecutive, expected {}",
+                index, found, expected
+            ),
+            Self::GasLimitExceeded {
+                index,
+                gas_limit,
+                limit,
+            } => write!(
+                f,
+                "content {}: gas limit {} exceeds the operation limit {}",
+                index, gas_limit, limit
+            ),
+            Self::StorageLimitExceeded {
+                index,
+                storage_limit,
+                limit,
+            } => write!(
+                f,
+                "content {}: storage limit {} exceeds the operation limit {}",
+                index, storage_limit, limit
+            ),
+            Self::BlockGasLimitExceeded { gas_limit, limit } => write!(
+                f,
+                "total gas limit {} exceeds the block limit {}",
+                gas_limit, limit
+            ),
+            Self::InsufficientFee { fee, minimal_fee } => write!(
+                f,
+                "total fee {} is below the minimal fee {}",
+                String::from(*fee),
+                String::from(*minimal_fee)
+            ),
+            Self::RevealNotFirst { index } => {
+                write!(f, "content {}: reveal must be the first content", index)
+            }
+            Self::OperationDataTooLarge { size, limit } => write!(
+                f,
+                "operation data size {} exceeds the limit {}",
+                size, limit
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationErrors(Vec<ValidationError>);
+
+impl ValidationErrors {
+    pub fn errors(&self) -> &[ValidationError] {
+        &self.0
+    }
+}
+
+impl From<Vec<ValidationError>> for ValidationErrors {
+    fn from(value: Vec<ValidationError>) -> Self {
+        Self(value)
+    }
+}
+
+impl From<ValidationErrors> for Vec<ValidationError> {
+    fn from(value: ValidationErrors) -> Self {
+        value.0
+    }
+}
+
+impl Display for ValidationErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut first = true;
+        for item in &self.0 {
+            if !first {
+                write!(f, ", {}", item)?;
+            } else {
+                write!(f, "{}", item)?;
+            }
+            first = false;
+        }
+        Ok(())
+    }
+}
+
+/// Checks an [UnsignedOperation] against the protocol rules offline, before it is sent to a node.
+///
+/// ```rust
+/// use tezos_operation::{operations::{Transaction, UnsignedOperation}, validator::OperationValidator};
+///
+/// let operation = UnsignedOperation::new(
+///     "BMdhifZkcb5i9D6FnBi19SSBjft3sYaeKDAsEBgbsRLPTihQQJU".try_into().expect("valid conversion to BlockHash"),
+///     vec![
+///         Transaction::new(
+///             "tz1PwXjsrgYBi9wpe3tFhazJpt7JMTVzBp5c".try_into().expect("valid conversion to ImplicitAddress"),
+///             0u8.into(), // fee: too low
+///             1u8.into(),
+///             1500u16.into(),
+///             0u8.into(),
+///             1000u16.into(),
+///             "tz2AjVPbMHdDF1XwHVhUrTg6ZvqY83AYhJEy".try_into().expect("valid conversion to ImplicitAddress"),
+///             None,
+///         ).into(),
+///     ]
+/// );
+/// let validator = OperationValidator::default();
+/// assert!(validator.validate(&operation).is_err());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct OperationValidator {
+    limits: ValidationLimits,
+}
+
+impl OperationValidator {
+    pub fn new(limits: ValidationLimits) -> Self {
+        Self { limits }
+    }
+
+    pub fn limits(&self) -> &ValidationLimits {
+        &self.limits
+    }
+
+    /// Validates the `operation`, failing with [Error::Validation] that lists every violated rule.
+    pub fn validate(&self, operation: &UnsignedOperation) -> Result<()> {
+        let errors = self.validation_errors(operation)?;
+        if errors.is_empty() {
+            return Ok(());
+        }
+
+        Err(Error::Validation(errors.into()))
+    }
+
+    /// Returns all the protocol rules violated by the `operation`.
+    pub fn validation_errors(&self, operation: &UnsignedOperation) -> Result<Vec<ValidationError>> {
+        let mut errors = vec![];
+
+        self.check_manager_contents(operation, &mut errors)?;
+        self.check_reveal(operation, &mut errors);
+        let size = self.check_size(operation, &mut errors)?;
+        self.check_fee(operation, size, &mut errors)?;
+
+        Ok(errors)
+    }
+
+    fn check_manager_contents(
+        &self,
+        operation: &UnsignedOperation,
+        errors: &mut Vec<ValidationError>,
+    ) -> Result<()> {
+        let mut expected_source: Option<&ImplicitAddress> = None;
+        let mut expected_counter: Option<BigUint> = None;
+        let mut total_gas_limit = BigUint::from(0u8);
+
+        for (index, content) in operation.contents().iter().enumerate() {
+            let (source, counter, gas_limit, storage_limit) = match (
+                content.source(),
+                content.counter(),
+                content.gas_limit(),
+                content.storage_limit(),
+            ) {
+                (Some(source), Some(counter), Some(gas_limit), Some(storage_limit)) => {
+                    (source, counter, gas_limit, storage_limit)
+                }
+                _ => continue,
+            };
+
+            match expected_source {
+                Some(expected) if expected != source => {
+                    errors.push(ValidationError::SourceMismatch {
+                        index,
+                        expected: expected.clone(),
+                        found: source.clone(),
+                    })
+                }
+                Some(_) => {}
+                None => expected_source = Some(source),
+            }
+
+            let counter_value: BigUint = counter.clone().into();
+            if let Some(expected) = expected_counter.as_ref() {
+                if expected != &counter_value {
+                    errors.push(ValidationError::CounterNotConsecutive {
+                        index,
+                        expected: expected.clone().into(),
+                        found: counter.clone(),
+                    })
+                }
+            }
+            expected_counter = Some(counter_value + 1u8);
+
+            let gas_limit_value: BigUint = gas_limit.clone().into();
+            if gas_limit_value > self.limits.hard_gas_limit_per_operation.into() {
+                errors.push(ValidationError::GasLimitExceeded {
+                    index,
+                    gas_limit: gas_limit.clone(),
+                    limit: self.limits.hard_gas_limit_per_operation,
+                })
+            }
+            total_gas_limit += gas_limit_value;
+
+            let storage_limit_value: BigUint = storage_limit.clone().into();
+            if storage_limit_value > self.limits.hard_storage_limit_per_operation.into() {
+                errors.push(ValidationError::StorageLimitExceeded {
+                    index,
+                    storage_limit: storage_limit.clone(),
+                    limit: self.limits.hard_storage_limit_per_operation,
+                })
+            }
+        }
+
+        if total_gas_limit > self.limits.hard_gas_limit_per_block.into() {
+            errors.push(ValidationError::BlockGasLimitExceeded {
+                gas_limit: total_gas_limit.into(),
+                limit: self.limits.hard_gas_limit_per_block,
+            })
+        }
+
+        Ok(())
+    }
+
+    fn check_reveal(&self, operation: &UnsignedOperation, errors: &mut Vec<ValidationError>) {
+        operation
+            .contents()
+            .iter()
+            .enumerate()
+            .skip(1)
+            .filter(|(_, content)| matches!(content, OperationContent::Reveal(_)))
+            .for_each(|(index, _)| errors.push(ValidationError::RevealNotFirst { index }));
+    }
+
+    /// Checks the size of the operation data, i.e. the forged contents and the signature without the branch.
+    fn check_size(
+        &self,
+        operation: &UnsignedOperation,
+        errors: &mut Vec<ValidationError>,
+    ) -> Result<usize> {
+        let contents_size = operation
+            .contents()
+            .iter()
+            .try_fold(0, |size, content| -> Result<usize> {
+                Ok(size + content.to_forged_bytes()?.len())
+            })?;
+        let size = contents_size + SIGNATURE_BYTES_LENGTH;
+        if size > self.limits.max_operation_data_length {
+            errors.push(ValidationError::OperationDataTooLarge {
+                size,
+                limit: self.limits.max_operation_data_length,
+            })
+        }
+
+        Ok(size)
+    }
+
+    fn check_fee(
+        &self,
+        operation: &UnsignedOperation,
+        size: usize,
+        errors: &mut Vec<ValidationError>,
+    ) -> Result<()> {
+        let manager_contents = operation
+            .contents()
+            .iter()
+            .filter(|content| content.gas_limit().is_some())
+            .collect::<Vec<_>>();
+        if manager_contents.is_empty() {
+            return Ok(());
+        }
+
+        let gas_limit = manager_contents
+            .iter()
+            .filter_map(|content| content.gas_limit())
+            .fold(BigUint::from(0u8), |total, gas_limit| {
+                total + BigUint::from(gas_limit.clone())
+            });
+        let fee = manager_contents
+            .iter()
+            .try_fold(BigUint::from(0u8), |total, content| -> Result<BigUint> {
+                Ok(total + u64::try_from(content.fee())?)
+            })?;
+
+        let nanotez = BigUint::from(self.limits.minimal_nanotez_per_byte) * size
+            + BigUint::from(self.limits.minimal_nanotez_per_gas_unit) * gas_limit;
+        let minimal_fee = BigUint::from(self.limits.minimal_fees)
+            + (nanotez + (NANOTEZ_PER_MUTEZ - 1)) / NANOTEZ_PER_MUTEZ;
+
+        if fee < minimal_fee {
+            errors.push(ValidationError::InsufficientFee {
+                fee: fee.try_into()?,
+                minimal_fee: minimal_fee.try_into()?,
+            })
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::operations::{Reveal, Transaction};
+
+    use super::*;
+
+    fn transaction(counter: u32, fee: u32, gas_limit: u32, storage_limit: u32) -> OperationContent {
+        Transaction::new(
+            "tz1PwXjsrgYBi9wpe3tFhazJpt7JMTVzBp5c".try_into().unwrap(),
+            fee.into(),
+            counter.into(),
+            gas_limit.into(),
+            storage_limit.into(),
+            1000u16.into(),
+            "tz2AjVPbMHdDF1XwHVhUrTg6ZvqY83AYhJEy".try_into().unwrap(),
+            None,
+        )
+        .into()
+    }
+
+    fn reveal(counter: u32) -> OperationContent {
+        Reveal::new(
+            "tz1PwXjsrgYBi9wpe3tFhazJpt7JMTVzBp5c".try_into().unwrap(),
+            1000u32.into(),
+            counter.into(),
+            1000u32.into(),
+            0u8.into(),
+            "edpkttZKC51wemRqL2QxwpMnEKxWnbd35pq47Y6xsCHp5M1f7LN8NP"
+                .try_into()
+                .unwrap(),
+        )
+        .into()
+    }
+
+    fn operation(contents: Vec<OperationContent>) -> UnsignedOperation {
+        UnsignedOperation::new(
+            "BMdhifZkcb5i9D6FnBi19SSBjft3sYaeKDAsEBgbsRLPTihQQJU"
+                .try_into()
+                .unwrap(),
+            contents,
+        )
+    }
+
+    #[test]
+    fn test_valid_operation() -> Result<()> {
+        let operation = operation(vec![reveal(10), transaction(11, 1000, 1500, 0)]);
+        OperationValidator::default().validate(&operation)
+    }
+
+    #[test]
+    fn test_invalid_operation() -> Result<()> {
+        let mut other_source = transaction(13, 1000, 1500, 0);
+        if let OperationContent::Transaction(value) = &mut other_source {
+            value.source = "tz1gru9Tsz1X7GaYnsKR2YeGJLTVm4NwMhvb".try_into().unwrap();
+        }
+        let operation = operation(vec![
+            transaction(10, 1000, 1500, 0),
+            reveal(12),
+            other_source,
+            transaction(14, 1000, 1_040_001, 60_001),
+        ]);
+
+        let errors = OperationValidator::default().validation_errors(&operation)?;
+
+        assert_eq!(
+            errors,
+            vec![
+                ValidationError::CounterNotConsecutive {
+                    index: 1,
+                    expected: 11u8.into(),
+                    found: 12u8.into(),
+                },
+                ValidationError::SourceMismatch {
+                    index: 2,
+                    expected: "tz1PwXjsrgYBi9wpe3tFhazJpt7JMTVzBp5c".try_into().unwrap(),
+                    found: "tz1gru9Tsz1X7GaYnsKR2YeGJLTVm4NwMhvb".try_into().unwrap(),
+                },
+                ValidationError::GasLimitExceeded {
+                    index: 3,
+                    gas_limit: 1_040_001u32.into(),
+                    limit: HARD_GAS_LIMIT_PER_OPERATION,
+                },
+                ValidationError::StorageLimitExceeded {
+                    index: 3,
+                    storage_limit: 60_001u32.into(),
+                    limit: HARD_STORAGE_LIMIT_PER_OPERATION,
+                },
+                ValidationError::RevealNotFirst { index: 1 },
+                ValidationError::InsufficientFee {
+                    fee: 4000u32.into(),
+                    minimal_fee: 104_788u32.into(),
+                },
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_block_limits() -> Result<()> {
+        let operation = operation(vec![transaction(1, 1000, 1500, 0)]);
+        let validator = OperationValidator::new(ValidationLimits {
+            hard_gas_limit_per_block: 1000,
+            max_operation_data_length: 64,
+            minimal_fees: 0,
+            ..Default::default()
+        });
+
+        let result = validator.validate(&operation);
+        let errors: Vec<ValidationError> = match result {
+            Err(Error::Validation(errors)) => errors.into(),
+            _ => panic!("expected validation errors"),
+        };
+
+        assert_eq!(
+            errors,
+            vec![
+                ValidationError::BlockGasLimitExceeded {
+                    gas_limit: 1500u16.into(),
+                    limit: 1000,
+                },
+                ValidationError::OperationDataTooLarge {
+                    size: 117,
+                    limit: 64,
+                },
+            ]
+        );
+
+        Ok(())
+    }
+}
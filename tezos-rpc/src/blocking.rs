@@ -0,0 +1,306 @@
+//! A blocking client, to call the RPCs from synchronous code without an async runtime.
+//!
+//! The [TezosRpc] of this module exposes the same request builders as [client::TezosRpc],
+//! but sends the requests with the blocking client of [reqwest]: the futures returned by `send()`
+//! complete on their first poll and [Wait::wait] returns their result directly on the current thread.
+//! The blocking client must not be used from within an async runtime.
+//!
+//! ```rust
+//! use tezos_rpc::{blocking::{TezosRpc, Wait}, Result};
+//!
+//! fn example() -> Result<()> {
+//!     let rpc = TezosRpc::new_rpc("https://testnet-tezos.giganode.io".into());
+//!     let block = rpc.get_block().send().wait()?;
+//!     let balance = rpc
+//!         .get_contract_balance(&"tz1gru9Tsz1X7GaYnsKR2YeGJLTVm4NwMhvb".try_into()?)
+//!         .send()
+//!         .wait()?;
+//!     Ok(())
+//! }
+//! ```
+
+use std::{future::Future, io::Read};
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use reqwest::{
+    blocking::{Client, RequestBuilder, Response},
+    header::RETRY_AFTER,
+    Method, StatusCode,
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    client,
+    http::{resilient, ByteStream, Http, HttpStream},
+    models::error::RpcError,
+    Error, Result,
+};
+
+/// The size of the chunks read from the body of a streaming response.
+const CHUNK_SIZE: usize = 8 * 1024;
+
+/// A Tezos RPC client sending its requests with the blocking [HttpClient].
+///
+/// ```rust
+/// use tezos_rpc::blocking::TezosRpc;
+///
+/// let client = TezosRpc::new_rpc("https://tezos-node.prod.gke.papers.tech".into());
+/// ```
+pub type TezosRpc = client::TezosRpc<HttpClient>;
+
+/// Runs a future to completion on the current thread, e.g. the result of a request builder's `send()`.
+pub trait Wait: Future {
+    /// Blocks the current thread until the future completes and returns its output.
+    fn wait(self) -> Self::Output
+    where
+        Self: Sized,
+    {
+        futures::executor::block_on(self)
+    }
+}
+
+impl<F: Future> Wait for F {}
+
+/// An [Http] client sending its requests with the blocking client of [reqwest].
+#[derive(Debug, Clone)]
+pub struct HttpClient {
+    rpc_endpoint: String,
+    client: Client,
+    headers: Vec<(String, String)>,
+}
+
+impl HttpClient {
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.rpc_endpoint, path)
+    }
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        self.headers.iter().fold(
+            self.client.request(method, self.url(path)),
+            |request, (name, value)| request.header(name, value),
+        )
+    }
+
+    fn handle_response<T: DeserializeOwned>(&self, response: Response) -> Result<T> {
+        Ok(self.check_status(response)?.json()?)
+    }
+
+    fn check_status(&self, response: Response) -> Result<Response> {
+        let status = response.status();
+        if status == StatusCode::TOO_MANY_REQUESTS || status == StatusCode::SERVICE_UNAVAILABLE {
+            return Err(Error::ServiceUnavailable {
+                status: status.as_u16(),
+                retry_after: response
+                    .headers()
+                    .get(RETRY_AFTER)
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(resilient::parse_retry_after),
+            });
+        }
+        if status != 200 {
+            // Do not parse JSON when the content type is `plain/text`
+            if response.headers()["content-type"] == "application/json" {
+                let errors: Vec<RpcError> = response.json()?;
+                return Err(Error::RpcErrors(errors.into()));
+            }
+            return Err(Error::RpcErrorPlain {
+                description: response.text()?,
+            });
+        }
+
+        Ok(response)
+    }
+}
+
+#[async_trait]
+impl Http for HttpClient {
+    /// Creates a blocking Http client that will be used to send requests to the specified node.
+    fn new(rpc_endpoint: String) -> Self {
+        Self {
+            rpc_endpoint,
+            client: Client::new(),
+            headers: vec![],
+        }
+    }
+
+    fn change_rpc_endpoint(&mut self, rpc_endpoint: String) {
+        self.rpc_endpoint = rpc_endpoint;
+    }
+
+    /// Returns a copy of the client which additionally sends the `headers` with each request.
+    fn with_headers(&self, headers: &[(String, String)]) -> Option<Self> {
+        let mut client = self.clone();
+        client.headers.extend_from_slice(headers);
+
+        Some(client)
+    }
+
+    /// Convenience method to make a `GET` request to a URL.
+    async fn get<T: DeserializeOwned>(&self, url: &str) -> Result<T> {
+        self.handle_response(self.request(Method::GET, url).send()?)
+    }
+
+    /// Convenience method to make a `GET` request with query parameters to a URL.
+    async fn get_with_query<T: DeserializeOwned, Q: Serialize + ?Sized + Sync>(
+        &self,
+        url: &str,
+        query: &Q,
+    ) -> Result<T> {
+        self.handle_response(self.request(Method::GET, url).query(query).send()?)
+    }
+
+    /// Convenience method to make a `POST` request to a URL.
+    async fn post<B: Serialize + Sync, T: DeserializeOwned, Q: Serialize + Sync>(
+        &self,
+        url: &str,
+        body: &B,
+        query: Option<&Q>,
+    ) -> Result<T> {
+        self.handle_response(
+            self.request(Method::POST, url)
+                .query(&query)
+                .json(body)
+                .send()?,
+        )
+    }
+
+    /// Convenience method to make a `PATCH` request to a URL.
+    async fn patch<B: Serialize + Sync, T: DeserializeOwned>(
+        &self,
+        url: &str,
+        body: Option<&B>,
+    ) -> Result<T> {
+        let mut req = self.request(Method::PATCH, url);
+
+        if let Some(json) = body {
+            req = req.json(json);
+        }
+
+        self.handle_response(req.send()?)
+    }
+
+    /// Convenience method to make a `DELETE` request to a URL.
+    async fn delete<B: Serialize + Sync, T: DeserializeOwned>(
+        &self,
+        url: &str,
+        body: Option<&B>,
+    ) -> Result<T> {
+        let mut req = self.request(Method::DELETE, url);
+
+        if let Some(json) = body {
+            req = req.json(json);
+        }
+
+        self.handle_response(req.send()?)
+    }
+}
+
+#[async_trait]
+impl HttpStream for HttpClient {
+    /// Opens a streaming `GET` request with query parameters to a URL.
+    ///
+    /// Polling the returned stream blocks the current thread until the next chunk is received.
+    async fn get_stream<Q: Serialize + ?Sized + Sync>(
+        &self,
+        url: &str,
+        query: &Q,
+    ) -> Result<ByteStream> {
+        let response = self.check_status(self.request(Method::GET, url).query(query).send()?)?;
+
+        Ok(
+            futures::stream::unfold(Some(response), |response| async move {
+                let mut response = response?;
+                let mut chunk = vec![0; CHUNK_SIZE];
+                match response.read(&mut chunk) {
+                    Ok(0) => None,
+                    Ok(size) => {
+                        chunk.truncate(size);
+                        Some((Ok(chunk), Some(response)))
+                    }
+                    Err(error) => Some((Err(error.into()), None)),
+                }
+            })
+            .boxed(),
+        )
+    }
+}
+
+#[cfg(all(test, feature = "blocking"))]
+mod tests {
+    use futures::TryStreamExt;
+    use httpmock::prelude::*;
+
+    use super::*;
+    use crate::models::block::BlockId;
+
+    #[test]
+    fn test_get_block_without_runtime() -> Result<()> {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET)
+                .path("/chains/main/blocks/2504461")
+                .header("authorization", "Bearer token");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(include_str!(
+                    "protocol_rpc/block/__TEST_DATA__/block_jakarta.json"
+                ));
+        });
+
+        let http_client = HttpClient::new(server.base_url())
+            .with_headers(&[("authorization".into(), "Bearer token".into())])
+            .ok_or(Error::HeadersNotSupported)?;
+        let rpc = TezosRpc::new_rpc_with_http_client(http_client, Default::default());
+        let block = rpc
+            .get_block()
+            .block_id(&BlockId::Level(2504461))
+            .send()
+            .wait()?;
+        assert_eq!(block.header.level, 2504461);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_inject_operation_error() -> Result<()> {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(POST).path("/injection/operation");
+            then.status(500)
+                .header("content-type", "application/json")
+                .body(r#"[{ "kind": "temporary", "id": "proto.013-PtJakart.contract.counter_in_the_past" }]"#);
+        });
+
+        let rpc = TezosRpc::new_rpc(server.base_url());
+        let result = rpc.inject_operation("00").send().wait();
+        assert!(
+            matches!(result, Err(Error::RpcErrors(errors)) if errors.errors()[0].id.ends_with("counter_in_the_past"))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_stream() -> Result<()> {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET).path("/monitor/heads/main");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body("{\"level\":1}\n{\"level\":2}\n");
+        });
+
+        let rpc = TezosRpc::new_rpc(server.base_url());
+        let chunks = rpc
+            .context()
+            .http_client()
+            .get_stream("/monitor/heads/main", &())
+            .wait()?
+            .try_collect::<Vec<_>>()
+            .wait()?;
+        assert_eq!(chunks.concat(), b"{\"level\":1}\n{\"level\":2}\n");
+
+        Ok(())
+    }
+}
@@ -1,17 +1,27 @@
-use tezos_core::types::encoded::{Address, BlockHash, ChainId, Encoded, ScriptExprHash};
-use tezos_operation::operations::UnsignedOperation;
+use tezos_core::{
+    types::encoded::{
+        Address, BlockHash, ChainId, Encoded, OperationHash, PublicKey, ScriptExprHash,
+    },
+    Tezos,
+};
+use tezos_operation::{
+    operations::{Operation as _, OperationContent, SignedOperation, UnsignedOperation},
+    signer::Signer,
+};
 
 #[cfg(feature = "http")]
 use crate::http::default::HttpClient;
 use crate::{
-    http::Http,
+    http::{Http, HttpStream},
+    inclusion::InclusionRequestBuilder,
     internal::estimator::{FeeEstimator, OperationFeeEstimator},
     models::limits::Limits,
-    Result,
+    Error, Result,
 };
 
 use {
-    crate::models::operation::Operation, crate::protocol_rpc, crate::shell_rpc,
+    crate::models::mempool::MempoolFilter, crate::models::operation::Operation,
+    crate::protocol_rpc, crate::shell_rpc,
     crate::shell_rpc::injection::block::InjectionBlockPayload,
 };
 
@@ -161,6 +171,89 @@ impl<HttpClient: Http> TezosRpc<HttpClient> {
             context: TezosRpcContext::new(chain_id, HttpClient::new(rpc_endpoint)),
         }
     }
+
+    /// Creates a Tezos RPC client that will send its requests through the provided `http_client`,
+    /// e.g. a [ResilientHttp](crate::http::resilient::ResilientHttp) configured with several endpoints.
+    pub fn new_rpc_with_http_client(http_client: HttpClient, chain_id: TezosRpcChainId) -> Self {
+        Self {
+            context: TezosRpcContext::new(chain_id, http_client),
+        }
+    }
+
+    /// Verifies the signature of the `operation` against the public key of its source.
+    ///
+    /// The public key is taken from the operation's `Reveal` content if present,
+    /// otherwise the source's manager key is fetched from the node.
+    pub async fn verify_operation(&self, operation: &SignedOperation) -> Result<bool> {
+        self.verify_operation_with(operation, &Default::default())
+            .await
+    }
+
+    /// Verifies the signature of the `operation` against the public key of its source,
+    /// using the crypto primitives configured in `tezos`.
+    ///
+    /// The public key is taken from the operation's `Reveal` content if present,
+    /// otherwise the source's manager key is fetched from the node.
+    pub async fn verify_operation_with(
+        &self,
+        operation: &SignedOperation,
+        tezos: &Tezos,
+    ) -> Result<bool> {
+        let public_key = self.operation_public_key(operation).await?;
+
+        Ok(operation.verify_with(&public_key, tezos)?)
+    }
+
+    /// Signs the `operation` with the `signer` and injects it into the node.
+    ///
+    /// Returns the hash of the injected operation.
+    pub async fn sign_and_inject_operation<S>(
+        &self,
+        operation: UnsignedOperation,
+        signer: &S,
+    ) -> Result<OperationHash>
+    where
+        S: Signer + ?Sized,
+    {
+        let signed = operation.into_signed_operation_using(signer).await?;
+
+        self.inject_operation(&signed.to_injectable_string()?)
+            .send()
+            .await
+    }
+
+    /// Waits for the operation to be included in a block, optionally followed by a number of confirmations.
+    ///
+    /// See [InclusionRequestBuilder](crate::inclusion::InclusionRequestBuilder) for the available options.
+    pub fn wait_for_inclusion<'a>(
+        &'a self,
+        operation_hash: &'a OperationHash,
+    ) -> InclusionRequestBuilder<'a, HttpClient> {
+        InclusionRequestBuilder::new(self, operation_hash)
+    }
+
+    async fn operation_public_key(&self, operation: &SignedOperation) -> Result<PublicKey> {
+        let revealed_key = operation.contents.iter().find_map(|content| match content {
+            OperationContent::Reveal(reveal) => Some(reveal.public_key.clone()),
+            _ => None,
+        });
+        if let Some(public_key) = revealed_key {
+            return Ok(public_key);
+        }
+
+        let source: Address = operation
+            .source()
+            .ok_or(Error::OperationNotSupported)?
+            .clone()
+            .into();
+        let manager_key = self
+            .get_contract_manager_key(&source)
+            .send()
+            .await?
+            .ok_or(Error::ManagerKeyNotRevealed)?;
+
+        Ok(manager_key.try_into()?)
+    }
 }
 
 // Tezos protocol-independent RPCs
@@ -250,6 +343,64 @@ impl<HttpClient: Http> TezosRpc<HttpClient> {
         shell_rpc::chains::chain::levels::savepoint::get(&self.context)
     }
 
+    /// Get the operations of the mempool, classified as validated, refused, outdated, branch refused,
+    /// branch delayed or unprocessed.
+    ///
+    /// [`GET /chains/<chain_id>/mempool/pending_operations?[version=<int>]&[validated]&[refused]&[outdated]&[branch_refused]&[branch_delayed]`](https://tezos.gitlab.io/active/rpc.html#get-chains-chain-id-mempool-pending-operations)
+    pub fn get_pending_operations(
+        &self,
+    ) -> shell_rpc::chains::chain::mempool::pending_operations::RpcRequestBuilder<HttpClient> {
+        shell_rpc::chains::chain::mempool::pending_operations::get(&self.context)
+    }
+
+    /// Get the configuration of the mempool filter.
+    ///
+    /// [`GET /chains/<chain_id>/mempool/filter?[include_default]`](https://tezos.gitlab.io/active/rpc.html#get-chains-chain-id-mempool-filter)
+    pub fn get_mempool_filter(
+        &self,
+    ) -> shell_rpc::chains::chain::mempool::filter::GetRPCRequestBuilder<HttpClient> {
+        shell_rpc::chains::chain::mempool::filter::get(&self.context)
+    }
+
+    /// Set the configuration of the mempool filter. The fields left to `None` are reset to their default value.
+    ///
+    /// [`POST /chains/<chain_id>/mempool/filter`](https://tezos.gitlab.io/active/rpc.html#post-chains-chain-id-mempool-filter)
+    pub fn set_mempool_filter<'a>(
+        &'a self,
+        filter: &'a MempoolFilter,
+    ) -> shell_rpc::chains::chain::mempool::filter::PostRPCRequestBuilder<HttpClient> {
+        shell_rpc::chains::chain::mempool::filter::post(&self.context, filter)
+    }
+
+    /// Remove an operation from the mempool if present, and ban it from being added to the mempool again.
+    ///
+    /// [`POST /chains/<chain_id>/mempool/ban_operation`](https://tezos.gitlab.io/active/rpc.html#post-chains-chain-id-mempool-ban-operation)
+    pub fn ban_operation<'a>(
+        &'a self,
+        operation_hash: &'a OperationHash,
+    ) -> shell_rpc::chains::chain::mempool::ban_operation::RpcRequestBuilder<HttpClient> {
+        shell_rpc::chains::chain::mempool::ban_operation::post(&self.context, operation_hash)
+    }
+
+    /// Remove an operation from the set of banned operations.
+    ///
+    /// [`POST /chains/<chain_id>/mempool/unban_operation`](https://tezos.gitlab.io/active/rpc.html#post-chains-chain-id-mempool-unban-operation)
+    pub fn unban_operation<'a>(
+        &'a self,
+        operation_hash: &'a OperationHash,
+    ) -> shell_rpc::chains::chain::mempool::unban_operation::RpcRequestBuilder<HttpClient> {
+        shell_rpc::chains::chain::mempool::unban_operation::post(&self.context, operation_hash)
+    }
+
+    /// Request the operations of the mempool of the connected peers.
+    ///
+    /// [`POST /chains/<chain_id>/mempool/request_operations?[peer_id=<P2p_peer.Id>]`](https://tezos.gitlab.io/active/rpc.html#post-chains-chain-id-mempool-request-operations)
+    pub fn request_operations(
+        &self,
+    ) -> shell_rpc::chains::chain::mempool::request_operations::RpcRequestBuilder<HttpClient> {
+        shell_rpc::chains::chain::mempool::request_operations::post(&self.context)
+    }
+
     /// Inject an operation in node and broadcast it.
     ///
     /// The `signed_operation_contents` should be constructed using contextual RPCs
@@ -291,6 +442,44 @@ impl<HttpClient: Http> TezosRpc<HttpClient> {
     }
 }
 
+// Streaming RPCs
+// The streams are transparently re-opened when the connection is lost.
+impl<HttpClient: HttpStream> TezosRpc<HttpClient> {
+    /// Monitor the blocks applied by the node while it's bootstrapping, the stream ends once it's bootstrapped.
+    ///
+    /// [`GET /monitor/bootstrapped`](https://tezos.gitlab.io/shell/rpc.html#get-monitor-bootstrapped)
+    pub fn monitor_bootstrapped(
+        &self,
+    ) -> shell_rpc::monitor::bootstrapped::RpcRequestBuilder<HttpClient> {
+        shell_rpc::monitor::bootstrapped::get(&self.context)
+    }
+
+    /// Monitor the new heads of a chain.
+    ///
+    /// [`GET /monitor/heads/<chain_id>?[next_protocol=<Protocol_hash>]*`](https://tezos.gitlab.io/shell/rpc.html#get-monitor-heads-chain-id)
+    pub fn monitor_heads(&self) -> shell_rpc::monitor::heads::RpcRequestBuilder<HttpClient> {
+        shell_rpc::monitor::heads::get(&self.context)
+    }
+
+    /// Monitor all the blocks validated by the node, optionally filtered by chain and protocol.
+    ///
+    /// [`GET /monitor/validated_blocks?[chain=<chain_id>]*&[protocol=<Protocol_hash>]*&[next_protocol=<Protocol_hash>]*`](https://tezos.gitlab.io/shell/rpc.html#get-monitor-validated-blocks)
+    pub fn monitor_validated_blocks(
+        &self,
+    ) -> shell_rpc::monitor::validated_blocks::RpcRequestBuilder<HttpClient> {
+        shell_rpc::monitor::validated_blocks::get(&self.context)
+    }
+
+    /// Monitor the operations entering the mempool of a chain.
+    ///
+    /// [`GET /chains/<chain_id>/mempool/monitor_operations?[validated]&[refused]&[outdated]&[branch_refused]&[branch_delayed]`](https://tezos.gitlab.io/active/rpc.html#get-chains-chain-id-mempool-monitor-operations)
+    pub fn monitor_mempool_operations(
+        &self,
+    ) -> shell_rpc::chains::chain::mempool::monitor_operations::RpcRequestBuilder<HttpClient> {
+        shell_rpc::chains::chain::mempool::monitor_operations::get(&self.context)
+    }
+}
+
 // Tezos protocol-dependent RPCs
 // See [RPCs - Reference](https://tezos.gitlab.io/active/rpc.html) for more details.
 impl<HttpClient: Http> TezosRpc<HttpClient> {
@@ -494,3 +683,120 @@ impl<HttpClient: Http> TezosRpc<HttpClient> {
         protocol_rpc::block::context::delegates::voting_info::get(&self.context, address)
     }
 }
+
+#[cfg(all(test, feature = "http"))]
+mod tests {
+    use tezos_core::types::encoded::{ImplicitAddress, PublicKey};
+    use tezos_operation::operations::{SignedOperation, Transaction};
+
+    use {super::*, httpmock::prelude::*};
+
+    const PUBLIC_KEY: &str = "edpkttZKC51wemRqL2QxwpMnEKxWnbd35pq47Y6xsCHp5M1f7LN8NP";
+
+    fn unsigned_operation(source: ImplicitAddress) -> UnsignedOperation {
+        UnsignedOperation::new(
+            "BMdhifZkcb5i9D6FnBi19SSBjft3sYaeKDAsEBgbsRLPTihQQJU"
+                .try_into()
+                .unwrap(),
+            vec![Transaction::new(
+                source,
+                417u32.into(),
+                2336132u32.into(),
+                1527u32.into(),
+                357u32.into(),
+                1000u32.into(),
+                "tz2AjVPbMHdDF1XwHVhUrTg6ZvqY83AYhJEy".try_into().unwrap(),
+                None,
+            )
+            .into()],
+        )
+    }
+
+    fn mock_manager_key(server: &MockServer, source: &Address, body: &str) {
+        server.mock(|when, then| {
+            when.method(GET).path(format!(
+                "/chains/main/blocks/head/context/contracts/{}/manager_key",
+                source.value()
+            ));
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(body);
+        });
+    }
+
+    #[cfg(feature = "ed25519")]
+    #[tokio::test]
+    async fn test_verify_operation() -> Result<()> {
+        let server = MockServer::start();
+        let public_key: PublicKey = PUBLIC_KEY.try_into()?;
+        let source: ImplicitAddress = public_key.bs58_address()?.try_into()?;
+        mock_manager_key(
+            &server,
+            &source.clone().into(),
+            &format!("\"{}\"", PUBLIC_KEY),
+        );
+
+        let secret_key = "edskRv7VyXGVZb8EsrR7D9XKUbbAQNQGtALP6QeB16ZCD7SmmJpzyeneJVg3Mq56YLbxRA1kSdAXiswwPiaVfR3NHGMCXCziuZ".try_into()?;
+        let signed = unsigned_operation(source).into_signed_operation(&secret_key)?;
+        let injectable = signed.to_injectable_string()?;
+        let parsed = SignedOperation::from_injectable_string(&injectable)?;
+
+        let client = TezosRpc::new(server.base_url());
+        assert!(client.verify_operation(&parsed).await?);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "ed25519")]
+    #[tokio::test]
+    async fn test_sign_and_inject_operation() -> Result<()> {
+        use tezos_operation::signer::InMemorySigner;
+
+        let server = MockServer::start();
+        let signer = InMemorySigner::new(
+            "edskRv7VyXGVZb8EsrR7D9XKUbbAQNQGtALP6QeB16ZCD7SmmJpzyeneJVg3Mq56YLbxRA1kSdAXiswwPiaVfR3NHGMCXCziuZ".try_into()?,
+            PUBLIC_KEY.try_into()?,
+        );
+        let operation = unsigned_operation(signer.address().await?);
+        let signed = operation
+            .clone()
+            .into_signed_operation_using(&signer)
+            .await?;
+        let operation_hash = "ooRAfDhmSNiwEdGQi2M5qt27EVtmk6GhBNmJFGzEjrLuVEz8Xrr";
+
+        server.mock(|when, then| {
+            when.method(POST)
+                .path("/injection/operation")
+                .query_param("chain", "main")
+                .json_body(serde_json::json!(signed.to_injectable_string().unwrap()));
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(format!("\"{}\"", operation_hash));
+        });
+
+        let client = TezosRpc::new(server.base_url());
+        let result = client.sign_and_inject_operation(operation, &signer).await?;
+        assert_eq!(result.value(), operation_hash);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_verify_operation_with_unrevealed_source() -> Result<()> {
+        let server = MockServer::start();
+        let public_key: PublicKey = PUBLIC_KEY.try_into()?;
+        let source: ImplicitAddress = public_key.bs58_address()?.try_into()?;
+        mock_manager_key(&server, &source.clone().into(), "null");
+
+        let signed = SignedOperation::from(
+            unsigned_operation(source),
+            "edsigtyqcyfipEAqFVexKahDjtQkzFgAkd9MroyYHnxxUVHAi4oSBJSezwiKaoNpH9NkY34cNuR4nrL6s8oPWVstQp9h2f7iGQF".try_into()?,
+        );
+
+        let client = TezosRpc::new(server.base_url());
+        let result = client.verify_operation(&signed).await;
+        assert!(matches!(result, Err(Error::ManagerKeyNotRevealed)));
+
+        Ok(())
+    }
+}
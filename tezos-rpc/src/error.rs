@@ -1,6 +1,7 @@
 use {
     crate::models::error::RpcErrors,
     derive_more::{Display, Error, From},
+    std::time::Duration,
 };
 
 #[derive(Debug, From, Display, Error)]
@@ -15,6 +16,9 @@ pub enum Error {
     HttpError {
         source: reqwest::Error,
     },
+    Io {
+        source: std::io::Error,
+    },
     ParsingError {
         source: serde_json::Error,
     },
@@ -30,6 +34,25 @@ pub enum Error {
     RpcErrors(#[error(not(source))] RpcErrors),
     InvalidConversion,
     OperationNotSupported,
+    ManagerKeyNotRevealed,
+    OperationExpired,
+    ReorganizationTooDeep,
+    HeadersNotSupported,
+    Timeout,
+    /// The node is rate limiting (`429`) or temporarily unavailable (`503`).
+    #[from(ignore)]
+    #[display(fmt = "ServiceUnavailable {}", status)]
+    ServiceUnavailable {
+        status: u16,
+        retry_after: Option<Duration>,
+    },
+    /// No response has been recorded for a request replayed by a `ReplayHttp`.
+    #[from(ignore)]
+    #[display(fmt = "FixtureNotFound {} {}", method, request)]
+    FixtureNotFound {
+        method: String,
+        request: String,
+    },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
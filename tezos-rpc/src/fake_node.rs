@@ -0,0 +1,964 @@
+//! An in-process fake Tezos node, to run integration tests without a sandboxed node.
+//!
+//! [FakeNode] implements [Http] and answers the RPCs used by [TezosRpc](crate::client::TezosRpc)
+//! from a simple in-memory state: the balances, counters, manager keys and delegates of the accounts,
+//! the scripts and storage of the contracts, the big maps and a chain of blocks.
+//! The injected operations are applied to the state and baked into a new block, with the results
+//! configured by [FakeNode::on_operation]. The signatures of the operations aren't verified
+//! and the context RPCs return the current state whichever block they're sent for.
+//!
+//! ```rust
+//! use tezos_core::types::encoded::Address;
+//! use tezos_rpc::{client::TezosRpc, fake_node::FakeNode, Result};
+//!
+//! async fn example() -> Result<()> {
+//!     let node = FakeNode::new();
+//!     let address: Address = "tz1gru9Tsz1X7GaYnsKR2YeGJLTVm4NwMhvb".try_into()?;
+//!     node.set_balance(&address, 1_000_000u32.into());
+//!
+//!     let rpc = TezosRpc::new_rpc_with_http_client(node.clone(), Default::default());
+//!     let balance = rpc.get_contract_balance(&address).send().await?;
+//!     Ok(())
+//! }
+//! ```
+
+mod state;
+
+use std::{
+    collections::HashMap,
+    fmt::{self, Debug},
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use async_trait::async_trait;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+use tezos_core::types::{
+    encoded::{
+        Address, BlockHash, ChainId, ContractAddress, Encoded, ImplicitAddress, PublicKey,
+        ScriptExprHash,
+    },
+    mutez::Mutez,
+};
+use tezos_michelson::micheline::Micheline;
+use tezos_operation::operations::{
+    Operation as _, OperationContent, SignedOperation, UnsignedOperation,
+};
+
+use self::state::{rpc_error, Account, BigMap, State, CHAIN_ID, PROTOCOL};
+use crate::{
+    http::{middleware::Method, Http},
+    models::{contract::ContractScript, error::RpcError, operation::Operation},
+    Error, Result,
+};
+
+/// The result a [FakeNode] applies an operation content with.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    /// The content is applied, transactions to a contract replacing its storage with `storage` if set.
+    Applied {
+        consumed_milligas: u64,
+        paid_storage_size_diff: u64,
+        storage: Option<Micheline>,
+    },
+    /// The content fails with the `errors`, the whole operation being reverted except for its fees.
+    Failed { errors: Vec<RpcError> },
+}
+
+impl Outcome {
+    /// An applied result with the default gas and storage consumption.
+    pub fn applied() -> Self {
+        Self::Applied {
+            consumed_milligas: 1_000_000,
+            paid_storage_size_diff: 0,
+            storage: None,
+        }
+    }
+
+    /// A failed result with a single error of the current protocol, e.g. `michelson_v1.script_rejected`.
+    pub fn failed(id: &str) -> Self {
+        Self::Failed {
+            errors: vec![rpc_error(id)],
+        }
+    }
+
+    fn default_for(content: &OperationContent) -> Self {
+        match content {
+            OperationContent::Transaction(_) => Self::Applied {
+                consumed_milligas: 1_421_000,
+                paid_storage_size_diff: 0,
+                storage: None,
+            },
+            OperationContent::Origination(_) => Self::Applied {
+                consumed_milligas: 1_500_000,
+                paid_storage_size_diff: 257,
+                storage: None,
+            },
+            _ => Self::applied(),
+        }
+    }
+}
+
+/// An in-process fake node implementing [Http], see the [module documentation](self).
+///
+/// The clones of a node share its state, so a test can keep a handle on the node
+/// to set it up and inspect it while a [TezosRpc](crate::client::TezosRpc) sends requests to it.
+#[derive(Clone)]
+pub struct FakeNode {
+    state: Arc<Mutex<State>>,
+}
+
+impl FakeNode {
+    /// Creates a node whose chain only contains the genesis block.
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(State::new())),
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// The identifier of the chain.
+    pub fn chain_id(&self) -> ChainId {
+        state::chain_id()
+    }
+
+    /// The hash of the head of the chain.
+    pub fn head_hash(&self) -> BlockHash {
+        self.state().head().hash.clone()
+    }
+
+    /// The level of the head of the chain.
+    pub fn head_level(&self) -> i32 {
+        self.state().head().level
+    }
+
+    /// Bakes the pending operations into a new block and returns its hash.
+    pub fn bake(&self) -> BlockHash {
+        self.state().bake()
+    }
+
+    /// Whether each injected operation is baked right away into a new block, `true` by default.
+    ///
+    /// Otherwise the injected operations stay pending until [FakeNode::bake] is called.
+    pub fn set_auto_bake(&self, auto_bake: bool) {
+        self.state().auto_bake = auto_bake;
+    }
+
+    /// Sets the function deciding the result of each applied operation content.
+    ///
+    /// The contents for which `on_operation` returns `None` are applied with a default result.
+    pub fn on_operation<F>(&self, on_operation: F)
+    where
+        F: Fn(&OperationContent) -> Option<Outcome> + Send + Sync + 'static,
+    {
+        self.state().on_operation = Some(Arc::new(on_operation));
+    }
+
+    /// The balance of an account, zero if it doesn't exist.
+    pub fn balance(&self, address: &Address) -> Mutez {
+        let balance = self
+            .state()
+            .account(address.value())
+            .map_or(0, |account| account.balance);
+
+        Mutez::try_from(balance).unwrap_or_default()
+    }
+
+    pub fn set_balance(&self, address: &Address, balance: Mutez) {
+        self.state().account_mut(address.value()).balance =
+            u64::try_from(balance).unwrap_or_default();
+    }
+
+    /// The counter of an implicit account, zero if it doesn't exist.
+    pub fn counter(&self, address: &ImplicitAddress) -> u64 {
+        self.state()
+            .account(address.value())
+            .map_or(0, |account| account.counter)
+    }
+
+    pub fn set_counter(&self, address: &ImplicitAddress, counter: u64) {
+        self.state().account_mut(address.value()).counter = counter;
+    }
+
+    /// Sets the revealed public key of an implicit account, `None` if it isn't revealed.
+    pub fn set_manager_key(&self, address: &ImplicitAddress, public_key: Option<&PublicKey>) {
+        self.state().account_mut(address.value()).manager_key =
+            public_key.map(|public_key| public_key.value().into());
+    }
+
+    pub fn set_delegate(&self, address: &Address, delegate: Option<&ImplicitAddress>) {
+        self.state().account_mut(address.value()).delegate =
+            delegate.map(|delegate| delegate.value().into());
+    }
+
+    /// Adds a contract with its `script` and `balance`, replacing any existing one at the same address.
+    pub fn add_contract(&self, address: &ContractAddress, script: ContractScript, balance: Mutez) {
+        self.state().accounts.insert(
+            address.value().into(),
+            Account {
+                balance: u64::try_from(balance).unwrap_or_default(),
+                script: Some(script),
+                ..Default::default()
+            },
+        );
+    }
+
+    /// The script of a contract, `None` if the contract doesn't exist.
+    pub fn script(&self, address: &ContractAddress) -> Option<ContractScript> {
+        self.state()
+            .account(address.value())
+            .and_then(|account| account.script.clone())
+    }
+
+    /// The storage of a contract, `None` if the contract doesn't exist.
+    pub fn storage(&self, address: &ContractAddress) -> Option<Micheline> {
+        self.script(address).map(|script| script.storage)
+    }
+
+    /// Replaces the storage of a contract, returns [Error::InvalidConversion] if the contract doesn't exist.
+    pub fn set_storage(&self, address: &ContractAddress, storage: Micheline) -> Result<()> {
+        let mut state = self.state();
+        let script = state
+            .accounts
+            .get_mut(address.value())
+            .and_then(|account| account.script.as_mut())
+            .ok_or(Error::InvalidConversion)?;
+        script.storage = storage;
+
+        Ok(())
+    }
+
+    /// Adds an empty big map with the type of its keys, replacing any existing one with the same `id`.
+    pub fn add_big_map(&self, id: u32, key_type: Micheline) {
+        self.state().big_maps.insert(
+            id,
+            BigMap {
+                key_type,
+                values: Default::default(),
+            },
+        );
+    }
+
+    /// Sets the `value` of a `key` of a big map, removing the key if `value` is `None`.
+    ///
+    /// Returns the hash of the key, which the big map RPCs expect.
+    pub fn set_big_map_value(
+        &self,
+        id: u32,
+        key: Micheline,
+        value: Option<Micheline>,
+    ) -> Result<ScriptExprHash> {
+        let mut state = self.state();
+        let big_map = state
+            .big_maps
+            .get_mut(&id)
+            .ok_or(Error::InvalidConversion)?;
+        let hash = State::script_expr(&key, &big_map.key_type)?;
+        match value {
+            Some(value) => big_map.values.insert(hash.value().into(), (key, value)),
+            None => big_map.values.remove(hash.value()),
+        };
+
+        Ok(hash)
+    }
+
+    fn respond<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        url: &str,
+        query: Value,
+        body: Value,
+    ) -> Result<T> {
+        let segments = url
+            .split('?')
+            .next()
+            .unwrap_or_default()
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .collect::<Vec<_>>();
+        let value = Router {
+            state: &mut self.state(),
+            method,
+            query: &query,
+            body,
+        }
+        .route(&segments)?
+        .ok_or_else(|| Error::RpcErrorPlain {
+            description: format!("No service found at this URL: {} {}", method, url),
+        })?;
+
+        Ok(serde_json::from_value(value)?)
+    }
+}
+
+impl Default for FakeNode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for FakeNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.state();
+        f.debug_struct("FakeNode")
+            .field("level", &state.head().level)
+            .field("accounts", &state.accounts.len())
+            .field("big_maps", &state.big_maps.len())
+            .field("mempool", &state.mempool.len())
+            .finish()
+    }
+}
+
+#[async_trait]
+impl Http for FakeNode {
+    /// Creates a new node, the `rpc_endpoint` is ignored.
+    fn new(_rpc_endpoint: String) -> Self {
+        Self::new()
+    }
+
+    fn change_rpc_endpoint(&mut self, _rpc_endpoint: String) {}
+
+    async fn get<T: DeserializeOwned>(&self, url: &str) -> Result<T> {
+        self.respond(Method::Get, url, Value::Null, Value::Null)
+    }
+
+    async fn get_with_query<T: DeserializeOwned, Q: Serialize + ?Sized + Sync>(
+        &self,
+        url: &str,
+        query: &Q,
+    ) -> Result<T> {
+        self.respond(Method::Get, url, serde_json::to_value(query)?, Value::Null)
+    }
+
+    async fn post<B: Serialize + Sync, T: DeserializeOwned, Q: Serialize + Sync>(
+        &self,
+        url: &str,
+        body: &B,
+        _query: Option<&Q>,
+    ) -> Result<T> {
+        self.respond(Method::Post, url, Value::Null, serde_json::to_value(body)?)
+    }
+
+    async fn patch<B: Serialize + Sync, T: DeserializeOwned>(
+        &self,
+        url: &str,
+        body: Option<&B>,
+    ) -> Result<T> {
+        self.respond(Method::Patch, url, Value::Null, serde_json::to_value(body)?)
+    }
+
+    async fn delete<B: Serialize + Sync, T: DeserializeOwned>(
+        &self,
+        url: &str,
+        body: Option<&B>,
+    ) -> Result<T> {
+        self.respond(
+            Method::Delete,
+            url,
+            Value::Null,
+            serde_json::to_value(body)?,
+        )
+    }
+
+    fn with_headers(&self, _headers: &[(String, String)]) -> Option<Self> {
+        Some(self.clone())
+    }
+}
+
+#[derive(Deserialize)]
+struct RunOperationParam {
+    operation: Operation,
+}
+
+/// Answers a request from the state of a [FakeNode], `None` meaning that the RPC isn't supported.
+struct Router<'a> {
+    state: &'a mut State,
+    method: Method,
+    query: &'a Value,
+    body: Value,
+}
+
+impl<'a> Router<'a> {
+    fn route(self, segments: &[&str]) -> Result<Option<Value>> {
+        match (self.method, segments) {
+            (Method::Post, ["injection", "operation"]) => self.inject(),
+            (_, ["chains", _, rest @ ..]) => self.route_chain(rest),
+            _ => Ok(None),
+        }
+    }
+
+    fn route_chain(self, segments: &[&str]) -> Result<Option<Value>> {
+        match (self.method, segments) {
+            (Method::Get, ["chain_id"]) => Ok(Some(CHAIN_ID.into())),
+            (Method::Get, ["is_bootstrapped"]) => Ok(Some(json!({
+                "bootstrapped": true,
+                "sync_state": "synced",
+            }))),
+            (Method::Get, ["blocks"]) => Ok(Some(self.blocks())),
+            (Method::Get, ["mempool", "pending_operations"]) => {
+                let validated = self
+                    .state
+                    .mempool
+                    .iter()
+                    .cloned()
+                    .map(without_metadata)
+                    .collect::<Vec<_>>();
+                Ok(Some(json!({ "validated": validated })))
+            }
+            (_, ["blocks", block_id, rest @ ..]) => {
+                let block = match self.state.block(block_id) {
+                    Some(block) => block.clone(),
+                    None => return Err(not_found(block_id)),
+                };
+                self.route_block(&block, rest)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn route_block(self, block: &state::Block, segments: &[&str]) -> Result<Option<Value>> {
+        match (self.method, segments) {
+            (Method::Get, []) => Ok(Some(self.state.block_json(block))),
+            (Method::Get, ["hash"]) => Ok(Some(block.hash.value().into())),
+            (Method::Get, ["header"]) => {
+                let mut header = self.state.header_json(block);
+                header["protocol"] = PROTOCOL.into();
+                header["chain_id"] = CHAIN_ID.into();
+                header["hash"] = block.hash.value().into();
+                Ok(Some(header))
+            }
+            (Method::Get, ["context", "constants"]) => {
+                Ok(Some(serde_json::from_str(include_str!(
+                    "protocol_rpc/block/context/constants/__TEST_DATA__/jakarta_constants.json"
+                ))?))
+            }
+            (_, ["context", "contracts", address, rest @ ..]) => self.route_contract(address, rest),
+            (_, ["context", "big_maps", id, rest @ ..]) => self.route_big_map(id, rest),
+            (Method::Post, ["helpers", "scripts", "run_operation"]) => {
+                let param: RunOperationParam = serde_json::from_value(self.body)?;
+                Ok(Some(run(self.state, param.operation)?))
+            }
+            (Method::Post, ["helpers", "preapply", "operations"]) => {
+                let operations: Vec<Operation> = serde_json::from_value(self.body)?;
+                let results = operations
+                    .into_iter()
+                    .map(|operation| run(self.state, operation))
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(Some(results.into()))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn route_contract(self, address: &str, segments: &[&str]) -> Result<Option<Value>> {
+        let account = match self.state.account(address) {
+            Some(account) => account.clone(),
+            None if !address.starts_with("KT1") => Account::default(),
+            None => return Err(not_found(address)),
+        };
+        let is_implicit = account.script.is_none();
+
+        let value = match (self.method, segments) {
+            (Method::Get, []) => {
+                let mut info = json!({ "balance": account.balance.to_string() });
+                if is_implicit {
+                    info["counter"] = account.counter.to_string().into();
+                }
+                if let Some(delegate) = &account.delegate {
+                    info["delegate"] = delegate.as_str().into();
+                }
+                if let Some(script) = &account.script {
+                    info["script"] = serde_json::to_value(script)?;
+                }
+                info
+            }
+            (Method::Get, ["balance"]) => account.balance.to_string().into(),
+            (Method::Get, ["counter"]) if is_implicit => account.counter.to_string().into(),
+            (Method::Get, ["manager_key"]) if is_implicit => account.manager_key.into(),
+            (Method::Get, ["delegate"]) => {
+                account.delegate.ok_or_else(|| not_found(address))?.into()
+            }
+            (Method::Get, ["script"]) | (Method::Post, ["script", "normalized"]) => {
+                serde_json::to_value(account.script.ok_or_else(|| not_found(address))?)?
+            }
+            (Method::Get, ["storage"]) | (Method::Post, ["storage", "normalized"]) => {
+                serde_json::to_value(account.script.ok_or_else(|| not_found(address))?.storage)?
+            }
+            (Method::Get, ["entrypoints"]) => {
+                let parameter =
+                    parameter_type(account.script.as_ref()).ok_or_else(|| not_found(address))?;
+                let mut entrypoints = HashMap::new();
+                collect_entrypoints(parameter, true, &mut entrypoints);
+                json!({ "entrypoints": entrypoints })
+            }
+            (Method::Get, ["entrypoints", name]) => {
+                let parameter =
+                    parameter_type(account.script.as_ref()).ok_or_else(|| not_found(address))?;
+                let mut entrypoints = HashMap::new();
+                collect_entrypoints(parameter, true, &mut entrypoints);
+                let entrypoint = match entrypoints.remove(*name) {
+                    Some(entrypoint) => entrypoint,
+                    None if *name == "default" => parameter.clone(),
+                    None => return Err(not_found(name)),
+                };
+                serde_json::to_value(entrypoint)?
+            }
+            _ => return Ok(None),
+        };
+
+        Ok(Some(value))
+    }
+
+    fn route_big_map(self, id: &str, segments: &[&str]) -> Result<Option<Value>> {
+        let big_map = id
+            .parse::<u32>()
+            .ok()
+            .and_then(|id| self.state.big_maps.get(&id))
+            .ok_or_else(|| not_found(id))?;
+
+        let value = match (self.method, segments) {
+            (Method::Get, []) => {
+                let values = big_map
+                    .values
+                    .values()
+                    .map(|(_, value)| value)
+                    .collect::<Vec<_>>();
+                serde_json::to_value(values)?
+            }
+            (Method::Get, [key_hash]) | (Method::Post, [key_hash, "normalized"]) => {
+                let (_, value) = big_map
+                    .values
+                    .get(*key_hash)
+                    .ok_or_else(|| not_found(key_hash))?;
+                serde_json::to_value(value)?
+            }
+            _ => return Ok(None),
+        };
+
+        Ok(Some(value))
+    }
+
+    fn blocks(&self) -> Value {
+        let length = query_param(self.query, "length")
+            .and_then(|length| length.parse::<usize>().ok())
+            .unwrap_or(1);
+        let hashes = self
+            .state
+            .blocks
+            .iter()
+            .rev()
+            .take(length)
+            .map(|block| block.hash.value())
+            .collect::<Vec<_>>();
+
+        json!([hashes])
+    }
+
+    fn inject(self) -> Result<Option<Value>> {
+        let payload: String = serde_json::from_value(self.body)?;
+        let operation = SignedOperation::from_injectable_string(&payload)?;
+        let hash = operation.hash()?;
+        let contents = operation.contents.clone();
+
+        self.state.validate(&contents)?;
+        let metadata = self.state.apply(&contents, &hash.to_bytes()?, false)?;
+        let mut value = serde_json::to_value(Operation::from(operation))?;
+        value["protocol"] = PROTOCOL.into();
+        value["chain_id"] = CHAIN_ID.into();
+        value["hash"] = hash.value().into();
+        with_metadata(&mut value, metadata);
+
+        self.state.mempool.push(value);
+        if self.state.auto_bake {
+            self.state.bake();
+        }
+
+        Ok(Some(hash.value().into()))
+    }
+}
+
+/// Applies an `operation` without modifying the state and returns it with the metadata of its contents.
+fn run(state: &mut State, operation: Operation) -> Result<Value> {
+    let contents = operation
+        .contents
+        .iter()
+        .cloned()
+        .map(OperationContent::try_from)
+        .collect::<Result<Vec<_>>>()?;
+    let unsigned = UnsignedOperation::new(operation.branch.clone(), contents.clone());
+    let mut payload = unsigned.to_forged_bytes()?;
+    if let Some(signature) = &operation.signature {
+        payload.extend(signature.to_bytes()?);
+    }
+    let hash = SignedOperation::operation_hash(&payload)?;
+
+    state.validate(&contents)?;
+    let metadata = state.apply(&contents, &hash.to_bytes()?, true)?;
+    let mut value = serde_json::to_value(operation)?;
+    with_metadata(&mut value, metadata);
+
+    Ok(value)
+}
+
+fn with_metadata(operation: &mut Value, metadata: Vec<Value>) {
+    if let Some(contents) = operation["contents"].as_array_mut() {
+        for (content, metadata) in contents.iter_mut().zip(metadata) {
+            content["metadata"] = metadata;
+        }
+    }
+}
+
+fn without_metadata(mut operation: Value) -> Value {
+    if let Some(contents) = operation["contents"].as_array_mut() {
+        for content in contents.iter_mut().filter_map(Value::as_object_mut) {
+            content.remove("metadata");
+        }
+    }
+
+    operation
+}
+
+/// Reads a query parameter serialized either as a list of pairs or as a map.
+fn query_param(query: &Value, name: &str) -> Option<String> {
+    let value = match query {
+        Value::Array(pairs) => pairs
+            .iter()
+            .find(|pair| pair[0] == name)
+            .map(|pair| &pair[1]),
+        Value::Object(map) => map.get(name),
+        _ => None,
+    }?;
+
+    match value {
+        Value::String(value) => Some(value.clone()),
+        value => Some(value.to_string()),
+    }
+}
+
+fn parameter_type(script: Option<&ContractScript>) -> Option<&Micheline> {
+    script?
+        .code
+        .values()
+        .iter()
+        .find_map(|section| match section {
+            Micheline::PrimitiveApplication(section) if section.prim() == "parameter" => {
+                section.first_arg()
+            }
+            _ => None,
+        })
+}
+
+/// Collects the entrypoints annotated in a parameter type, going through its `or` branches.
+fn collect_entrypoints(
+    parameter: &Micheline,
+    is_root: bool,
+    entrypoints: &mut HashMap<String, Micheline>,
+) {
+    let application = match parameter {
+        Micheline::PrimitiveApplication(application) => application,
+        _ => return,
+    };
+    let name = application
+        .annots()
+        .iter()
+        .flatten()
+        .find_map(|annot| annot.strip_prefix('%'));
+    if let Some(name) = name {
+        if !is_root || name == "default" {
+            entrypoints.insert(name.into(), parameter.clone());
+        }
+    }
+    if application.prim() == "or" {
+        for arg in application.args().iter().flatten() {
+            collect_entrypoints(arg, false, entrypoints);
+        }
+    }
+}
+
+fn not_found(id: &str) -> Error {
+    Error::RpcErrors(
+        vec![RpcError {
+            kind: "temporary".into(),
+            id: "context.storage_error".into(),
+            message: Some(format!("{} not found", id)),
+            msg: None,
+            amount: None,
+            balance: None,
+            contract: None,
+        }]
+        .into(),
+    )
+}
+
+#[cfg(all(test, feature = "fake_node"))]
+mod tests {
+    use tezos_core::types::number::Nat;
+    use tezos_operation::operations::{Origination, Reveal, Script, Transaction};
+
+    use super::*;
+    use crate::{
+        client::TezosRpc,
+        models::operation::{
+            operation_result::OperationResultStatus, OperationContent as RpcOperationContent,
+        },
+    };
+
+    const SOURCE: &str = "tz1gru9Tsz1X7GaYnsKR2YeGJLTVm4NwMhvb";
+    const DESTINATION: &str = "tz1bQMn5xYFbX6geRxqvuAiTywsCtNywawxH";
+    const PUBLIC_KEY: &str = "edpkttZKC51wemRqL2QxwpMnEKxWnbd35pq47Y6xsCHp5M1f7LN8NP";
+    const SIGNATURE: &str = "sigQh51NhuohSTe11Pp8hXSE9VMn1TbbqZrGKAbLEUus852PPSmXs5Hjf9v3cbwtRewvcYK8F6PLUg85ZBYrUGX5GqgkRFC7";
+
+    fn funded_node() -> Result<FakeNode> {
+        let node = FakeNode::new();
+        let source: ImplicitAddress = SOURCE.try_into()?;
+        node.set_balance(&source.clone().into(), 10_000_000u32.into());
+        node.set_counter(&source, 5);
+        node.set_manager_key(&source, Some(&PUBLIC_KEY.try_into()?));
+
+        Ok(node)
+    }
+
+    fn transaction(counter: u32, amount: u32) -> Result<OperationContent> {
+        Ok(Transaction::new(
+            SOURCE.try_into()?,
+            1_000u16.into(),
+            Nat::from_integer(counter),
+            10_000u16.into(),
+            0u8.into(),
+            amount.into(),
+            DESTINATION.try_into()?,
+            None,
+        )
+        .into())
+    }
+
+    fn sign(node: &FakeNode, contents: Vec<OperationContent>) -> Result<SignedOperation> {
+        Ok(SignedOperation::new(
+            node.head_hash(),
+            contents,
+            SIGNATURE.try_into()?,
+        ))
+    }
+
+    fn counter_script() -> Result<Script> {
+        let code: Micheline = serde_json::from_value(json!([
+            { "prim": "parameter", "args": [{ "prim": "or", "args": [
+                { "prim": "nat", "annots": ["%increment"] },
+                { "prim": "unit", "annots": ["%reset"] }
+            ] }] },
+            { "prim": "storage", "args": [{ "prim": "nat" }] },
+            { "prim": "code", "args": [[
+                { "prim": "CDR" },
+                { "prim": "NIL", "args": [{ "prim": "operation" }] },
+                { "prim": "PAIR" }
+            ]] }
+        ]))?;
+
+        Ok(Script::new(
+            code.into_sequence().ok_or(Error::InvalidConversion)?,
+            serde_json::from_value(json!({ "int": "0" }))?,
+        ))
+    }
+
+    fn status(content: &RpcOperationContent) -> Option<OperationResultStatus> {
+        match content {
+            RpcOperationContent::Transaction(transaction) => transaction
+                .metadata
+                .as_ref()
+                .map(|metadata| metadata.operation_result.status),
+            RpcOperationContent::Origination(origination) => origination
+                .metadata
+                .as_ref()
+                .map(|metadata| metadata.operation_result.status),
+            _ => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_and_inject_transaction() -> Result<()> {
+        let node = funded_node()?;
+        let rpc = TezosRpc::new_rpc_with_http_client(node.clone(), Default::default());
+        let operation = sign(&node, vec![transaction(6, 1_000_000)?])?;
+
+        let result = rpc.run_operation(&operation.clone().into()).send().await?;
+        assert_eq!(
+            status(&result.contents[0]),
+            Some(OperationResultStatus::Applied)
+        );
+        assert_eq!(node.balance(&SOURCE.try_into()?), 10_000_000u32.into());
+
+        let hash = rpc
+            .inject_operation(&operation.to_injectable_string()?)
+            .send()
+            .await?;
+        assert_eq!(hash, operation.hash()?);
+        assert_eq!(node.head_level(), 1);
+        assert_eq!(node.balance(&SOURCE.try_into()?), 8_999_000u32.into());
+        assert_eq!(node.balance(&DESTINATION.try_into()?), 1_000_000u32.into());
+
+        let counter = rpc.get_contract_counter(&SOURCE.try_into()?).send().await?;
+        assert_eq!(counter, 6u8.into());
+
+        let block = rpc.get_block().send().await?;
+        assert_eq!(block.hash, node.head_hash());
+        assert_eq!(block.header.level, 1);
+        assert_eq!(block.operations[3][0].hash, Some(hash));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rejects_invalid_counter_and_unrevealed_key() -> Result<()> {
+        let node = funded_node()?;
+        let rpc = TezosRpc::new_rpc_with_http_client(node.clone(), Default::default());
+
+        let operation = sign(&node, vec![transaction(8, 1_000)?])?;
+        let result = rpc
+            .inject_operation(&operation.to_injectable_string()?)
+            .send()
+            .await;
+        assert!(
+            matches!(result, Err(Error::RpcErrors(errors)) if errors.errors()[0].id.ends_with("counter_in_the_future"))
+        );
+
+        node.set_manager_key(&SOURCE.try_into()?, None);
+        let operation = sign(&node, vec![transaction(6, 1_000)?])?;
+        let result = rpc.run_operation(&operation.into()).send().await;
+        assert!(
+            matches!(result, Err(Error::RpcErrors(errors)) if errors.errors()[0].id.ends_with("unrevealed_key"))
+        );
+
+        let reveal = Reveal::new(
+            SOURCE.try_into()?,
+            1_000u16.into(),
+            6u8.into(),
+            1_000u16.into(),
+            0u8.into(),
+            PUBLIC_KEY.try_into()?,
+        );
+        let operation = sign(&node, vec![reveal.into(), transaction(7, 1_000)?])?;
+        rpc.inject_operation(&operation.to_injectable_string()?)
+            .send()
+            .await?;
+        let manager_key = rpc
+            .get_contract_manager_key(&SOURCE.try_into()?)
+            .send()
+            .await?;
+        assert_eq!(manager_key.as_deref(), Some(PUBLIC_KEY));
+        assert_eq!(node.head_level(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_originate_and_call_contract() -> Result<()> {
+        let node = funded_node()?;
+        let rpc = TezosRpc::new_rpc_with_http_client(node.clone(), Default::default());
+
+        let origination = Origination::new(
+            SOURCE.try_into()?,
+            1_000u16.into(),
+            6u8.into(),
+            10_000u16.into(),
+            500u16.into(),
+            0u8.into(),
+            None,
+            counter_script()?,
+        );
+        let operation = sign(&node, vec![origination.into()])?;
+        rpc.inject_operation(&operation.to_injectable_string()?)
+            .send()
+            .await?;
+
+        let block = rpc.get_block().send().await?;
+        let contract = match &block.operations[3][0].contents[0] {
+            RpcOperationContent::Origination(origination) => origination
+                .metadata
+                .as_ref()
+                .and_then(|metadata| metadata.operation_result.originated_contracts.clone())
+                .and_then(|contracts| contracts.into_iter().next()),
+            _ => None,
+        }
+        .ok_or(Error::InvalidConversion)?;
+        let entrypoints = rpc
+            .get_contract_entrypoints(&contract.clone().into())
+            .send()
+            .await?;
+        assert!(entrypoints.entrypoints.contains_key("increment"));
+        assert!(entrypoints.entrypoints.contains_key("reset"));
+
+        let storage: Micheline = serde_json::from_value(json!({ "int": "7" }))?;
+        let expected_storage = storage.clone();
+        node.on_operation(move |content| match content {
+            OperationContent::Transaction(transaction) if transaction.amount == 0u8.into() => {
+                Some(Outcome::failed("michelson_v1.script_rejected"))
+            }
+            OperationContent::Transaction(_) => Some(Outcome::Applied {
+                consumed_milligas: 2_000_000,
+                paid_storage_size_diff: 0,
+                storage: Some(storage.clone()),
+            }),
+            _ => None,
+        });
+
+        let call = |counter: u32, amount: u32| -> Result<OperationContent> {
+            Ok(Transaction::new(
+                SOURCE.try_into()?,
+                1_000u16.into(),
+                Nat::from_integer(counter),
+                10_000u16.into(),
+                0u8.into(),
+                amount.into(),
+                contract.clone().into(),
+                None,
+            )
+            .into())
+        };
+        let operation = sign(&node, vec![call(7, 10)?, call(8, 0)?, call(9, 10)?])?;
+        let result = rpc.run_operation(&operation.into()).send().await?;
+        let statuses = result.contents.iter().map(status).collect::<Vec<_>>();
+        assert_eq!(
+            statuses,
+            vec![
+                Some(OperationResultStatus::Backtracked),
+                Some(OperationResultStatus::Failed),
+                Some(OperationResultStatus::Skipped)
+            ]
+        );
+
+        let operation = sign(&node, vec![call(7, 10)?])?;
+        rpc.inject_operation(&operation.to_injectable_string()?)
+            .send()
+            .await?;
+        assert_eq!(node.storage(&contract), Some(expected_storage));
+        assert_eq!(node.balance(&contract.into()), 10u8.into());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_big_map_values() -> Result<()> {
+        let node = FakeNode::new();
+        let rpc = TezosRpc::new_rpc_with_http_client(node.clone(), Default::default());
+        node.add_big_map(12, serde_json::from_value(json!({ "prim": "string" }))?);
+        let value: Micheline = serde_json::from_value(json!({ "int": "42" }))?;
+        let hash = node.set_big_map_value(
+            12,
+            serde_json::from_value(json!({ "string": "key" }))?,
+            Some(value.clone()),
+        )?;
+
+        let result = rpc.get_big_map_value(12, &hash).send().await?;
+        assert_eq!(result, value);
+
+        let result = rpc.get_big_map_value(13, &hash).send().await;
+        assert!(matches!(result, Err(Error::RpcErrors(_))));
+
+        Ok(())
+    }
+}
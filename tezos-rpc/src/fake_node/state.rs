@@ -0,0 +1,448 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+use chrono::NaiveDateTime;
+use serde_json::{json, Map, Value};
+use tezos_core::{
+    internal::crypto::blake2b,
+    types::encoded::{BlockHash, ChainId, ContractHash, Encoded, ScriptExprHash},
+};
+use tezos_michelson::micheline::Micheline;
+use tezos_operation::operations::OperationContent;
+
+use super::Outcome;
+use crate::{
+    models::{contract::ContractScript, error::RpcError},
+    Error, Result,
+};
+
+pub(super) const CHAIN_ID: &str = "NetXdQprcVkpaWU";
+pub(super) const PROTOCOL: &str = "PtJakart2xVj7pYXJBXrqHgd82rdkLey5ZeeGwDgPp9rhQUbSqY";
+const OPERATIONS_HASH: &str = "LLoaxj33NkFZLAc9uvXcwNRZKdk6z83Ke5PEKrs32cUCPpkb9cJ8S";
+const CONTEXT_HASH: &str = "CoVeQQ6XqvBF4vp8uCSatdWuZsyDx5x3SQwopjwcoWES8RFcjbgZ";
+/// The timestamp of the genesis block, each following block is baked [BLOCK_DELAY] seconds later.
+const GENESIS_TIMESTAMP: i64 = 1656916664;
+const BLOCK_DELAY: i64 = 15;
+const MAX_OPERATIONS_TTL: i32 = 120;
+
+pub(super) type OutcomeHandler = dyn Fn(&OperationContent) -> Option<Outcome> + Send + Sync;
+
+#[derive(Debug, Clone, Default)]
+pub(super) struct Account {
+    pub balance: u64,
+    pub counter: u64,
+    pub manager_key: Option<String>,
+    pub delegate: Option<String>,
+    pub script: Option<ContractScript>,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct BigMap {
+    pub key_type: Micheline,
+    /// The keys and values by the hash of the keys.
+    pub values: BTreeMap<String, (Micheline, Micheline)>,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct Block {
+    pub hash: BlockHash,
+    pub level: i32,
+    pub predecessor: BlockHash,
+    /// The operations with their metadata, as returned by the RPCs.
+    pub operations: Vec<Value>,
+}
+
+pub(super) struct State {
+    pub blocks: Vec<Block>,
+    pub accounts: HashMap<String, Account>,
+    pub big_maps: HashMap<u32, BigMap>,
+    /// The injected operations not baked yet.
+    pub mempool: Vec<Value>,
+    pub auto_bake: bool,
+    pub on_operation: Option<Arc<OutcomeHandler>>,
+}
+
+impl State {
+    pub fn new() -> Self {
+        let genesis_hash = block_hash("genesis", 0);
+        Self {
+            blocks: vec![Block {
+                hash: genesis_hash.clone(),
+                level: 0,
+                predecessor: genesis_hash,
+                operations: vec![],
+            }],
+            accounts: HashMap::new(),
+            big_maps: HashMap::new(),
+            mempool: vec![],
+            auto_bake: true,
+            on_operation: None,
+        }
+    }
+
+    pub fn head(&self) -> &Block {
+        self.blocks
+            .last()
+            .expect("the genesis block is never removed")
+    }
+
+    /// Resolves a block identifier: `head`, `head~<n>`, `genesis`, a level or a hash.
+    pub fn block(&self, block_id: &str) -> Option<&Block> {
+        let head_level = self.head().level;
+        let level = match block_id {
+            "head" => head_level,
+            "genesis" => 0,
+            _ => {
+                if let Some(offset) = block_id
+                    .strip_prefix("head~")
+                    .or_else(|| block_id.strip_prefix("head-"))
+                {
+                    head_level - offset.parse::<i32>().ok()?
+                } else if let Ok(level) = block_id.parse::<i32>() {
+                    level
+                } else {
+                    return self
+                        .blocks
+                        .iter()
+                        .find(|block| block.hash.value() == block_id);
+                }
+            }
+        };
+
+        self.blocks.get(usize::try_from(level).ok()?)
+    }
+
+    /// Bakes the operations of the mempool into a new block.
+    pub fn bake(&mut self) -> BlockHash {
+        let head = self.head();
+        let level = head.level + 1;
+        let block = Block {
+            hash: block_hash(head.hash.value(), level),
+            level,
+            predecessor: head.hash.clone(),
+            operations: std::mem::take(&mut self.mempool),
+        };
+        let hash = block.hash.clone();
+        self.blocks.push(block);
+
+        hash
+    }
+
+    pub fn account(&self, address: &str) -> Option<&Account> {
+        self.accounts.get(address)
+    }
+
+    pub fn account_mut(&mut self, address: &str) -> &mut Account {
+        self.accounts.entry(address.into()).or_default()
+    }
+
+    /// Checks that the manager operations of a batch can be applied: their sources are revealed
+    /// and their counters follow the ones of the sources.
+    pub fn validate(&self, contents: &[OperationContent]) -> Result<()> {
+        let mut counters = HashMap::new();
+        let mut revealed = vec![];
+        for content in contents {
+            let (source, counter) = match (content.source(), content.counter()) {
+                (Some(source), Some(counter)) => (source.value(), counter.to_integer::<u64>()?),
+                _ => continue,
+            };
+            let account = self.account(source);
+            let expected = counters
+                .entry(source)
+                .or_insert_with(|| account.map_or(0, |account| account.counter));
+            *expected += 1;
+            if counter != *expected {
+                let id = if counter < *expected {
+                    "contract.counter_in_the_past"
+                } else {
+                    "contract.counter_in_the_future"
+                };
+                return Err(Error::RpcErrors(vec![rpc_error(id)].into()));
+            }
+
+            if let OperationContent::Reveal(_) = content {
+                revealed.push(source);
+            } else if !revealed.contains(&source)
+                && account
+                    .and_then(|account| account.manager_key.as_ref())
+                    .is_none()
+            {
+                return Err(Error::RpcErrors(
+                    vec![rpc_error("contract.unrevealed_key")].into(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Applies a batch of operation contents and returns the metadata of each content.
+    ///
+    /// The fees are paid and the counters incremented even if a content fails, in which case
+    /// the effects of the whole batch are reverted. With `dry_run`, the state isn't modified.
+    pub fn apply(
+        &mut self,
+        contents: &[OperationContent],
+        operation_hash: &[u8],
+        dry_run: bool,
+    ) -> Result<Vec<Value>> {
+        let mut paid = self.accounts.clone();
+        for content in contents {
+            if let (Some(source), Some(counter)) = (content.source(), content.counter()) {
+                let account = paid.entry(source.value().into()).or_default();
+                account.balance = account
+                    .balance
+                    .saturating_sub(u64::try_from(content.fee()).unwrap_or_default());
+                account.counter = counter.to_integer()?;
+            }
+        }
+
+        let mut applied = paid.clone();
+        let mut results = vec![];
+        let mut failed = false;
+        for (index, content) in contents.iter().enumerate() {
+            if failed {
+                results.push(json!({ "status": "skipped" }));
+                continue;
+            }
+            let outcome = self
+                .on_operation
+                .as_ref()
+                .and_then(|on_operation| on_operation(content))
+                .unwrap_or_else(|| Outcome::default_for(content));
+            let result = match outcome {
+                Outcome::Applied {
+                    consumed_milligas,
+                    paid_storage_size_diff,
+                    storage,
+                } => Self::apply_content(&mut applied, content, operation_hash, index, storage)
+                    .map(|mut result| {
+                        result["consumed_milligas"] = consumed_milligas.to_string().into();
+                        if matches!(
+                            content,
+                            OperationContent::Transaction(_) | OperationContent::Origination(_)
+                        ) {
+                            result["paid_storage_size_diff"] =
+                                paid_storage_size_diff.to_string().into();
+                        }
+                        result
+                    }),
+                Outcome::Failed { errors } => Err(errors),
+            };
+            match result {
+                Ok(result) => results.push(result),
+                Err(errors) => {
+                    failed = true;
+                    for result in results.iter_mut() {
+                        result["status"] = "backtracked".into();
+                    }
+                    results.push(json!({ "status": "failed", "errors": errors }));
+                }
+            }
+        }
+
+        if !dry_run {
+            self.accounts = if failed { paid } else { applied };
+        }
+
+        Ok(results
+            .into_iter()
+            .map(|operation_result| {
+                json!({
+                    "balance_updates": [],
+                    "operation_result": operation_result,
+                    "internal_operation_results": [],
+                })
+            })
+            .collect())
+    }
+
+    /// Applies the effects of an operation content to the `accounts` and returns its result.
+    fn apply_content(
+        accounts: &mut HashMap<String, Account>,
+        content: &OperationContent,
+        operation_hash: &[u8],
+        index: usize,
+        storage: Option<Micheline>,
+    ) -> std::result::Result<Value, Vec<RpcError>> {
+        let mut result = Map::new();
+        result.insert("status".into(), "applied".into());
+
+        match content {
+            OperationContent::Reveal(reveal) => {
+                accounts
+                    .entry(reveal.source.value().into())
+                    .or_default()
+                    .manager_key = Some(reveal.public_key.value().into());
+            }
+            OperationContent::Transaction(transaction) => {
+                let amount = u64::try_from(transaction.amount).unwrap_or_default();
+                let destination = transaction.destination.value();
+                debit(accounts, transaction.source.value(), amount)?;
+                let account = match accounts.get_mut(destination) {
+                    Some(account) => account,
+                    None if destination.starts_with("KT1") => {
+                        return Err(vec![rpc_error("contract.non_existing_contract")])
+                    }
+                    None => {
+                        result.insert("allocated_destination_contract".into(), true.into());
+                        accounts.entry(destination.into()).or_default()
+                    }
+                };
+                account.balance += amount;
+                if let (Some(script), Some(storage)) = (account.script.as_mut(), storage) {
+                    script.storage = storage.clone();
+                    result.insert("storage".into(), to_value(&storage)?);
+                }
+            }
+            OperationContent::Origination(origination) => {
+                let balance = u64::try_from(origination.balance).unwrap_or_default();
+                debit(accounts, origination.source.value(), balance)?;
+                let address = contract_address(operation_hash, index)?;
+                accounts.insert(
+                    address.clone(),
+                    Account {
+                        balance,
+                        delegate: origination
+                            .delegate
+                            .as_ref()
+                            .map(|delegate| delegate.value().into()),
+                        script: Some(origination.script.clone().into()),
+                        ..Default::default()
+                    },
+                );
+                result.insert("originated_contracts".into(), json!([address]));
+            }
+            OperationContent::Delegation(delegation) => {
+                accounts
+                    .entry(delegation.source.value().into())
+                    .or_default()
+                    .delegate = delegation
+                    .delegate
+                    .as_ref()
+                    .map(|delegate| delegate.value().into());
+            }
+            _ => {}
+        }
+
+        Ok(Value::Object(result))
+    }
+
+    /// The hash of a big map key, as used in the big map RPCs.
+    pub fn script_expr(key: &Micheline, key_type: &Micheline) -> Result<ScriptExprHash> {
+        let packed = key
+            .clone()
+            .pack(Some(key_type))
+            .map_err(|_| Error::InvalidConversion)?;
+
+        Ok(ScriptExprHash::from_bytes(&blake2b(&packed, 32)?)?)
+    }
+
+    pub fn block_json(&self, block: &Block) -> Value {
+        let mut operations = vec![json!([]), json!([]), json!([])];
+        operations.push(Value::Array(block.operations.clone()));
+
+        json!({
+            "protocol": PROTOCOL,
+            "chain_id": CHAIN_ID,
+            "hash": block.hash.value(),
+            "header": self.header_json(block),
+            "metadata": {
+                "protocol": PROTOCOL,
+                "next_protocol": PROTOCOL,
+                "test_chain_status": { "status": "not_running" },
+                "max_operations_ttl": MAX_OPERATIONS_TTL,
+                "max_operation_data_length": 32768,
+                "max_block_header_length": 289,
+                "max_operation_list_length": [
+                    { "max_size": 4194304, "max_op": 2048 },
+                    { "max_size": 32768 },
+                    { "max_size": 135168, "max_op": 132 },
+                    { "max_size": 524288 }
+                ],
+            },
+            "operations": operations,
+        })
+    }
+
+    pub fn header_json(&self, block: &Block) -> Value {
+        let timestamp = NaiveDateTime::from_timestamp_opt(
+            GENESIS_TIMESTAMP + BLOCK_DELAY * block.level as i64,
+            0,
+        )
+        .unwrap_or_default();
+
+        json!({
+            "level": block.level,
+            "proto": 13,
+            "predecessor": block.predecessor.value(),
+            "timestamp": timestamp.format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+            "validation_pass": 4,
+            "operations_hash": OPERATIONS_HASH,
+            "fitness": [],
+            "context": CONTEXT_HASH,
+        })
+    }
+}
+
+fn debit(
+    accounts: &mut HashMap<String, Account>,
+    address: &str,
+    amount: u64,
+) -> std::result::Result<(), Vec<RpcError>> {
+    let account = accounts.entry(address.into()).or_default();
+    if account.balance < amount {
+        return Err(vec![RpcError {
+            amount: Some(amount.to_string()),
+            balance: Some(account.balance.to_string()),
+            contract: Some(address.into()),
+            ..rpc_error("tez.subtraction_underflow")
+        }]);
+    }
+    account.balance -= amount;
+
+    Ok(())
+}
+
+fn to_value<T: serde::Serialize>(value: &T) -> std::result::Result<Value, Vec<RpcError>> {
+    serde_json::to_value(value).map_err(|_| vec![rpc_error("invalid_value")])
+}
+
+/// The address of the contract originated by the `index`-th content of an operation.
+fn contract_address(
+    operation_hash: &[u8],
+    index: usize,
+) -> std::result::Result<String, Vec<RpcError>> {
+    let mut nonce = operation_hash.to_vec();
+    nonce.extend((index as u32).to_be_bytes());
+    blake2b(&nonce, 20)
+        .and_then(|hash| ContractHash::from_bytes(&hash))
+        .map(|hash| hash.value().into())
+        .map_err(|_| vec![rpc_error("contract.origination_failed")])
+}
+
+fn block_hash(predecessor: &str, level: i32) -> BlockHash {
+    let seed = format!("{}{}{}", CHAIN_ID, predecessor, level);
+    blake2b(seed.as_bytes(), 32)
+        .and_then(|hash| BlockHash::from_bytes(&hash))
+        .expect("a 32 bytes hash is a valid block hash")
+}
+
+/// An error of the current protocol, e.g. `contract.counter_in_the_past`.
+pub(super) fn rpc_error(id: &str) -> RpcError {
+    RpcError {
+        kind: "temporary".into(),
+        id: format!("proto.013-PtJakart.{}", id),
+        message: None,
+        msg: None,
+        amount: None,
+        balance: None,
+        contract: None,
+    }
+}
+
+pub(super) fn chain_id() -> ChainId {
+    CHAIN_ID.try_into().expect("the chain id is valid")
+}
@@ -0,0 +1,374 @@
+//! Following the chain block by block, including its reorganizations.
+//!
+//! ```rust
+//! use futures::TryStreamExt;
+//! use tezos_rpc::{client::TezosRpc, follower::{ChainEvent, ChainFollower}, Result};
+//!
+//! async fn example() -> Result<()> {
+//!     let rpc = TezosRpc::new("https://testnet-tezos.giganode.io".into());
+//!     let mut events = ChainFollower::new(&rpc).concurrency(8).into_stream();
+//!     while let Some(event) = events.try_next().await? {
+//!         match event {
+//!             ChainEvent::Applied(block) => println!("applied {}", block.header.level),
+//!             ChainEvent::Reverted(hash) => println!("reverted {:?}", hash),
+//!         }
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+use std::{collections::VecDeque, time::Duration};
+
+use futures::{
+    stream::{self, BoxStream},
+    StreamExt, TryStreamExt,
+};
+use futures_timer::Delay;
+use tezos_core::types::encoded::BlockHash;
+
+use crate::{
+    client::TezosRpc,
+    http::Http,
+    models::block::{Block, BlockId},
+    protocol_rpc::block::MetadataArg,
+    Error, Result,
+};
+
+pub use crate::inclusion::DEFAULT_POLL_INTERVAL;
+
+/// The default number of recent blocks remembered to detect reorganizations.
+pub const DEFAULT_WINDOW_SIZE: usize = 128;
+/// The default number of blocks fetched concurrently while backfilling.
+pub const DEFAULT_CONCURRENCY: usize = 4;
+
+/// The maximum number of blocks applied by a single [ChainFollower::poll].
+const BACKFILL_BATCH_SIZE: i32 = 256;
+
+/// A change of the chain, emitted in order by a [ChainFollower].
+#[derive(Debug, Clone)]
+pub enum ChainEvent {
+    /// The block has been added on top of the chain.
+    Applied(Box<Block>),
+    /// The block has been removed from the top of the chain by a reorganization.
+    Reverted(BlockHash),
+}
+
+/// Tracks the head of the chain and emits the blocks applied and reverted since the last poll.
+pub struct ChainFollower<'a, HttpClient: Http> {
+    rpc: &'a TezosRpc<HttpClient>,
+    /// The recently applied blocks, ordered by level.
+    window: VecDeque<(i32, BlockHash)>,
+    window_size: usize,
+    concurrency: usize,
+    poll_interval: Duration,
+}
+
+impl<'a, HttpClient: Http> ChainFollower<'a, HttpClient> {
+    /// Creates a follower starting at the current head of the chain.
+    pub fn new(rpc: &'a TezosRpc<HttpClient>) -> Self {
+        Self {
+            rpc,
+            window: VecDeque::new(),
+            window_size: DEFAULT_WINDOW_SIZE,
+            concurrency: DEFAULT_CONCURRENCY,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+        }
+    }
+
+    /// Resumes after the last block applied by a previous run, e.g. persisted from [ChainFollower::last_applied].
+    ///
+    /// The blocks baked since are backfilled. If the block itself is no longer part of the chain,
+    /// the follower fails with [Error::ReorganizationTooDeep] as the fork point can't be found.
+    pub fn resume_from(mut self, level: i32, hash: BlockHash) -> Self {
+        self.window.clear();
+        self.window.push_back((level, hash));
+
+        self
+    }
+
+    /// Modify the number of recent blocks remembered to detect reorganizations (default: [DEFAULT_WINDOW_SIZE]).
+    ///
+    /// A reorganization deeper than the window fails with [Error::ReorganizationTooDeep].
+    pub fn window_size(mut self, window_size: usize) -> Self {
+        self.window_size = window_size.max(1);
+
+        self
+    }
+
+    /// Modify the number of blocks fetched concurrently while backfilling (default: [DEFAULT_CONCURRENCY]).
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+
+        self
+    }
+
+    /// Modify the interval between two polls of the head of the chain (default: [DEFAULT_POLL_INTERVAL]).
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+
+        self
+    }
+
+    /// The level and hash of the last applied block.
+    pub fn last_applied(&self) -> Option<&(i32, BlockHash)> {
+        self.window.back()
+    }
+
+    /// Compares the chain with the recently applied blocks and returns the events needed to catch up with its head.
+    ///
+    /// At most a batch of blocks is applied at once, the next ones are applied by the following polls.
+    pub async fn poll(&mut self) -> Result<Vec<ChainEvent>> {
+        let head = self
+            .rpc
+            .get_block()
+            .metadata(MetadataArg::Never)
+            .send()
+            .await?;
+        match self.window.back() {
+            Some((_, hash)) if *hash == head.hash => return Ok(vec![]),
+            Some(_) => {}
+            None => {
+                let block_id = BlockId::Hash(head.hash);
+                let head = self.rpc.get_block().block_id(&block_id).send().await?;
+                self.push(head.header.level, head.hash.clone());
+                return Ok(vec![ChainEvent::Applied(Box::new(head))]);
+            }
+        }
+
+        // The window is only modified once all the blocks have been fetched, so that an error leaves it unchanged.
+        let fork_point = self.fork_point(head.header.level).await?;
+        let (tip_level, tip_hash) = self.window[fork_point - 1].clone();
+        let last_level = head.header.level.min(tip_level + BACKFILL_BATCH_SIZE);
+        let rpc = self.rpc;
+        let blocks = stream::iter(tip_level + 1..=last_level)
+            .map(|level| async move {
+                let block_id = BlockId::Level(level);
+                rpc.get_block().block_id(&block_id).send().await
+            })
+            .buffered(self.concurrency)
+            .try_collect::<Vec<_>>()
+            .await?;
+
+        let mut events = self
+            .window
+            .drain(fork_point..)
+            .rev()
+            .map(|(_, hash)| ChainEvent::Reverted(hash))
+            .collect::<Vec<_>>();
+        let mut predecessor = tip_hash;
+        for block in blocks {
+            // A reorganization happened while fetching, the rest is applied by the next poll.
+            if block.header.predecessor != predecessor {
+                break;
+            }
+            predecessor = block.hash.clone();
+            self.push(block.header.level, block.hash.clone());
+            events.push(ChainEvent::Applied(Box::new(block)));
+        }
+
+        Ok(events)
+    }
+
+    /// Turns the follower into a stream of events, polling the head of the chain when it has caught up.
+    pub fn into_stream(self) -> BoxStream<'a, Result<ChainEvent>>
+    where
+        HttpClient: Send + Sync,
+    {
+        stream::try_unfold(
+            (self, VecDeque::new()),
+            |(mut follower, mut pending)| async move {
+                loop {
+                    if let Some(event) = pending.pop_front() {
+                        return Ok(Some((event, (follower, pending))));
+                    }
+                    pending.extend(follower.poll().await?);
+                    if pending.is_empty() {
+                        Delay::new(follower.poll_interval).await;
+                    }
+                }
+            },
+        )
+        .boxed()
+    }
+
+    /// Finds the number of recently applied blocks which are still part of the chain, without modifying the window.
+    async fn fork_point(&self, head_level: i32) -> Result<usize> {
+        for (index, (level, hash)) in self.window.iter().enumerate().rev() {
+            let is_reverted = *level > head_level || {
+                let block_id = BlockId::Level(*level);
+                self.rpc.get_block_hash().block_id(&block_id).send().await? != *hash
+            };
+            if !is_reverted {
+                return Ok(index + 1);
+            }
+        }
+
+        Err(Error::ReorganizationTooDeep)
+    }
+
+    fn push(&mut self, level: i32, hash: BlockHash) {
+        if self.window.len() == self.window_size {
+            self.window.pop_front();
+        }
+        self.window.push_back((level, hash));
+    }
+}
+
+#[cfg(all(test, feature = "http"))]
+mod tests {
+    use {
+        super::*,
+        crate::internal::test_blocks::{self, *},
+        httpmock::prelude::*,
+        tezos_core::types::encoded::Encoded,
+    };
+
+    fn mock_block<'a>(
+        server: &'a MockServer,
+        block_id: &str,
+        hash: &str,
+        level: i32,
+        predecessor: &str,
+    ) -> Vec<httpmock::Mock<'a>> {
+        vec![
+            test_blocks::mock_block(server, block_id, block(hash, level, predecessor, 120)),
+            server.mock(|when, then| {
+                when.method(GET)
+                    .path(format!("/chains/main/blocks/{}/hash", block_id));
+                then.status(200)
+                    .header("content-type", "application/json")
+                    .json_body(hash);
+            }),
+        ]
+    }
+
+    fn summary(events: &[ChainEvent]) -> Vec<String> {
+        events
+            .iter()
+            .map(|event| match event {
+                ChainEvent::Applied(block) => format!("+{}", block.hash.value()),
+                ChainEvent::Reverted(hash) => format!("-{}", hash.value()),
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_follow_chain() -> Result<()> {
+        let server = MockServer::start();
+        let rpc_url = server.base_url();
+
+        let mut mocks = vec![
+            mock_block(&server, "head", BLOCK_A101, 101, BLOCK_A100),
+            mock_block(&server, "101", BLOCK_A101, 101, BLOCK_A100),
+            mock_block(&server, "100", BLOCK_A100, 100, BLOCK_A99),
+        ];
+        mock_block(&server, "99", BLOCK_A99, 99, BLOCK_A99);
+
+        let client = TezosRpc::new(rpc_url);
+        let mut follower = ChainFollower::new(&client)
+            .resume_from(99, BLOCK_A99.try_into()?)
+            .concurrency(2);
+
+        // Backfills from the persisted level.
+        assert_eq!(
+            summary(&follower.poll().await?),
+            vec![format!("+{}", BLOCK_A100), format!("+{}", BLOCK_A101)]
+        );
+        assert!(follower.poll().await?.is_empty());
+        assert_eq!(follower.last_applied().unwrap().0, 101);
+
+        // Switches to a longer branch forking after the persisted level.
+        for mut mock in mocks.drain(..).flatten() {
+            mock.delete();
+        }
+        mock_block(&server, "head", BLOCK_B102, 102, BLOCK_B101);
+        mock_block(&server, "102", BLOCK_B102, 102, BLOCK_B101);
+        mock_block(&server, "101", BLOCK_B101, 101, BLOCK_B100);
+        mock_block(&server, "100", BLOCK_B100, 100, BLOCK_A99);
+
+        assert_eq!(
+            summary(&follower.poll().await?),
+            vec![
+                format!("-{}", BLOCK_A101),
+                format!("-{}", BLOCK_A100),
+                format!("+{}", BLOCK_B100),
+                format!("+{}", BLOCK_B101),
+                format!("+{}", BLOCK_B102),
+            ]
+        );
+        assert_eq!(follower.last_applied().unwrap().1.value(), BLOCK_B102);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_follow_chain_transient_error() -> Result<()> {
+        let server = MockServer::start();
+        let rpc_url = server.base_url();
+
+        let mut mocks = vec![
+            mock_block(&server, "head", BLOCK_A101, 101, BLOCK_A100),
+            mock_block(&server, "101", BLOCK_A101, 101, BLOCK_A100),
+            mock_block(&server, "100", BLOCK_A100, 100, BLOCK_A99),
+        ];
+        mock_block(&server, "99", BLOCK_A99, 99, BLOCK_A99);
+
+        let client = TezosRpc::new(rpc_url);
+        let mut follower = ChainFollower::new(&client).resume_from(99, BLOCK_A99.try_into()?);
+        assert_eq!(follower.poll().await?.len(), 2);
+
+        // The node fails while the fork point is searched.
+        for mut mock in mocks.drain(..).flatten() {
+            mock.delete();
+        }
+        mock_block(&server, "head", BLOCK_B102, 102, BLOCK_B101);
+        mock_block(&server, "102", BLOCK_B102, 102, BLOCK_B101);
+        mock_block(&server, "101", BLOCK_B101, 101, BLOCK_B100);
+        let mut failure = server.mock(|when, then| {
+            when.method(GET).path("/chains/main/blocks/100/hash");
+            then.status(500)
+                .header("content-type", "text/plain")
+                .body("Internal error");
+        });
+
+        assert!(follower.poll().await.is_err());
+        assert_eq!(follower.last_applied().unwrap().1.value(), BLOCK_A101);
+
+        // The reverted blocks are emitted once the node recovers.
+        failure.delete();
+        mock_block(&server, "100", BLOCK_B100, 100, BLOCK_A99);
+
+        assert_eq!(
+            summary(&follower.poll().await?),
+            vec![
+                format!("-{}", BLOCK_A101),
+                format!("-{}", BLOCK_A100),
+                format!("+{}", BLOCK_B100),
+                format!("+{}", BLOCK_B101),
+                format!("+{}", BLOCK_B102),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_follow_chain_too_deep() -> Result<()> {
+        let server = MockServer::start();
+        let rpc_url = server.base_url();
+
+        mock_block(&server, "head", BLOCK_B101, 101, BLOCK_B100);
+        mock_block(&server, "100", BLOCK_B100, 100, BLOCK_A99);
+
+        let client = TezosRpc::new(rpc_url);
+        let mut follower = ChainFollower::new(&client).resume_from(100, BLOCK_A100.try_into()?);
+
+        assert!(matches!(
+            follower.poll().await,
+            Err(Error::ReorganizationTooDeep)
+        ));
+        assert_eq!(follower.last_applied().unwrap().1.value(), BLOCK_A100);
+
+        Ok(())
+    }
+}
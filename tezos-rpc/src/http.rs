@@ -1,9 +1,13 @@
 use {
     crate::error::Error,
     async_trait::async_trait,
+    futures::stream::BoxStream,
     serde::{de::DeserializeOwned, Serialize},
 };
 
+/// The chunks of a response body, as they're received.
+pub type ByteStream = BoxStream<'static, Result<Vec<u8>, Error>>;
+
 #[async_trait]
 pub trait Http {
     fn new(rpc_endpoint: String) -> Self;
@@ -36,19 +40,47 @@ pub trait Http {
         url: &str,
         body: Option<&B>,
     ) -> Result<T, Error>;
+
+    /// Returns a copy of the client which additionally sends the `headers` with each request,
+    /// or `None` if the implementation doesn't support custom headers.
+    fn with_headers(&self, _headers: &[(String, String)]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        None
+    }
+}
+
+/// An extension of [Http] for the streaming RPCs (e.g. `/monitor/heads/<chain_id>`), whose responses
+/// are chunked sequences of JSON values kept open by the node.
+#[async_trait]
+pub trait HttpStream: Http + Send + Sync {
+    /// Opens a `GET` request with query parameters to a URL and returns the body of the response as a [ByteStream].
+    async fn get_stream<Q: Serialize + ?Sized + Sync>(
+        &self,
+        url: &str,
+        query: &Q,
+    ) -> Result<ByteStream, Error>;
 }
 
+pub mod cache;
+pub mod fixtures;
+pub mod middleware;
+pub mod resilient;
+
 #[cfg(feature = "http")]
 pub mod default {
     use crate::models::error::RpcError;
 
     use super::*;
-    use reqwest::{Client, Response};
+    use futures::{StreamExt, TryStreamExt};
+    use reqwest::{header::RETRY_AFTER, Client, Method, RequestBuilder, Response, StatusCode};
 
-    #[derive(Debug)]
+    #[derive(Debug, Clone)]
     pub struct HttpClient {
         rpc_endpoint: String,
         client: Client,
+        headers: Vec<(String, String)>,
     }
 
     impl HttpClient {
@@ -56,11 +88,34 @@ pub mod default {
             format!("{}{}", self.rpc_endpoint, path)
         }
 
+        fn request(&self, method: Method, path: &str) -> RequestBuilder {
+            self.headers.iter().fold(
+                self.client.request(method, self.url(path)),
+                |request, (name, value)| request.header(name, value),
+            )
+        }
+
         async fn handle_response<T: DeserializeOwned>(
             &self,
             response: Response,
         ) -> Result<T, Error> {
-            if response.status() != 200 {
+            Ok(self.check_status(response).await?.json().await?)
+        }
+
+        async fn check_status(&self, response: Response) -> Result<Response, Error> {
+            let status = response.status();
+            if status == StatusCode::TOO_MANY_REQUESTS || status == StatusCode::SERVICE_UNAVAILABLE
+            {
+                return Err(Error::ServiceUnavailable {
+                    status: status.as_u16(),
+                    retry_after: response
+                        .headers()
+                        .get(RETRY_AFTER)
+                        .and_then(|value| value.to_str().ok())
+                        .and_then(resilient::parse_retry_after),
+                });
+            }
+            if status != 200 {
                 // Do not parse JSON when the content type is `plain/text`
                 if response.headers()["content-type"] == "application/json" {
                     let errors: Vec<RpcError> = response.json().await?;
@@ -71,7 +126,7 @@ pub mod default {
                 });
             }
 
-            Ok(response.json().await?)
+            Ok(response)
         }
     }
 
@@ -83,6 +138,7 @@ pub mod default {
             Self {
                 rpc_endpoint,
                 client: Client::new(),
+                headers: vec![],
             }
         }
 
@@ -90,9 +146,17 @@ pub mod default {
             self.rpc_endpoint = rpc_endpoint;
         }
 
+        /// Returns a copy of the client which additionally sends the `headers` with each request.
+        fn with_headers(&self, headers: &[(String, String)]) -> Option<Self> {
+            let mut client = self.clone();
+            client.headers.extend_from_slice(headers);
+
+            Some(client)
+        }
+
         /// Convenience method to make a `GET` request to a URL.
         async fn get<T: DeserializeOwned>(&self, url: &str) -> Result<T, Error> {
-            self.handle_response(self.client.get(self.url(url)).send().await?)
+            self.handle_response(self.request(Method::GET, url).send().await?)
                 .await
         }
 
@@ -102,7 +166,7 @@ pub mod default {
             url: &str,
             query: &Q,
         ) -> Result<T, Error> {
-            self.handle_response(self.client.get(self.url(url)).query(query).send().await?)
+            self.handle_response(self.request(Method::GET, url).query(query).send().await?)
                 .await
         }
 
@@ -114,8 +178,7 @@ pub mod default {
             query: Option<&Q>,
         ) -> Result<T, Error> {
             self.handle_response(
-                self.client
-                    .post(self.url(url))
+                self.request(Method::POST, url)
                     .query(&query)
                     .json(body)
                     .send()
@@ -130,7 +193,7 @@ pub mod default {
             url: &str,
             body: Option<&B>,
         ) -> Result<T, Error> {
-            let mut req = self.client.patch(self.url(url));
+            let mut req = self.request(Method::PATCH, url);
 
             if let Some(json) = body {
                 req = req.json(json);
@@ -145,7 +208,7 @@ pub mod default {
             url: &str,
             body: Option<&B>,
         ) -> Result<T, Error> {
-            let mut req = self.client.delete(self.url(url));
+            let mut req = self.request(Method::DELETE, url);
 
             if let Some(json) = body {
                 req = req.json(json);
@@ -154,4 +217,24 @@ pub mod default {
             self.handle_response(req.send().await?).await
         }
     }
+
+    #[async_trait]
+    impl HttpStream for HttpClient {
+        /// Opens a streaming `GET` request with query parameters to a URL.
+        async fn get_stream<Q: Serialize + ?Sized + Sync>(
+            &self,
+            url: &str,
+            query: &Q,
+        ) -> Result<ByteStream, Error> {
+            let response = self
+                .check_status(self.request(Method::GET, url).query(query).send().await?)
+                .await?;
+
+            Ok(response
+                .bytes_stream()
+                .map_ok(|chunk| chunk.to_vec())
+                .map_err(Error::from)
+                .boxed())
+        }
+    }
 }
@@ -0,0 +1,671 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::{self, Debug},
+    fs,
+    future::Future,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use serde_json::Value;
+use tezos_core::internal::crypto::blake2b;
+
+use super::*;
+
+/// The default number of responses kept by a [MemoryCache].
+pub const DEFAULT_CAPACITY: usize = 1024;
+/// The default number of blocks baked on top of a block before its level is considered final.
+///
+/// Tenderbake finalizes a block once two blocks have been baked on top of it.
+pub const DEFAULT_CONFIRMATIONS: u32 = 2;
+
+/// A storage for the responses cached by a [CachedHttp].
+///
+/// A cache is best-effort: a store which fails to read or write an entry treats it as missing.
+pub trait CacheStore: Send + Sync {
+    /// Returns the value stored for the `key`, unless it has expired.
+    fn get(&self, key: &str) -> Option<Vec<u8>>;
+
+    /// Stores the `value` for the `key`, for at most `ttl` if set.
+    fn insert(&self, key: &str, value: Vec<u8>, ttl: Option<Duration>);
+
+    /// Removes all the stored values.
+    fn clear(&self);
+}
+
+/// An in-memory [CacheStore], which evicts the least recently used values once it's full.
+#[derive(Debug)]
+pub struct MemoryCache {
+    capacity: usize,
+    state: Mutex<MemoryCacheState>,
+}
+
+#[derive(Debug, Default)]
+struct MemoryCacheState {
+    entries: HashMap<String, MemoryCacheEntry>,
+    /// The keys of the entries by the time they've last been used.
+    usage: BTreeMap<u64, String>,
+    time: u64,
+}
+
+#[derive(Debug)]
+struct MemoryCacheEntry {
+    value: Vec<u8>,
+    expires_at: Option<Instant>,
+    used_at: u64,
+}
+
+impl MemoryCache {
+    /// Creates a cache keeping at most `capacity` values.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            state: Default::default(),
+        }
+    }
+
+    /// The number of stored values, including the expired ones not evicted yet.
+    pub fn len(&self) -> usize {
+        self.state
+            .lock()
+            .map(|state| state.entries.len())
+            .unwrap_or_default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for MemoryCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl MemoryCacheState {
+    fn touch(&mut self, key: &str) -> u64 {
+        self.time += 1;
+        if let Some(entry) = self.entries.get_mut(key) {
+            self.usage.remove(&entry.used_at);
+            entry.used_at = self.time;
+        }
+        self.usage.insert(self.time, key.into());
+
+        self.time
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.usage.remove(&entry.used_at);
+        }
+    }
+}
+
+impl CacheStore for MemoryCache {
+    fn get(&self, key: &str) -> Option<Vec<u8>> {
+        let mut state = self.state.lock().ok()?;
+        let is_expired = state
+            .entries
+            .get(key)?
+            .expires_at
+            .map_or(false, |expires_at| expires_at <= Instant::now());
+        if is_expired {
+            state.remove(key);
+            return None;
+        }
+        state.touch(key);
+
+        state.entries.get(key).map(|entry| entry.value.clone())
+    }
+
+    fn insert(&self, key: &str, value: Vec<u8>, ttl: Option<Duration>) {
+        if self.capacity == 0 {
+            return;
+        }
+        if let Ok(mut state) = self.state.lock() {
+            state.remove(key);
+            let used_at = state.touch(key);
+            state.entries.insert(
+                key.into(),
+                MemoryCacheEntry {
+                    value,
+                    expires_at: ttl.map(|ttl| Instant::now() + ttl),
+                    used_at,
+                },
+            );
+            while state.entries.len() > self.capacity {
+                let oldest = match state.usage.keys().next().cloned() {
+                    Some(used_at) => used_at,
+                    None => break,
+                };
+                if let Some(key) = state.usage.remove(&oldest) {
+                    state.entries.remove(&key);
+                }
+            }
+        }
+    }
+
+    fn clear(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            *state = Default::default();
+        }
+    }
+}
+
+/// A [CacheStore] keeping each value in a file of a directory, which persists across runs.
+///
+/// Nothing is evicted, the values which have expired are only removed when they're read.
+#[derive(Debug, Clone)]
+pub struct DiskCache {
+    directory: PathBuf,
+}
+
+impl DiskCache {
+    /// Creates a cache storing its values in `directory`, created if it doesn't exist.
+    pub fn new<P: Into<PathBuf>>(directory: P) -> Result<Self, Error> {
+        let directory = directory.into();
+        fs::create_dir_all(&directory)?;
+
+        Ok(Self { directory })
+    }
+
+    fn path(&self, key: &str) -> Option<PathBuf> {
+        let hash = blake2b(key.as_bytes(), 20).ok()?;
+
+        Some(self.directory.join(hex::encode(hash)))
+    }
+}
+
+/// The entries are stored as the key, the expiration timestamp (empty if none) and the value, separated by new lines.
+impl CacheStore for DiskCache {
+    fn get(&self, key: &str) -> Option<Vec<u8>> {
+        let path = self.path(key)?;
+        let bytes = fs::read(&path).ok()?;
+        let mut parts = bytes.splitn(3, |byte| *byte == b'\n');
+        if parts.next()? != key.as_bytes() {
+            return None;
+        }
+        let expires_at = std::str::from_utf8(parts.next()?).ok()?;
+        if !expires_at.is_empty() {
+            let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?;
+            if expires_at.parse::<u64>().ok()? <= now.as_secs() {
+                let _ = fs::remove_file(&path);
+                return None;
+            }
+        }
+
+        parts.next().map(|value| value.to_vec())
+    }
+
+    fn insert(&self, key: &str, value: Vec<u8>, ttl: Option<Duration>) {
+        let path = match self.path(key) {
+            Some(path) => path,
+            None => return,
+        };
+        let expires_at = ttl
+            .and_then(|ttl| (SystemTime::now() + ttl).duration_since(UNIX_EPOCH).ok())
+            .map(|expires_at| expires_at.as_secs().to_string())
+            .unwrap_or_default();
+        let mut bytes = format!("{}\n{}\n", key, expires_at).into_bytes();
+        bytes.extend(value);
+
+        // The file is replaced at once so a concurrent read never sees a partial entry.
+        let temporary = path.with_extension("tmp");
+        if fs::write(&temporary, bytes).is_ok() {
+            let _ = fs::rename(&temporary, &path);
+        }
+    }
+
+    fn clear(&self) {
+        if let Ok(entries) = fs::read_dir(&self.directory) {
+            for entry in entries.flatten() {
+                let _ = fs::remove_file(entry.path());
+            }
+        }
+    }
+}
+
+/// How a [CachedHttp] handles the requests to blocks addressed relatively to the head
+/// (e.g. `head` or `head~2`), whose responses change as new blocks are baked.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HeadRelative {
+    /// The requests are always sent to the node.
+    Bypass,
+    /// The responses are cached for `ttl`, during which a new head may not be seen.
+    Cache { ttl: Duration },
+}
+
+impl Default for HeadRelative {
+    fn default() -> Self {
+        Self::Bypass
+    }
+}
+
+/// An [Http] client caching the responses to the `GET` requests for data which can't change anymore.
+///
+/// The requests below `/chains/<chain_id>/blocks/<block_id>` are cached when the block is addressed
+/// by its hash, is the genesis block, or by a level which is final, i.e. with at least
+/// [DEFAULT_CONFIRMATIONS] blocks baked on top of it. The requests to the head or relative to it
+/// are handled as configured with [HeadRelative], the other requests are always sent to the node.
+///
+/// The responses are keyed by their URL, prefixed with a namespace which must be different
+/// for each network sharing a persistent [CacheStore], since block levels are only unique within a network.
+///
+/// ```rust
+/// # #[cfg(feature = "http")]
+/// # {
+/// use tezos_rpc::{
+///     client::TezosRpc,
+///     http::{cache::{CachedHttp, MemoryCache}, default::HttpClient, Http},
+/// };
+///
+/// let http_client = CachedHttp::from_client(
+///     HttpClient::new("https://rpc.tzbeta.net".into()),
+///     MemoryCache::new(4096),
+/// )
+/// .namespace("mainnet");
+/// let client = TezosRpc::new_rpc_with_http_client(http_client, Default::default());
+/// # }
+/// ```
+pub struct CachedHttp<HttpClient: Http> {
+    client: HttpClient,
+    store: Arc<dyn CacheStore>,
+    namespace: String,
+    head_relative: HeadRelative,
+    confirmations: u32,
+    /// The highest final level known for each chain.
+    final_levels: Arc<Mutex<HashMap<String, i64>>>,
+}
+
+/// How the response to a request is cached.
+#[derive(Debug, PartialEq)]
+enum Caching {
+    Never,
+    Forever,
+    For(Duration),
+    /// Cached forever if the level is final.
+    IfFinal {
+        chain_id: String,
+        level: i64,
+    },
+}
+
+impl<HttpClient: Http> CachedHttp<HttpClient> {
+    /// Creates a client wrapping the already configured `client`, which caches the responses in `store`.
+    pub fn from_client<S: CacheStore + 'static>(client: HttpClient, store: S) -> Self {
+        Self {
+            client,
+            store: Arc::new(store),
+            namespace: "".into(),
+            head_relative: Default::default(),
+            confirmations: DEFAULT_CONFIRMATIONS,
+            final_levels: Default::default(),
+        }
+    }
+
+    /// Modify the prefix of the cache keys, empty by default.
+    pub fn namespace<S: Into<String>>(mut self, namespace: S) -> Self {
+        self.namespace = namespace.into();
+
+        self
+    }
+
+    /// Modify how the requests relative to the head are handled (default: [HeadRelative::Bypass]).
+    pub fn head_relative(mut self, head_relative: HeadRelative) -> Self {
+        self.head_relative = head_relative;
+
+        self
+    }
+
+    /// Modify the number of blocks baked on top of a block before its level is considered final
+    /// (default: [DEFAULT_CONFIRMATIONS]).
+    pub fn confirmations(mut self, confirmations: u32) -> Self {
+        self.confirmations = confirmations;
+
+        self
+    }
+
+    /// The wrapped client.
+    pub fn client(&self) -> &HttpClient {
+        &self.client
+    }
+
+    /// The store of the cached responses.
+    pub fn store(&self) -> &dyn CacheStore {
+        self.store.as_ref()
+    }
+
+    fn caching(&self, path: &str) -> Caching {
+        let segments = path
+            .trim_start_matches('/')
+            .split(['/', '?'])
+            .collect::<Vec<_>>();
+        let (chain_id, block_id) = match segments.as_slice() {
+            ["chains", chain_id, "blocks", block_id, ..] => (*chain_id, *block_id),
+            _ => return Caching::Never,
+        };
+
+        if block_id == "genesis" || (block_id.starts_with('B') && block_id.len() == 51) {
+            return Caching::Forever;
+        }
+        if let Ok(level) = block_id.parse::<i64>() {
+            return Caching::IfFinal {
+                chain_id: chain_id.into(),
+                level,
+            };
+        }
+        let is_head_relative = block_id == "head"
+            || block_id
+                .strip_prefix("head")
+                .map_or(false, |offset| offset.starts_with(['~', '-']));
+        match self.head_relative {
+            HeadRelative::Cache { ttl } if is_head_relative => Caching::For(ttl),
+            _ => Caching::Never,
+        }
+    }
+
+    /// Resolves whether the level of a [Caching::IfFinal] request is final.
+    async fn resolve(&self, caching: Caching) -> Result<Caching, Error> {
+        match caching {
+            Caching::IfFinal { chain_id, level } if self.is_final(&chain_id, level).await? => {
+                Ok(Caching::Forever)
+            }
+            Caching::IfFinal { .. } => Ok(Caching::Never),
+            caching => Ok(caching),
+        }
+    }
+
+    /// Whether the `level` is final, the head is fetched only if it's higher than the last known final level.
+    async fn is_final(&self, chain_id: &str, level: i64) -> Result<bool, Error> {
+        if level <= self.final_level(chain_id) {
+            return Ok(true);
+        }
+        let header: Value = self
+            .client
+            .get(&format!("/chains/{}/blocks/head/header", chain_id))
+            .await?;
+        let head_level = header["level"].as_i64().ok_or(Error::InvalidConversion)?;
+        let final_level = head_level - self.confirmations as i64;
+        if let Ok(mut final_levels) = self.final_levels.lock() {
+            let known = final_levels.entry(chain_id.into()).or_insert(final_level);
+            *known = (*known).max(final_level);
+        }
+
+        Ok(level <= final_level)
+    }
+
+    fn final_level(&self, chain_id: &str) -> i64 {
+        self.final_levels
+            .lock()
+            .ok()
+            .and_then(|final_levels| final_levels.get(chain_id).copied())
+            .unwrap_or(-1)
+    }
+
+    async fn get_cached<T, F>(&self, path: &str, key: String, request: F) -> Result<T, Error>
+    where
+        T: DeserializeOwned,
+        F: Future<Output = Result<Value, Error>>,
+    {
+        let ttl = match self.resolve(self.caching(path)).await? {
+            Caching::Forever => None,
+            Caching::For(ttl) => Some(ttl),
+            _ => return Ok(serde_json::from_value(request.await?)?),
+        };
+        if let Some(bytes) = self.store.get(&key) {
+            if let Ok(value) = serde_json::from_slice(&bytes) {
+                return Ok(value);
+            }
+        }
+        let value = request.await?;
+        self.store.insert(&key, serde_json::to_vec(&value)?, ttl);
+
+        Ok(serde_json::from_value(value)?)
+    }
+}
+
+impl<HttpClient: Http + Debug> Debug for CachedHttp<HttpClient> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CachedHttp")
+            .field("client", &self.client)
+            .field("namespace", &self.namespace)
+            .field("head_relative", &self.head_relative)
+            .field("confirmations", &self.confirmations)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl<HttpClient: Http + Send + Sync> Http for CachedHttp<HttpClient> {
+    /// Creates a client to the endpoint caching the responses in a [MemoryCache].
+    fn new(rpc_endpoint: String) -> Self {
+        Self::from_client(HttpClient::new(rpc_endpoint), MemoryCache::default())
+    }
+
+    /// Changes the endpoint of the wrapped client, the cached responses are kept.
+    fn change_rpc_endpoint(&mut self, rpc_endpoint: String) {
+        self.client.change_rpc_endpoint(rpc_endpoint);
+    }
+
+    async fn get<T: DeserializeOwned>(&self, url: &str) -> Result<T, Error> {
+        let key = format!("{}{}", self.namespace, url);
+        self.get_cached(url, key, self.client.get(url)).await
+    }
+
+    async fn get_with_query<T: DeserializeOwned, Q: Serialize + ?Sized + Sync>(
+        &self,
+        url: &str,
+        query: &Q,
+    ) -> Result<T, Error> {
+        let key = format!(
+            "{}{}?{}",
+            self.namespace,
+            url,
+            serde_json::to_string(query)?
+        );
+        self.get_cached(url, key, self.client.get_with_query(url, query))
+            .await
+    }
+
+    async fn post<B: Serialize + Sync, T: DeserializeOwned, Q: Serialize + Sync>(
+        &self,
+        url: &str,
+        body: &B,
+        query: Option<&Q>,
+    ) -> Result<T, Error> {
+        self.client.post(url, body, query).await
+    }
+
+    async fn patch<B: Serialize + Sync, T: DeserializeOwned>(
+        &self,
+        url: &str,
+        body: Option<&B>,
+    ) -> Result<T, Error> {
+        self.client.patch(url, body).await
+    }
+
+    async fn delete<B: Serialize + Sync, T: DeserializeOwned>(
+        &self,
+        url: &str,
+        body: Option<&B>,
+    ) -> Result<T, Error> {
+        self.client.delete(url, body).await
+    }
+
+    /// Returns a copy of the client sharing the same cache.
+    fn with_headers(&self, headers: &[(String, String)]) -> Option<Self> {
+        Some(Self {
+            client: self.client.with_headers(headers)?,
+            store: self.store.clone(),
+            namespace: self.namespace.clone(),
+            head_relative: self.head_relative,
+            confirmations: self.confirmations,
+            final_levels: self.final_levels.clone(),
+        })
+    }
+}
+
+#[async_trait]
+impl<HttpClient: HttpStream> HttpStream for CachedHttp<HttpClient> {
+    /// Opens a streaming `GET` request, which is never cached.
+    async fn get_stream<Q: Serialize + ?Sized + Sync>(
+        &self,
+        url: &str,
+        query: &Q,
+    ) -> Result<ByteStream, Error> {
+        self.client.get_stream(url, query).await
+    }
+}
+
+#[cfg(all(test, feature = "http"))]
+mod tests {
+    use {super::*, crate::http::default::HttpClient, httpmock::prelude::*};
+
+    const BLOCK_HASH: &str = "BLq7VzUh9rvH57XxvE22tMsNyBciwzDmDuaGLHnhyhCt5raizQz";
+
+    fn mock_get<'a>(server: &'a MockServer, path: &str, body: Value) -> httpmock::Mock<'a> {
+        server.mock(|when, then| {
+            when.method(GET).path(path);
+            then.header("content-type", "application/json")
+                .json_body(body);
+        })
+    }
+
+    #[tokio::test]
+    async fn test_cache_immutable() -> Result<(), Error> {
+        let server = MockServer::start();
+        let hash_path = format!("/chains/main/blocks/{}/header", BLOCK_HASH);
+        let hash_mock = mock_get(&server, &hash_path, serde_json::json!({ "level": 99 }));
+        let head_mock = mock_get(
+            &server,
+            "/chains/main/blocks/head/header",
+            serde_json::json!({ "level": 100 }),
+        );
+        let final_mock = mock_get(
+            &server,
+            "/chains/main/blocks/98/header",
+            serde_json::json!({ "level": 98 }),
+        );
+        let recent_mock = mock_get(
+            &server,
+            "/chains/main/blocks/99/header",
+            serde_json::json!({ "level": 99 }),
+        );
+
+        let http = CachedHttp::<HttpClient>::new(server.base_url());
+        for _ in 0..2 {
+            let header: Value = http.get(&hash_path).await?;
+            assert_eq!(header["level"], 99);
+            let _: Value = http.get("/chains/main/blocks/98/header").await?;
+            let _: Value = http.get("/chains/main/blocks/99/header").await?;
+            let _: Value = http.get("/chains/main/blocks/head/header").await?;
+        }
+
+        hash_mock.assert_hits(1);
+        final_mock.assert_hits(1);
+        // The level 99 isn't final yet, the head is fetched again to check it each time.
+        recent_mock.assert_hits(2);
+        head_mock.assert_hits(5);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cache_head_relative() -> Result<(), Error> {
+        let server = MockServer::start();
+        let head_mock = mock_get(
+            &server,
+            "/chains/main/blocks/head~2/hash",
+            serde_json::json!(BLOCK_HASH),
+        );
+
+        let http =
+            CachedHttp::from_client(HttpClient::new(server.base_url()), MemoryCache::default())
+                .head_relative(HeadRelative::Cache {
+                    ttl: Duration::from_millis(50),
+                });
+        let _: String = http.get("/chains/main/blocks/head~2/hash").await?;
+        let _: String = http.get("/chains/main/blocks/head~2/hash").await?;
+        head_mock.assert_hits(1);
+
+        futures_timer::Delay::new(Duration::from_millis(60)).await;
+        let _: String = http.get("/chains/main/blocks/head~2/hash").await?;
+        head_mock.assert_hits(2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_caching() {
+        let http = CachedHttp::<HttpClient>::new("http://localhost".into());
+        let cases = [
+            (
+                format!("/chains/main/blocks/{}", BLOCK_HASH),
+                Caching::Forever,
+            ),
+            (
+                "/chains/main/blocks/genesis/header".into(),
+                Caching::Forever,
+            ),
+            (
+                "/chains/main/blocks/42/context/constants".into(),
+                Caching::IfFinal {
+                    chain_id: "main".into(),
+                    level: 42,
+                },
+            ),
+            ("/chains/main/blocks/head~2/hash".into(), Caching::Never),
+            ("/chains/main/blocks".into(), Caching::Never),
+            (
+                "/chains/main/mempool/pending_operations".into(),
+                Caching::Never,
+            ),
+        ];
+        for (path, caching) in cases {
+            assert_eq!(http.caching(&path), caching, "{}", path);
+        }
+    }
+
+    #[test]
+    fn test_memory_cache_eviction() {
+        let cache = MemoryCache::new(2);
+        cache.insert("a", vec![1], None);
+        cache.insert("b", vec![2], None);
+        assert_eq!(cache.get("a"), Some(vec![1]));
+        cache.insert("c", vec![3], None);
+
+        // `b` is the least recently used value.
+        assert_eq!(cache.get("b"), None);
+        assert_eq!(cache.get("a"), Some(vec![1]));
+        assert_eq!(cache.get("c"), Some(vec![3]));
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn test_disk_cache() -> Result<(), Error> {
+        let directory =
+            std::env::temp_dir().join(format!("tezos-rpc-disk-cache-{}", std::process::id()));
+        let cache = DiskCache::new(&directory)?;
+        cache.insert("/chains/main/blocks/1/hash", b"\"BL\"".to_vec(), None);
+        cache.insert(
+            "/chains/main/blocks/head/hash",
+            b"\"BL\"".to_vec(),
+            Some(Duration::ZERO),
+        );
+
+        let reopened = DiskCache::new(&directory)?;
+        assert_eq!(
+            reopened.get("/chains/main/blocks/1/hash"),
+            Some(b"\"BL\"".to_vec())
+        );
+        assert_eq!(reopened.get("/chains/main/blocks/head/hash"), None);
+
+        reopened.clear();
+        assert_eq!(reopened.get("/chains/main/blocks/1/hash"), None);
+        fs::remove_dir_all(directory)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,542 @@
+use std::{
+    collections::HashMap,
+    fmt::{self, Debug},
+    fs,
+    future::Future,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use serde::Deserialize;
+use serde_json::Value;
+use tezos_core::internal::crypto::blake2b;
+
+use super::{middleware::Method, *};
+use crate::models::error::RpcErrors;
+
+/// The directory the fixtures are recorded to and replayed from when the clients are created with [Http::new].
+pub const DEFAULT_DIRECTORY: &str = "__TEST_DATA__";
+
+/// A request recorded by a [RecordingHttp], along with its response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Fixture {
+    pub method: Method,
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub query: Value,
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub body: Value,
+    /// The position of the response among the ones recorded for the same request.
+    #[serde(default)]
+    pub sequence: usize,
+    pub response: FixtureResponse,
+}
+
+/// A recorded response, either the JSON value returned by the node or the error it failed with.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FixtureResponse {
+    Ok(Value),
+    RpcErrors(RpcErrors),
+    RpcErrorPlain(String),
+}
+
+impl Fixture {
+    /// Loads all the fixtures of a `directory`.
+    pub fn load<P: AsRef<Path>>(directory: P) -> Result<Vec<Self>, Error> {
+        let mut fixtures = vec![];
+        for entry in fs::read_dir(directory)? {
+            let path = entry?.path();
+            if path
+                .extension()
+                .map_or(false, |extension| extension == "json")
+            {
+                fixtures.push(serde_json::from_slice(&fs::read(path)?)?);
+            }
+        }
+
+        Ok(fixtures)
+    }
+
+    fn into_result<T: DeserializeOwned>(response: FixtureResponse) -> Result<T, Error> {
+        match response {
+            FixtureResponse::Ok(value) => Ok(serde_json::from_value(value)?),
+            FixtureResponse::RpcErrors(errors) => Err(Error::RpcErrors(errors)),
+            FixtureResponse::RpcErrorPlain(description) => {
+                Err(Error::RpcErrorPlain { description })
+            }
+        }
+    }
+}
+
+/// The key a request is matched on: its method, path, query and body.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct FixtureKey {
+    method: Method,
+    path: String,
+    query: String,
+    body: String,
+}
+
+impl FixtureKey {
+    fn new(method: Method, path: &str, query: &Value, body: &Value) -> Self {
+        Self {
+            method,
+            path: path.into(),
+            query: canonical(query),
+            body: canonical(body),
+        }
+    }
+
+    /// A file name derived from the request, e.g. `get_chains_main_blocks_head_header_3f1a9c20.json`.
+    fn file_name(&self, sequence: usize) -> String {
+        let path = self
+            .path
+            .trim_matches('/')
+            .replace(|c: char| !c.is_ascii_alphanumeric(), "_");
+        let path = &path[..path.len().min(96)];
+        let hash = blake2b(
+            format!("{}{}?{}{}", self.method, self.path, self.query, self.body).as_bytes(),
+            4,
+        )
+        .map(hex::encode)
+        .unwrap_or_default();
+        let name = format!(
+            "{}_{}_{}",
+            self.method.to_string().to_lowercase(),
+            path,
+            hash
+        );
+        if sequence == 0 {
+            format!("{}.json", name)
+        } else {
+            format!("{}_{}.json", name, sequence)
+        }
+    }
+
+    fn description(&self) -> String {
+        let mut description = self.path.clone();
+        if !self.query.is_empty() {
+            description += &format!(" query {}", self.query);
+        }
+        if !self.body.is_empty() {
+            description += &format!(" body {}", self.body);
+        }
+        description
+    }
+}
+
+/// The JSON string of a query or body, empty if there's none.
+fn canonical(value: &Value) -> String {
+    match value {
+        Value::Null => "".into(),
+        Value::Array(values) if values.is_empty() => "".into(),
+        Value::Object(values) if values.is_empty() => "".into(),
+        value => value.to_string(),
+    }
+}
+
+fn to_value<V: Serialize + ?Sized>(value: Option<&V>) -> Result<Value, Error> {
+    Ok(value
+        .map(serde_json::to_value)
+        .transpose()?
+        .unwrap_or_default())
+}
+
+/// An [Http] client writing each request sent through a wrapped client and its response
+/// to a JSON [Fixture] file, to be served later by a [ReplayHttp].
+///
+/// The successful responses and the errors returned by the node are recorded, the other errors
+/// (e.g. connection errors) are only returned. The streaming RPCs aren't recorded.
+///
+/// ```rust
+/// # #[cfg(feature = "http")]
+/// # {
+/// use tezos_rpc::{
+///     client::TezosRpc,
+///     http::{default::HttpClient, fixtures::RecordingHttp, Http},
+/// };
+///
+/// let http_client = RecordingHttp::from_client(
+///     HttpClient::new("https://rpc.tzbeta.net".into()),
+///     "tests/__TEST_DATA__/transfer",
+/// );
+/// let client = TezosRpc::new_rpc_with_http_client(http_client, Default::default());
+/// # }
+/// ```
+pub struct RecordingHttp<HttpClient: Http> {
+    client: HttpClient,
+    directory: PathBuf,
+    /// The number of responses recorded for each request.
+    recorded: Arc<Mutex<HashMap<FixtureKey, usize>>>,
+}
+
+impl<HttpClient: Http> RecordingHttp<HttpClient> {
+    /// Creates a client recording the requests sent through `client` to `directory`,
+    /// which is created on the first recording if it doesn't exist.
+    pub fn from_client<P: Into<PathBuf>>(client: HttpClient, directory: P) -> Self {
+        Self {
+            client,
+            directory: directory.into(),
+            recorded: Default::default(),
+        }
+    }
+
+    /// The wrapped client.
+    pub fn client(&self) -> &HttpClient {
+        &self.client
+    }
+
+    async fn record<T, F>(
+        &self,
+        method: Method,
+        path: &str,
+        query: Value,
+        body: Value,
+        request: F,
+    ) -> Result<T, Error>
+    where
+        T: DeserializeOwned,
+        F: Future<Output = Result<Value, Error>>,
+    {
+        let response = match request.await {
+            Ok(value) => FixtureResponse::Ok(value),
+            Err(Error::RpcErrors(errors)) => FixtureResponse::RpcErrors(errors),
+            Err(Error::RpcErrorPlain { description }) => {
+                FixtureResponse::RpcErrorPlain(description)
+            }
+            Err(error) => return Err(error),
+        };
+
+        let key = FixtureKey::new(method, path, &query, &body);
+        let sequence = match self.recorded.lock() {
+            Ok(mut recorded) => {
+                let count = recorded.entry(key.clone()).or_default();
+                *count += 1;
+                *count - 1
+            }
+            Err(_) => 0,
+        };
+        let fixture = Fixture {
+            method,
+            path: path.into(),
+            query,
+            body,
+            sequence,
+            response,
+        };
+        fs::create_dir_all(&self.directory)?;
+        fs::write(
+            self.directory.join(key.file_name(sequence)),
+            serde_json::to_vec_pretty(&fixture)?,
+        )?;
+
+        Fixture::into_result(fixture.response)
+    }
+}
+
+impl<HttpClient: Http + Debug> Debug for RecordingHttp<HttpClient> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RecordingHttp")
+            .field("client", &self.client)
+            .field("directory", &self.directory)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl<HttpClient: Http + Send + Sync> Http for RecordingHttp<HttpClient> {
+    /// Creates a client to the endpoint recording to [DEFAULT_DIRECTORY].
+    fn new(rpc_endpoint: String) -> Self {
+        Self::from_client(HttpClient::new(rpc_endpoint), DEFAULT_DIRECTORY)
+    }
+
+    fn change_rpc_endpoint(&mut self, rpc_endpoint: String) {
+        self.client.change_rpc_endpoint(rpc_endpoint);
+    }
+
+    async fn get<T: DeserializeOwned>(&self, url: &str) -> Result<T, Error> {
+        self.record(
+            Method::Get,
+            url,
+            Value::Null,
+            Value::Null,
+            self.client.get(url),
+        )
+        .await
+    }
+
+    async fn get_with_query<T: DeserializeOwned, Q: Serialize + ?Sized + Sync>(
+        &self,
+        url: &str,
+        query: &Q,
+    ) -> Result<T, Error> {
+        let request = self.client.get_with_query(url, query);
+        self.record(
+            Method::Get,
+            url,
+            to_value(Some(query))?,
+            Value::Null,
+            request,
+        )
+        .await
+    }
+
+    async fn post<B: Serialize + Sync, T: DeserializeOwned, Q: Serialize + Sync>(
+        &self,
+        url: &str,
+        body: &B,
+        query: Option<&Q>,
+    ) -> Result<T, Error> {
+        let request = self.client.post(url, body, query);
+        self.record(
+            Method::Post,
+            url,
+            to_value(query)?,
+            to_value(Some(body))?,
+            request,
+        )
+        .await
+    }
+
+    async fn patch<B: Serialize + Sync, T: DeserializeOwned>(
+        &self,
+        url: &str,
+        body: Option<&B>,
+    ) -> Result<T, Error> {
+        let request = self.client.patch(url, body);
+        self.record(Method::Patch, url, Value::Null, to_value(body)?, request)
+            .await
+    }
+
+    async fn delete<B: Serialize + Sync, T: DeserializeOwned>(
+        &self,
+        url: &str,
+        body: Option<&B>,
+    ) -> Result<T, Error> {
+        let request = self.client.delete(url, body);
+        self.record(Method::Delete, url, Value::Null, to_value(body)?, request)
+            .await
+    }
+
+    fn with_headers(&self, headers: &[(String, String)]) -> Option<Self> {
+        Some(Self {
+            client: self.client.with_headers(headers)?,
+            directory: self.directory.clone(),
+            recorded: self.recorded.clone(),
+        })
+    }
+}
+
+/// An [Http] client serving the responses recorded by a [RecordingHttp], without any network access.
+///
+/// A request is matched on its method, path, query and body, and fails with [Error::FixtureNotFound]
+/// if no response has been recorded for it. The responses recorded for the same request are served
+/// in the order they've been recorded in, the last one being repeated once all of them have been served.
+///
+/// ```rust
+/// use tezos_rpc::{client::TezosRpc, http::fixtures::ReplayHttp, Result};
+///
+/// async fn example() -> Result<()> {
+///     let http_client = ReplayHttp::from_directory("tests/__TEST_DATA__/transfer")?;
+///     let rpc = TezosRpc::new_rpc_with_http_client(http_client, Default::default());
+///     let block = rpc.get_block().send().await?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ReplayHttp {
+    responses: Arc<HashMap<FixtureKey, Vec<FixtureResponse>>>,
+    /// The number of times each request has been served.
+    served: Arc<Mutex<HashMap<FixtureKey, usize>>>,
+}
+
+impl ReplayHttp {
+    /// Creates a client serving the fixtures of a `directory`.
+    pub fn from_directory<P: AsRef<Path>>(directory: P) -> Result<Self, Error> {
+        Ok(Self::from_fixtures(Fixture::load(directory)?))
+    }
+
+    /// Creates a client serving the `fixtures`.
+    pub fn from_fixtures<I: IntoIterator<Item = Fixture>>(fixtures: I) -> Self {
+        let mut fixtures = fixtures.into_iter().collect::<Vec<_>>();
+        fixtures.sort_by_key(|fixture| fixture.sequence);
+
+        let mut responses: HashMap<FixtureKey, Vec<FixtureResponse>> = HashMap::new();
+        for fixture in fixtures {
+            let key = FixtureKey::new(fixture.method, &fixture.path, &fixture.query, &fixture.body);
+            responses.entry(key).or_default().push(fixture.response);
+        }
+
+        Self {
+            responses: Arc::new(responses),
+            served: Default::default(),
+        }
+    }
+
+    fn replay<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        query: Value,
+        body: Value,
+    ) -> Result<T, Error> {
+        let key = FixtureKey::new(method, path, &query, &body);
+        let responses = self
+            .responses
+            .get(&key)
+            .filter(|responses| !responses.is_empty())
+            .ok_or_else(|| Error::FixtureNotFound {
+                method: method.to_string(),
+                request: key.description(),
+            })?;
+        let index = match self.served.lock() {
+            Ok(mut served) => {
+                let count = served.entry(key.clone()).or_default();
+                *count += 1;
+                *count - 1
+            }
+            Err(_) => 0,
+        };
+
+        Fixture::into_result(responses[index.min(responses.len() - 1)].clone())
+    }
+}
+
+#[async_trait]
+impl Http for ReplayHttp {
+    /// Creates a client serving the fixtures of the `rpc_endpoint` directory.
+    ///
+    /// Panics if the fixtures can't be loaded, use [ReplayHttp::from_directory] to handle the error.
+    fn new(rpc_endpoint: String) -> Self {
+        Self::from_directory(rpc_endpoint).expect("the fixtures can be loaded")
+    }
+
+    fn change_rpc_endpoint(&mut self, _rpc_endpoint: String) {}
+
+    async fn get<T: DeserializeOwned>(&self, url: &str) -> Result<T, Error> {
+        self.replay(Method::Get, url, Value::Null, Value::Null)
+    }
+
+    async fn get_with_query<T: DeserializeOwned, Q: Serialize + ?Sized + Sync>(
+        &self,
+        url: &str,
+        query: &Q,
+    ) -> Result<T, Error> {
+        self.replay(Method::Get, url, to_value(Some(query))?, Value::Null)
+    }
+
+    async fn post<B: Serialize + Sync, T: DeserializeOwned, Q: Serialize + Sync>(
+        &self,
+        url: &str,
+        body: &B,
+        query: Option<&Q>,
+    ) -> Result<T, Error> {
+        self.replay(Method::Post, url, to_value(query)?, to_value(Some(body))?)
+    }
+
+    async fn patch<B: Serialize + Sync, T: DeserializeOwned>(
+        &self,
+        url: &str,
+        body: Option<&B>,
+    ) -> Result<T, Error> {
+        self.replay(Method::Patch, url, Value::Null, to_value(body)?)
+    }
+
+    async fn delete<B: Serialize + Sync, T: DeserializeOwned>(
+        &self,
+        url: &str,
+        body: Option<&B>,
+    ) -> Result<T, Error> {
+        self.replay(Method::Delete, url, Value::Null, to_value(body)?)
+    }
+
+    /// The headers aren't part of the recorded requests, the copy shares the same fixtures.
+    fn with_headers(&self, _headers: &[(String, String)]) -> Option<Self> {
+        Some(self.clone())
+    }
+}
+
+#[cfg(all(test, feature = "http"))]
+mod tests {
+    use {super::*, crate::http::default::HttpClient, httpmock::prelude::*};
+
+    fn directory(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("tezos-rpc-{}-{}", name, std::process::id()))
+    }
+
+    #[tokio::test]
+    async fn test_record_and_replay() -> Result<(), Error> {
+        let server = MockServer::start();
+        let mut head_mock = server.mock(|when, then| {
+            when.method(GET).path("/chains/main/blocks/head/header");
+            then.header("content-type", "application/json")
+                .json_body(serde_json::json!({ "level": 100 }));
+        });
+        server.mock(|when, then| {
+            when.method(POST)
+                .path("/injection/operation")
+                .query_param("chain", "main");
+            then.status(400)
+                .header("content-type", "application/json")
+                .json_body(serde_json::json!([{ "kind": "temporary", "id": "failure" }]));
+        });
+
+        let directory = directory("fixtures");
+        let recorder =
+            RecordingHttp::from_client(HttpClient::new(server.base_url()), directory.clone());
+        let _: Value = recorder.get("/chains/main/blocks/head/header").await?;
+        head_mock.delete();
+        server.mock(|when, then| {
+            when.method(GET).path("/chains/main/blocks/head/header");
+            then.header("content-type", "application/json")
+                .json_body(serde_json::json!({ "level": 101 }));
+        });
+        let _: Value = recorder.get("/chains/main/blocks/head/header").await?;
+        let injection = recorder
+            .post::<_, Value, _>("/injection/operation", &"00ab", Some(&[("chain", "main")]))
+            .await;
+        assert!(matches!(injection, Err(Error::RpcErrors(_))));
+        assert_eq!(fs::read_dir(&directory)?.count(), 3);
+
+        let replay = ReplayHttp::from_directory(&directory)?;
+        fs::remove_dir_all(&directory)?;
+
+        let levels = [100, 101, 101];
+        for level in levels {
+            let header: Value = replay.get("/chains/main/blocks/head/header").await?;
+            assert_eq!(header["level"], level);
+        }
+        let injection = replay
+            .post::<_, Value, _>("/injection/operation", &"00ab", Some(&[("chain", "main")]))
+            .await;
+        assert!(
+            matches!(injection, Err(Error::RpcErrors(errors)) if errors.errors()[0].id == "failure")
+        );
+
+        let missing = replay
+            .post::<_, Value, _>("/injection/operation", &"00cd", Some(&[("chain", "main")]))
+            .await;
+        assert!(matches!(
+            missing,
+            Err(Error::FixtureNotFound { method, request })
+                if method == "POST" && request == r#"/injection/operation query [["chain","main"]] body "00cd""#
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_file_name() {
+        let key = FixtureKey::new(
+            Method::Get,
+            "/chains/main/blocks/head~2/header",
+            &Value::Null,
+            &Value::Null,
+        );
+        let name = key.file_name(0);
+        assert!(name.starts_with("get_chains_main_blocks_head_2_header_"));
+        assert!(name.ends_with(".json"));
+        assert_eq!(key.file_name(2), name.replace(".json", "_2.json"));
+    }
+}
@@ -0,0 +1,643 @@
+use std::{
+    collections::HashMap,
+    fmt::{self, Debug},
+    future::Future,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use derive_more::Display;
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::*;
+
+/// The method of a [Request].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Method {
+    #[display(fmt = "GET")]
+    Get,
+    #[display(fmt = "POST")]
+    Post,
+    #[display(fmt = "PATCH")]
+    Patch,
+    #[display(fmt = "DELETE")]
+    Delete,
+}
+
+/// A request sent through a [MiddlewareHttp], as seen by its [Middleware]s.
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub method: Method,
+    /// The path of the RPC, e.g. `/chains/main/blocks/head/header`.
+    pub path: String,
+    /// The headers sent with the request, in addition to the ones of the wrapped client.
+    pub headers: Vec<(String, String)>,
+    /// The JSON body of the request, only set if a middleware [inspects the bodies](Middleware::inspects_bodies).
+    pub body: Option<Value>,
+}
+
+impl Request {
+    /// Adds a header to the request.
+    pub fn add_header<N: Into<String>, V: Into<String>>(&mut self, name: N, value: V) {
+        self.headers.push((name.into(), value.into()));
+    }
+}
+
+/// The outcome of a [Request], as seen by the [Middleware]s.
+#[derive(Debug, Clone, Copy)]
+pub struct Response<'a> {
+    /// The time elapsed between sending the request and receiving the whole response.
+    pub latency: Duration,
+    /// The JSON body of the response, only set if a middleware [inspects the bodies](Middleware::inspects_bodies),
+    /// or the error the request failed with.
+    pub result: std::result::Result<Option<&'a Value>, &'a Error>,
+}
+
+/// A hook called around each request sent through a [MiddlewareHttp].
+#[async_trait]
+pub trait Middleware: Send + Sync {
+    /// Whether the middleware reads the bodies of the requests and responses.
+    ///
+    /// The bodies are converted to JSON values only if at least one middleware reads them.
+    fn inspects_bodies(&self) -> bool {
+        false
+    }
+
+    /// Called before a request is sent. It may add headers to the request or abort it with an error.
+    async fn on_request(&self, _request: &mut Request) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Called once the response to a request has been received or the request has failed.
+    fn on_response(&self, _request: &Request, _response: &Response<'_>) {}
+}
+
+/// An [Http] client that runs a chain of [Middleware]s around the requests of a wrapped client.
+///
+/// The middlewares are called in the order they have been added before a request is sent,
+/// and in the reverse order once it's complete. The headers they add are sent
+/// through [Http::with_headers], which fails with [Error::HeadersNotSupported]
+/// if the wrapped client doesn't support custom headers.
+///
+/// With the `tracing` feature, each request is additionally instrumented with an `rpc` span.
+///
+/// ```rust
+/// # #[cfg(feature = "http")]
+/// # {
+/// use tezos_rpc::{
+///     client::TezosRpc,
+///     http::{default::HttpClient, middleware::{Headers, Metrics, MiddlewareHttp}, Http},
+/// };
+///
+/// let metrics = Metrics::new();
+/// let http_client = MiddlewareHttp::from_client(HttpClient::new("https://rpc.tzbeta.net".into()))
+///     .with(Headers::bearer("my-api-token"))
+///     .with(metrics.clone());
+/// let client = TezosRpc::new_rpc_with_http_client(http_client, Default::default());
+/// # }
+/// ```
+pub struct MiddlewareHttp<HttpClient: Http> {
+    client: HttpClient,
+    middlewares: Vec<Arc<dyn Middleware>>,
+}
+
+impl<HttpClient: Http> MiddlewareHttp<HttpClient> {
+    /// Creates a client wrapping the already configured `client`, without any middleware.
+    pub fn from_client(client: HttpClient) -> Self {
+        Self {
+            client,
+            middlewares: vec![],
+        }
+    }
+
+    /// Adds a `middleware` at the end of the chain.
+    pub fn with<M: Middleware + 'static>(mut self, middleware: M) -> Self {
+        self.middlewares.push(Arc::new(middleware));
+
+        self
+    }
+
+    /// The wrapped client.
+    pub fn client(&self) -> &HttpClient {
+        &self.client
+    }
+
+    fn inspects_bodies(&self) -> bool {
+        self.middlewares
+            .iter()
+            .any(|middleware| middleware.inspects_bodies())
+    }
+
+    /// Runs the middlewares on a new request and returns it, along with the client
+    /// sending its headers if it has any.
+    async fn prepare(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<Value>,
+    ) -> Result<(Request, Option<HttpClient>), Error> {
+        let mut request = Request {
+            method,
+            path: path.into(),
+            headers: vec![],
+            body,
+        };
+        for middleware in self.middlewares.iter() {
+            middleware.on_request(&mut request).await?;
+        }
+        if request.headers.is_empty() {
+            return Ok((request, None));
+        }
+        let client = self
+            .client
+            .with_headers(&request.headers)
+            .ok_or(Error::HeadersNotSupported)?;
+
+        Ok((request, Some(client)))
+    }
+
+    fn complete(
+        &self,
+        request: &Request,
+        started: Instant,
+        result: Result<Option<&Value>, &Error>,
+    ) {
+        let response = Response {
+            latency: started.elapsed(),
+            result,
+        };
+        for middleware in self.middlewares.iter().rev() {
+            middleware.on_response(request, &response);
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    async fn instrument<F: Future>(&self, method: Method, path: &str, future: F) -> F::Output {
+        use tracing::Instrument;
+
+        future
+            .instrument(tracing::debug_span!("rpc", method = %method, path))
+            .await
+    }
+
+    #[cfg(not(feature = "tracing"))]
+    async fn instrument<F: Future>(&self, _method: Method, _path: &str, future: F) -> F::Output {
+        future.await
+    }
+}
+
+/// Sends a request through the middlewares, `$request` builds it with the `$client` sending its headers.
+macro_rules! send {
+    ($self:ident, $method:expr, $url:expr, $body:expr, |$client:ident| $request:expr) => {
+        $self
+            .instrument($method, $url, async {
+                let (request, headers_client) = $self.prepare($method, $url, $body).await?;
+                let $client = headers_client.as_ref().unwrap_or(&$self.client);
+                let started = Instant::now();
+                if $self.inspects_bodies() {
+                    let result: Result<Value, Error> = $request.await;
+                    $self.complete(&request, started, result.as_ref().map(Some));
+                    Ok(serde_json::from_value(result?)?)
+                } else {
+                    let result = $request.await;
+                    $self.complete(&request, started, result.as_ref().map(|_| None));
+                    result
+                }
+            })
+            .await
+    };
+}
+
+impl<HttpClient: Http + Debug> Debug for MiddlewareHttp<HttpClient> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MiddlewareHttp")
+            .field("client", &self.client)
+            .field("middlewares", &self.middlewares.len())
+            .finish()
+    }
+}
+
+#[async_trait]
+impl<HttpClient: Http + Send + Sync> Http for MiddlewareHttp<HttpClient> {
+    /// Creates a client to the endpoint, without any middleware.
+    fn new(rpc_endpoint: String) -> Self {
+        Self::from_client(HttpClient::new(rpc_endpoint))
+    }
+
+    fn change_rpc_endpoint(&mut self, rpc_endpoint: String) {
+        self.client.change_rpc_endpoint(rpc_endpoint);
+    }
+
+    async fn get<T: DeserializeOwned>(&self, url: &str) -> Result<T, Error> {
+        send!(self, Method::Get, url, None, |client| client.get(url))
+    }
+
+    async fn get_with_query<T: DeserializeOwned, Q: Serialize + ?Sized + Sync>(
+        &self,
+        url: &str,
+        query: &Q,
+    ) -> Result<T, Error> {
+        send!(self, Method::Get, url, None, |client| client
+            .get_with_query(url, query))
+    }
+
+    async fn post<B: Serialize + Sync, T: DeserializeOwned, Q: Serialize + Sync>(
+        &self,
+        url: &str,
+        body: &B,
+        query: Option<&Q>,
+    ) -> Result<T, Error> {
+        let value = self.body_value(Some(body))?;
+        send!(self, Method::Post, url, value, |client| client
+            .post(url, body, query))
+    }
+
+    async fn patch<B: Serialize + Sync, T: DeserializeOwned>(
+        &self,
+        url: &str,
+        body: Option<&B>,
+    ) -> Result<T, Error> {
+        let value = self.body_value(body)?;
+        send!(self, Method::Patch, url, value, |client| client
+            .patch(url, body))
+    }
+
+    async fn delete<B: Serialize + Sync, T: DeserializeOwned>(
+        &self,
+        url: &str,
+        body: Option<&B>,
+    ) -> Result<T, Error> {
+        let value = self.body_value(body)?;
+        send!(self, Method::Delete, url, value, |client| client
+            .delete(url, body))
+    }
+
+    fn with_headers(&self, headers: &[(String, String)]) -> Option<Self> {
+        Some(Self {
+            client: self.client.with_headers(headers)?,
+            middlewares: self.middlewares.clone(),
+        })
+    }
+}
+
+impl<HttpClient: Http> MiddlewareHttp<HttpClient> {
+    fn body_value<B: Serialize>(&self, body: Option<&B>) -> Result<Option<Value>, Error> {
+        match body {
+            Some(body) if self.inspects_bodies() => Ok(Some(serde_json::to_value(body)?)),
+            _ => Ok(None),
+        }
+    }
+}
+
+#[async_trait]
+impl<HttpClient: HttpStream> HttpStream for MiddlewareHttp<HttpClient> {
+    /// Opens a streaming `GET` request, the middlewares see the response once the stream is open.
+    async fn get_stream<Q: Serialize + ?Sized + Sync>(
+        &self,
+        url: &str,
+        query: &Q,
+    ) -> Result<ByteStream, Error> {
+        self.instrument(Method::Get, url, async {
+            let (request, headers_client) = self.prepare(Method::Get, url, None).await?;
+            let client = headers_client.as_ref().unwrap_or(&self.client);
+            let started = Instant::now();
+            let result = client.get_stream(url, query).await;
+            self.complete(&request, started, result.as_ref().map(|_| None));
+
+            result
+        })
+        .await
+    }
+}
+
+/// A [Middleware] adding the same headers to each request, e.g. the API key of an RPC provider.
+#[derive(Debug, Clone, Default)]
+pub struct Headers {
+    headers: Vec<(String, String)>,
+}
+
+impl Headers {
+    pub fn new<I, N, V>(headers: I) -> Self
+    where
+        I: IntoIterator<Item = (N, V)>,
+        N: Into<String>,
+        V: Into<String>,
+    {
+        Self {
+            headers: headers
+                .into_iter()
+                .map(|(name, value)| (name.into(), value.into()))
+                .collect(),
+        }
+    }
+
+    /// Creates a middleware sending the `token` in an `Authorization: Bearer` header.
+    pub fn bearer<S: AsRef<str>>(token: S) -> Self {
+        Self::new([("Authorization", format!("Bearer {}", token.as_ref()))])
+    }
+}
+
+#[async_trait]
+impl Middleware for Headers {
+    async fn on_request(&self, request: &mut Request) -> Result<(), Error> {
+        request.headers.extend_from_slice(&self.headers);
+
+        Ok(())
+    }
+}
+
+/// A [Middleware] adding the headers computed for each request, e.g. a regularly renewed access token.
+///
+/// Implement [Middleware] directly if the headers have to be computed asynchronously.
+pub struct DynamicHeaders<F> {
+    headers: F,
+}
+
+impl<F> DynamicHeaders<F>
+where
+    F: Fn(&Request) -> Vec<(String, String)> + Send + Sync,
+{
+    pub fn new(headers: F) -> Self {
+        Self { headers }
+    }
+}
+
+#[async_trait]
+impl<F> Middleware for DynamicHeaders<F>
+where
+    F: Fn(&Request) -> Vec<(String, String)> + Send + Sync,
+{
+    async fn on_request(&self, request: &mut Request) -> Result<(), Error> {
+        let headers = (self.headers)(request);
+        request.headers.extend(headers);
+
+        Ok(())
+    }
+}
+
+/// The metrics of the requests sent to an RPC path.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PathMetrics {
+    pub requests: u64,
+    pub errors: u64,
+    pub total_latency: Duration,
+    pub max_latency: Duration,
+}
+
+impl PathMetrics {
+    pub fn average_latency(&self) -> Duration {
+        if self.requests == 0 {
+            return Duration::ZERO;
+        }
+        self.total_latency.div_f64(self.requests as f64)
+    }
+}
+
+/// A [Middleware] recording the number of requests, errors and their latency per RPC path.
+///
+/// The paths are grouped by their method and template, in which the block, contract, operation
+/// or big map identifiers are replaced with `<id>`, e.g. `GET /chains/main/blocks/<id>/header`.
+/// The clones of a [Metrics] share the same records.
+#[derive(Debug, Clone, Default)]
+pub struct Metrics {
+    paths: Arc<Mutex<HashMap<String, PathMetrics>>>,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// The metrics recorded so far, by method and path template.
+    pub fn snapshot(&self) -> HashMap<String, PathMetrics> {
+        self.paths
+            .lock()
+            .map(|paths| paths.clone())
+            .unwrap_or_default()
+    }
+
+    /// Clears the recorded metrics.
+    pub fn reset(&self) {
+        if let Ok(mut paths) = self.paths.lock() {
+            paths.clear();
+        }
+    }
+}
+
+#[async_trait]
+impl Middleware for Metrics {
+    fn on_response(&self, request: &Request, response: &Response<'_>) {
+        let key = format!("{} {}", request.method, path_template(&request.path));
+        if let Ok(mut paths) = self.paths.lock() {
+            let metrics = paths.entry(key).or_default();
+            metrics.requests += 1;
+            if response.result.is_err() {
+                metrics.errors += 1;
+            }
+            metrics.total_latency += response.latency;
+            metrics.max_latency = metrics.max_latency.max(response.latency);
+        }
+    }
+}
+
+/// Replaces the identifiers in an RPC `path` with `<id>`.
+fn path_template(path: &str) -> String {
+    path.split('/')
+        .map(|segment| {
+            let is_level = !segment.is_empty() && segment.chars().all(|c| c.is_ascii_digit());
+            let is_encoded =
+                segment.len() >= 36 && segment.chars().all(|c| c.is_ascii_alphanumeric());
+            let is_relative = segment
+                .strip_prefix("head")
+                .map_or(false, |offset| offset.starts_with(['~', '-', '+']));
+            if is_level || is_encoded || is_relative {
+                "<id>"
+            } else {
+                segment
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// A [Middleware] logging each request and its outcome as `tracing` events.
+///
+/// The events are recorded in the `rpc` span of the request.
+#[cfg(feature = "tracing")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Tracing;
+
+#[cfg(feature = "tracing")]
+#[async_trait]
+impl Middleware for Tracing {
+    async fn on_request(&self, _request: &mut Request) -> Result<(), Error> {
+        tracing::debug!("sending request");
+
+        Ok(())
+    }
+
+    fn on_response(&self, _request: &Request, response: &Response<'_>) {
+        let latency_ms = response.latency.as_millis() as u64;
+        match response.result {
+            Ok(_) => tracing::debug!(latency_ms, "received response"),
+            Err(error) => tracing::warn!(latency_ms, %error, "request failed"),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "http"))]
+mod tests {
+    use {
+        super::*,
+        crate::http::{
+            default::HttpClient,
+            resilient::{Failover, ResilientHttp},
+        },
+        httpmock::prelude::*,
+    };
+
+    const CHAIN_ID: &str = "/chains/main/chain_id";
+
+    #[derive(Default)]
+    struct Recorder {
+        responses: Mutex<Vec<(String, Option<Value>)>>,
+    }
+
+    #[async_trait]
+    impl Middleware for Arc<Recorder> {
+        fn inspects_bodies(&self) -> bool {
+            true
+        }
+
+        fn on_response(&self, request: &Request, response: &Response<'_>) {
+            let body = response.result.ok().flatten().cloned();
+            self.responses
+                .lock()
+                .unwrap()
+                .push((request.path.clone(), body));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_headers() -> Result<(), Error> {
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(GET)
+                .path(CHAIN_ID)
+                .header("x-api-key", "key")
+                .header("authorization", "Bearer token")
+                .header("x-request-path", CHAIN_ID);
+            then.header("content-type", "application/json")
+                .json_body(serde_json::json!("NetXdQprcVkpaWU"));
+        });
+
+        let http = MiddlewareHttp::from_client(HttpClient::new(server.base_url()))
+            .with(Headers::new([("x-api-key", "key")]))
+            .with(Headers::bearer("token"))
+            .with(DynamicHeaders::new(|request: &Request| {
+                vec![("x-request-path".into(), request.path.clone())]
+            }));
+
+        let chain_id: String = http.get(CHAIN_ID).await?;
+        assert_eq!(chain_id, "NetXdQprcVkpaWU");
+        mock.assert();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_headers_with_resilient_client() -> Result<(), Error> {
+        let servers = [MockServer::start(), MockServer::start()];
+        let mocks = servers
+            .iter()
+            .map(|server| {
+                server.mock(|when, then| {
+                    when.method(GET)
+                        .path(CHAIN_ID)
+                        .header("authorization", "Bearer token");
+                    then.status(200)
+                        .header("content-type", "application/json")
+                        .json_body(serde_json::json!("NetXdQprcVkpaWU"));
+                })
+            })
+            .collect::<Vec<_>>();
+        let http = MiddlewareHttp::from_client(
+            ResilientHttp::<HttpClient>::with_endpoints(
+                servers.iter().map(|server| server.base_url()),
+            )
+            .failover(Failover::RoundRobin),
+        )
+        .with(Headers::bearer("token"));
+
+        for _ in 0..2 {
+            let chain_id: String = http.get(CHAIN_ID).await?;
+            assert_eq!(chain_id, "NetXdQprcVkpaWU");
+        }
+        for mock in mocks {
+            mock.assert_hits(1);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_inspection_and_metrics() -> Result<(), Error> {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET).path(CHAIN_ID);
+            then.header("content-type", "application/json")
+                .json_body(serde_json::json!("NetXdQprcVkpaWU"));
+        });
+        server.mock(|when, then| {
+            when.method(GET);
+            then.status(404)
+                .header("content-type", "text/plain")
+                .body("Not found");
+        });
+
+        let recorder = Arc::new(Recorder::default());
+        let metrics = Metrics::new();
+        let http = MiddlewareHttp::from_client(HttpClient::new(server.base_url()))
+            .with(recorder.clone())
+            .with(metrics.clone());
+
+        let _: String = http.get(CHAIN_ID).await?;
+        let _: String = http.get(CHAIN_ID).await?;
+        let header =
+            "/chains/main/blocks/BLq7VzUh9rvH57XxvE22tMsNyBciwzDmDuaGLHnhyhCt5raizQz/header";
+        assert!(http.get::<Value>(header).await.is_err());
+        assert!(http
+            .get::<Value>("/chains/main/blocks/2504461/header")
+            .await
+            .is_err());
+
+        assert_eq!(
+            recorder.responses.lock().unwrap()[0],
+            (CHAIN_ID.into(), Some(serde_json::json!("NetXdQprcVkpaWU")))
+        );
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.len(), 2);
+        let chain_id = snapshot[&format!("GET {}", CHAIN_ID)];
+        assert_eq!((chain_id.requests, chain_id.errors), (2, 0));
+        assert!(chain_id.max_latency >= chain_id.average_latency());
+        let header = snapshot["GET /chains/main/blocks/<id>/header"];
+        assert_eq!((header.requests, header.errors), (2, 2));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_path_template() {
+        assert_eq!(
+            path_template("/chains/main/blocks/head~2/context/contracts/tz1PwXjsrgYBi9wpe3tFhazJpt7JMTVzBp5c/balance"),
+            "/chains/main/blocks/<id>/context/contracts/<id>/balance"
+        );
+        assert_eq!(
+            path_template("/chains/main/blocks/head/context/big_maps/1234/exprv6UsC1sN3Fk2XfgcJCL8NCerP5rCGy1PRESZAqr7L2JdzX55EN"),
+            "/chains/main/blocks/head/context/big_maps/<id>/<id>"
+        );
+    }
+}
@@ -0,0 +1,522 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use futures::future::{self, BoxFuture, Either};
+use futures_timer::Delay;
+
+use super::*;
+
+/// The default maximum number of times an idempotent request is retried.
+pub const DEFAULT_MAX_RETRIES: usize = 3;
+/// The default delay before the first retry, doubled with each retry.
+pub const DEFAULT_INITIAL_BACKOFF: Duration = Duration::from_millis(250);
+/// The default maximum delay between two retries.
+pub const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(10);
+
+/// The order in which the endpoints of a [ResilientHttp] are tried.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Failover {
+    /// Each request starts with the endpoint following the one the previous request started with.
+    RoundRobin,
+    /// Each request starts with the first endpoint, in the configured order, which hasn't failed
+    /// during the last `cooldown`.
+    HealthChecked { cooldown: Duration },
+}
+
+impl Default for Failover {
+    fn default() -> Self {
+        Self::HealthChecked {
+            cooldown: Duration::from_secs(30),
+        }
+    }
+}
+
+/// An [Http] client wrapping clients to one or more endpoints, which adds timeouts,
+/// retries with exponential backoff and failover between the endpoints.
+///
+/// Only idempotent requests are retried: `GET`, `DELETE` and the `POST` requests
+/// other than the injections (e.g. `/injection/operation`), which could otherwise be applied twice.
+/// A request is retried on timeouts, connection errors and `429`/`503` responses,
+/// after the delay requested by their `Retry-After` header, if any.
+///
+/// ```rust
+/// # #[cfg(feature = "http")]
+/// # {
+/// use std::time::Duration;
+/// use tezos_rpc::{
+///     client::TezosRpc,
+///     http::{default::HttpClient, resilient::{Failover, ResilientHttp}},
+/// };
+///
+/// let http_client = ResilientHttp::<HttpClient>::with_endpoints([
+///     "https://rpc.tzbeta.net",
+///     "https://mainnet.api.tez.ie",
+/// ])
+/// .timeout(Duration::from_secs(10))
+/// .failover(Failover::RoundRobin);
+/// let client = TezosRpc::new_rpc_with_http_client(http_client, Default::default());
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ResilientHttp<HttpClient: Http> {
+    endpoints: Vec<Endpoint<HttpClient>>,
+    timeout: Option<Duration>,
+    max_retries: usize,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    failover: Failover,
+    /// Shared with the copies made by [Http::with_headers], like the health of the endpoints.
+    next_endpoint: Arc<AtomicUsize>,
+}
+
+#[derive(Debug)]
+struct Endpoint<HttpClient> {
+    client: HttpClient,
+    unhealthy_until: Arc<Mutex<Option<Instant>>>,
+}
+
+impl<HttpClient: Http> ResilientHttp<HttpClient> {
+    /// Creates a client to the `endpoints`, tried in the configured [Failover] order.
+    ///
+    /// Panics if `endpoints` is empty.
+    pub fn with_endpoints<I, S>(endpoints: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::from_clients(
+            endpoints
+                .into_iter()
+                .map(|endpoint| HttpClient::new(endpoint.into()))
+                .collect(),
+        )
+    }
+
+    /// Creates a client wrapping the already configured `clients`, one per endpoint.
+    ///
+    /// Panics if `clients` is empty.
+    pub fn from_clients(clients: Vec<HttpClient>) -> Self {
+        assert!(!clients.is_empty(), "at least one endpoint is required");
+
+        Self {
+            endpoints: clients
+                .into_iter()
+                .map(|client| Endpoint {
+                    client,
+                    unhealthy_until: Arc::new(Mutex::new(None)),
+                })
+                .collect(),
+            timeout: None,
+            max_retries: DEFAULT_MAX_RETRIES,
+            initial_backoff: DEFAULT_INITIAL_BACKOFF,
+            max_backoff: DEFAULT_MAX_BACKOFF,
+            failover: Default::default(),
+            next_endpoint: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Modify the maximum duration of a single attempt of a request, unlimited by default.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+
+        self
+    }
+
+    /// Modify the maximum number of times an idempotent request is retried (default: [DEFAULT_MAX_RETRIES]).
+    pub fn max_retries(mut self, max_retries: usize) -> Self {
+        self.max_retries = max_retries;
+
+        self
+    }
+
+    /// Modify the delay before the first retry and the maximum delay between two retries
+    /// (default: [DEFAULT_INITIAL_BACKOFF] and [DEFAULT_MAX_BACKOFF]).
+    pub fn backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+
+        self
+    }
+
+    /// Modify the order in which the endpoints are tried (default: [Failover::HealthChecked]).
+    pub fn failover(mut self, failover: Failover) -> Self {
+        self.failover = failover;
+
+        self
+    }
+
+    async fn send<'a, T, F>(&'a self, is_idempotent: bool, request: F) -> Result<T, Error>
+    where
+        F: Fn(&'a HttpClient) -> BoxFuture<'a, Result<T, Error>>,
+    {
+        let order = self.endpoint_order();
+        let attempts = if is_idempotent {
+            self.max_retries + 1
+        } else {
+            1
+        };
+
+        let mut attempt = 0;
+        loop {
+            let endpoint = &self.endpoints[order[attempt % order.len()]];
+            let error = match self.with_timeout(request(&endpoint.client)).await {
+                Ok(value) => {
+                    endpoint.set_unhealthy_until(None);
+                    return Ok(value);
+                }
+                Err(error) => error,
+            };
+            if !is_transient(&error) {
+                return Err(error);
+            }
+            if let Failover::HealthChecked { cooldown } = self.failover {
+                endpoint.set_unhealthy_until(Some(Instant::now() + cooldown));
+            }
+
+            attempt += 1;
+            if attempt >= attempts {
+                return Err(error);
+            }
+            // The other endpoints are tried right away, the backoff applies once all of them have failed.
+            if attempt % order.len() == 0 {
+                Delay::new(self.delay(attempt / order.len(), &error)).await;
+            }
+        }
+    }
+
+    async fn with_timeout<T>(&self, request: BoxFuture<'_, Result<T, Error>>) -> Result<T, Error> {
+        match self.timeout {
+            Some(timeout) => match future::select(request, Delay::new(timeout)).await {
+                Either::Left((result, _)) => result,
+                Either::Right(_) => Err(Error::Timeout),
+            },
+            None => request.await,
+        }
+    }
+
+    fn endpoint_order(&self) -> Vec<usize> {
+        let count = self.endpoints.len();
+        match self.failover {
+            Failover::RoundRobin => {
+                let first = self.next_endpoint.fetch_add(1, Ordering::Relaxed);
+                (0..count).map(|index| (first + index) % count).collect()
+            }
+            Failover::HealthChecked { .. } => {
+                let now = Instant::now();
+                let mut order = (0..count).collect::<Vec<_>>();
+                // The unhealthy endpoints are kept as a last resort, the soonest to recover first.
+                order.sort_by_key(|index| self.endpoints[*index].unhealthy_until(now));
+                order
+            }
+        }
+    }
+
+    /// The delay before the `round`-th retry of all the endpoints.
+    fn delay(&self, round: usize, error: &Error) -> Duration {
+        let exponent = (round - 1).min(16) as u32;
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(1 << exponent)
+            .min(self.max_backoff);
+        let backoff = backoff / 2 + jitter(backoff / 2);
+
+        match error {
+            Error::ServiceUnavailable {
+                retry_after: Some(retry_after),
+                ..
+            } => backoff.max(*retry_after),
+            _ => backoff,
+        }
+    }
+}
+
+impl<HttpClient> Endpoint<HttpClient> {
+    fn unhealthy_until(&self, now: Instant) -> Option<Instant> {
+        self.unhealthy_until
+            .lock()
+            .ok()
+            .and_then(|until| *until)
+            .filter(|until| *until > now)
+    }
+
+    fn set_unhealthy_until(&self, until: Option<Instant>) {
+        if let Ok(mut unhealthy_until) = self.unhealthy_until.lock() {
+            *unhealthy_until = until;
+        }
+    }
+}
+
+#[async_trait]
+impl<HttpClient: Http + Send + Sync> Http for ResilientHttp<HttpClient> {
+    /// Creates a client to a single endpoint.
+    fn new(rpc_endpoint: String) -> Self {
+        Self::with_endpoints([rpc_endpoint])
+    }
+
+    /// Replaces all the endpoints with `rpc_endpoint`.
+    fn change_rpc_endpoint(&mut self, rpc_endpoint: String) {
+        self.endpoints.truncate(1);
+        self.endpoints[0].client.change_rpc_endpoint(rpc_endpoint);
+        self.endpoints[0].set_unhealthy_until(None);
+    }
+
+    /// Returns a copy of the client which additionally sends the `headers` to every endpoint,
+    /// sharing the health of the endpoints with the original client.
+    fn with_headers(&self, headers: &[(String, String)]) -> Option<Self> {
+        Some(Self {
+            endpoints: self
+                .endpoints
+                .iter()
+                .map(|endpoint| {
+                    Some(Endpoint {
+                        client: endpoint.client.with_headers(headers)?,
+                        unhealthy_until: endpoint.unhealthy_until.clone(),
+                    })
+                })
+                .collect::<Option<Vec<_>>>()?,
+            timeout: self.timeout,
+            max_retries: self.max_retries,
+            initial_backoff: self.initial_backoff,
+            max_backoff: self.max_backoff,
+            failover: self.failover,
+            next_endpoint: self.next_endpoint.clone(),
+        })
+    }
+
+    async fn get<T: DeserializeOwned>(&self, url: &str) -> Result<T, Error> {
+        self.send(true, |client| client.get(url)).await
+    }
+
+    async fn get_with_query<T: DeserializeOwned, Q: Serialize + ?Sized + Sync>(
+        &self,
+        url: &str,
+        query: &Q,
+    ) -> Result<T, Error> {
+        self.send(true, |client| client.get_with_query(url, query))
+            .await
+    }
+
+    async fn post<B: Serialize + Sync, T: DeserializeOwned, Q: Serialize + Sync>(
+        &self,
+        url: &str,
+        body: &B,
+        query: Option<&Q>,
+    ) -> Result<T, Error> {
+        let is_idempotent = !url.starts_with("/injection/");
+        self.send(is_idempotent, |client| client.post(url, body, query))
+            .await
+    }
+
+    async fn patch<B: Serialize + Sync, T: DeserializeOwned>(
+        &self,
+        url: &str,
+        body: Option<&B>,
+    ) -> Result<T, Error> {
+        self.send(false, |client| client.patch(url, body)).await
+    }
+
+    async fn delete<B: Serialize + Sync, T: DeserializeOwned>(
+        &self,
+        url: &str,
+        body: Option<&B>,
+    ) -> Result<T, Error> {
+        self.send(true, |client| client.delete(url, body)).await
+    }
+}
+
+#[async_trait]
+impl<HttpClient: HttpStream> HttpStream for ResilientHttp<HttpClient> {
+    /// Opens a streaming `GET` request, the timeout and retries only apply to opening the stream.
+    async fn get_stream<Q: Serialize + ?Sized + Sync>(
+        &self,
+        url: &str,
+        query: &Q,
+    ) -> Result<ByteStream, Error> {
+        self.send(true, |client| client.get_stream(url, query))
+            .await
+    }
+}
+
+/// Whether a request may succeed if it's sent again.
+fn is_transient(error: &Error) -> bool {
+    match error {
+        Error::Timeout | Error::ServiceUnavailable { .. } => true,
+        #[cfg(feature = "http")]
+        Error::HttpError { source } => !source.is_decode() && !source.is_status(),
+        _ => false,
+    }
+}
+
+/// Parses the value of a `Retry-After` header, either a number of seconds or an HTTP date.
+pub(crate) fn parse_retry_after(value: &str) -> Option<Duration> {
+    if let Ok(seconds) = value.trim().parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value.trim()).ok()?;
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?;
+    let seconds = date.timestamp() - now.as_secs() as i64;
+
+    Some(Duration::from_secs(seconds.max(0) as u64))
+}
+
+/// A random duration between zero and `max`.
+pub(crate) fn jitter(max: Duration) -> Duration {
+    static STATE: AtomicU64 = AtomicU64::new(0x853c_49e6_748f_ea9b);
+
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|now| now.subsec_nanos() as u64)
+        .unwrap_or_default();
+    // SplitMix64
+    let mut x = STATE.fetch_add(0x9e37_79b9_7f4a_7c15, Ordering::Relaxed) ^ nanos;
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^= x >> 31;
+
+    max.mul_f64((x >> 11) as f64 / (1u64 << 53) as f64)
+}
+
+#[cfg(all(test, feature = "http"))]
+mod tests {
+    use {super::*, crate::http::default::HttpClient, httpmock::prelude::*};
+
+    const CHAIN_ID: &str = "/chains/main/chain_id";
+
+    fn mock_status<'a>(
+        server: &'a MockServer,
+        method: httpmock::Method,
+        path: &str,
+        status: u16,
+    ) -> httpmock::Mock<'a> {
+        server.mock(|when, then| {
+            when.method(method).path(path);
+            then.status(status)
+                .header("content-type", "application/json")
+                .header("retry-after", "1")
+                .json_body(serde_json::json!("NetXdQprcVkpaWU"));
+        })
+    }
+
+    #[tokio::test]
+    async fn test_failover() -> Result<(), Error> {
+        let unavailable = MockServer::start();
+        let available = MockServer::start();
+        let unavailable_mock = mock_status(&unavailable, GET, CHAIN_ID, 503);
+        let available_mock = mock_status(&available, GET, CHAIN_ID, 200);
+
+        let http = ResilientHttp::<HttpClient>::with_endpoints([
+            unavailable.base_url(),
+            available.base_url(),
+        ]);
+
+        let chain_id: String = http.get(CHAIN_ID).await?;
+        assert_eq!(chain_id, "NetXdQprcVkpaWU");
+        unavailable_mock.assert_hits(1);
+        available_mock.assert_hits(1);
+
+        // The failed endpoint is skipped until its cooldown ends.
+        let _: String = http.get(CHAIN_ID).await?;
+        unavailable_mock.assert_hits(1);
+        available_mock.assert_hits(2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_round_robin() -> Result<(), Error> {
+        let first = MockServer::start();
+        let second = MockServer::start();
+        let first_mock = mock_status(&first, GET, CHAIN_ID, 200);
+        let second_mock = mock_status(&second, GET, CHAIN_ID, 200);
+
+        let http =
+            ResilientHttp::<HttpClient>::with_endpoints([first.base_url(), second.base_url()])
+                .failover(Failover::RoundRobin);
+
+        for _ in 0..4 {
+            let _: String = http.get(CHAIN_ID).await?;
+        }
+        first_mock.assert_hits(2);
+        second_mock.assert_hits(2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_retries() {
+        let server = MockServer::start();
+        let mock = mock_status(&server, GET, CHAIN_ID, 429);
+
+        let http = ResilientHttp::<HttpClient>::new(server.base_url())
+            .max_retries(2)
+            .backoff(Duration::from_millis(1), Duration::from_millis(1));
+
+        // The `Retry-After` header of the last response is returned with the error.
+        let result = http.get::<String>(CHAIN_ID).await;
+        assert!(matches!(
+            result,
+            Err(Error::ServiceUnavailable {
+                status: 429,
+                retry_after: Some(retry_after),
+            }) if retry_after == Duration::from_secs(1)
+        ));
+        mock.assert_hits(3);
+    }
+
+    #[tokio::test]
+    async fn test_injection_not_retried() {
+        let unavailable = MockServer::start();
+        let available = MockServer::start();
+        let path = "/injection/operation";
+        let unavailable_mock = mock_status(&unavailable, POST, path, 503);
+        let available_mock = mock_status(&available, POST, path, 200);
+
+        let http = ResilientHttp::<HttpClient>::with_endpoints([
+            unavailable.base_url(),
+            available.base_url(),
+        ]);
+
+        let result = http.post::<_, String, ()>(path, &"00ab", None).await;
+        assert!(matches!(
+            result,
+            Err(Error::ServiceUnavailable { status: 503, .. })
+        ));
+        unavailable_mock.assert_hits(1);
+        available_mock.assert_hits(0);
+    }
+
+    #[tokio::test]
+    async fn test_timeout() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET).path(CHAIN_ID);
+            then.status(200)
+                .delay(Duration::from_millis(500))
+                .header("content-type", "application/json")
+                .json_body(serde_json::json!("NetXdQprcVkpaWU"));
+        });
+
+        let http = ResilientHttp::<HttpClient>::new(server.base_url())
+            .timeout(Duration::from_millis(20))
+            .max_retries(1)
+            .backoff(Duration::from_millis(1), Duration::from_millis(1));
+
+        let result = http.get::<String>(CHAIN_ID).await;
+        assert!(matches!(result, Err(Error::Timeout)));
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        assert_eq!(parse_retry_after(" 120 "), Some(Duration::from_secs(120)));
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon"), None);
+    }
+}
@@ -0,0 +1,363 @@
+//! Waiting for an injected operation to be included in a block.
+//!
+//! ```rust
+//! use tezos_core::types::encoded::OperationHash;
+//! use tezos_rpc::{client::TezosRpc, Result};
+//!
+//! async fn example(operation_hash: &OperationHash) -> Result<()> {
+//!     let rpc = TezosRpc::new("https://testnet-tezos.giganode.io".into());
+//!     let inclusion = rpc
+//!         .wait_for_inclusion(operation_hash)
+//!         .confirmations(2)
+//!         .send()
+//!         .await?;
+//!     println!("included at level {}", inclusion.level);
+//!     Ok(())
+//! }
+//! ```
+
+use std::time::Duration;
+
+use futures::TryStreamExt;
+use futures_timer::Delay;
+use tezos_core::types::encoded::{BlockHash, OperationHash};
+
+use crate::{
+    client::TezosRpc,
+    http::{Http, HttpStream},
+    models::{
+        block::{Block, BlockId},
+        operation::OperationWithMetadata,
+    },
+    protocol_rpc::block::MetadataArg,
+    Error, Result,
+};
+
+/// The default interval between two polls of the head of the chain.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The block an operation has been included in.
+#[derive(Debug, Clone)]
+pub struct Inclusion {
+    pub block_hash: BlockHash,
+    pub level: i32,
+    /// The index of the list of operations, i.e. of the validation pass, containing the operation.
+    pub validation_pass: usize,
+    /// The index of the operation in its list of operations.
+    pub index: usize,
+    /// The number of blocks baked on top of the inclusion block.
+    pub confirmations: u32,
+    pub operation: OperationWithMetadata,
+}
+
+/// A builder to construct the properties of a wait for an operation to be included in a block.
+#[derive(Clone, Copy)]
+pub struct InclusionRequestBuilder<'a, HttpClient: Http> {
+    rpc: &'a TezosRpc<HttpClient>,
+    operation_hash: &'a OperationHash,
+    branch: Option<&'a BlockHash>,
+    confirmations: u32,
+    poll_interval: Duration,
+}
+
+impl<'a, HttpClient: Http> InclusionRequestBuilder<'a, HttpClient> {
+    pub fn new(rpc: &'a TezosRpc<HttpClient>, operation_hash: &'a OperationHash) -> Self {
+        Self {
+            rpc,
+            operation_hash,
+            branch: None,
+            confirmations: 0,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+        }
+    }
+
+    /// Modify the branch of the operation.
+    ///
+    /// The blocks are searched from the branch on and the wait fails once the operation can't be
+    /// included anymore. By default, the head at the time of the call is used as the branch,
+    /// which assumes the operation has just been injected.
+    pub fn branch(mut self, branch: &'a BlockHash) -> Self {
+        self.branch = Some(branch);
+
+        self
+    }
+
+    /// Modify the number of blocks that must be baked on top of the inclusion block (default: `0`).
+    pub fn confirmations(mut self, confirmations: u32) -> Self {
+        self.confirmations = confirmations;
+
+        self
+    }
+
+    /// Modify the interval between two polls of the head of the chain (default: [DEFAULT_POLL_INTERVAL]).
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+
+        self
+    }
+
+    /// Waits for the operation to be included, polling the head of the chain.
+    ///
+    /// Fails with [Error::OperationExpired] once the operation can't be included anymore,
+    /// which also covers a reorganization dropping the inclusion block.
+    pub async fn send(&self) -> Result<Inclusion> {
+        let mut tracker = InclusionTracker::new(self).await?;
+        loop {
+            if let Some(inclusion) = tracker.update().await? {
+                return Ok(inclusion);
+            }
+            Delay::new(self.poll_interval).await;
+        }
+    }
+}
+
+impl<'a, HttpClient: HttpStream> InclusionRequestBuilder<'a, HttpClient> {
+    /// Waits for the operation to be included, checking the chain each time a new head is
+    /// streamed by [`GET /monitor/heads/<chain_id>`](crate::shell_rpc::monitor::heads).
+    ///
+    /// Falls back to polling if the stream of heads ends.
+    pub async fn monitor(&self) -> Result<Inclusion> {
+        let mut heads = Some(self.rpc.monitor_heads().send().await?);
+        let mut tracker = InclusionTracker::new(self).await?;
+        loop {
+            if let Some(inclusion) = tracker.update().await? {
+                return Ok(inclusion);
+            }
+            match heads.as_mut() {
+                Some(stream) => {
+                    if stream.try_next().await?.is_none() {
+                        heads = None;
+                    }
+                }
+                None => Delay::new(self.poll_interval).await,
+            }
+        }
+    }
+}
+
+struct InclusionTracker<'a, 'b, HttpClient: Http> {
+    request: &'b InclusionRequestBuilder<'a, HttpClient>,
+    /// The level after which the operation can't be included anymore.
+    expiration_level: i32,
+    /// The blocks searched for the operation, ordered by level.
+    searched: Vec<(i32, BlockHash)>,
+    next_level: i32,
+    inclusion: Option<Inclusion>,
+}
+
+impl<'a, 'b, HttpClient: Http> InclusionTracker<'a, 'b, HttpClient> {
+    async fn new(request: &'b InclusionRequestBuilder<'a, HttpClient>) -> Result<Self> {
+        let head = request
+            .rpc
+            .get_block()
+            .metadata(MetadataArg::Always)
+            .send()
+            .await?;
+        let max_operations_ttl = head
+            .metadata
+            .as_ref()
+            .ok_or(Error::InvalidConversion)?
+            .max_operations_ttl;
+        let (branch_level, next_level) = match request.branch {
+            Some(branch) => {
+                let block_id = BlockId::Hash(branch.clone());
+                let level = request
+                    .rpc
+                    .get_block()
+                    .block_id(&block_id)
+                    .metadata(MetadataArg::Never)
+                    .send()
+                    .await?
+                    .header
+                    .level;
+                (level, level + 1)
+            }
+            None => (head.header.level, head.header.level),
+        };
+
+        Ok(Self {
+            request,
+            expiration_level: branch_level + max_operations_ttl,
+            searched: vec![],
+            next_level,
+            inclusion: None,
+        })
+    }
+
+    /// Searches the blocks baked since the last update and returns the inclusion once it has enough confirmations.
+    async fn update(&mut self) -> Result<Option<Inclusion>> {
+        let head_level = self
+            .request
+            .rpc
+            .get_block()
+            .metadata(MetadataArg::Never)
+            .send()
+            .await?
+            .header
+            .level;
+        self.drop_reorganized_blocks(head_level).await?;
+
+        while self.inclusion.is_none() && self.next_level <= head_level {
+            let block_id = BlockId::Level(self.next_level);
+            let block = self
+                .request
+                .rpc
+                .get_block()
+                .block_id(&block_id)
+                .metadata(MetadataArg::Always)
+                .send()
+                .await?;
+            self.search(block);
+            self.next_level += 1;
+        }
+
+        match self.inclusion.as_mut() {
+            Some(inclusion) => {
+                inclusion.confirmations = (head_level - inclusion.level) as u32;
+                if inclusion.confirmations >= self.request.confirmations {
+                    return Ok(Some(inclusion.clone()));
+                }
+                Ok(None)
+            }
+            None if head_level >= self.expiration_level => Err(Error::OperationExpired),
+            None => Ok(None),
+        }
+    }
+
+    /// Forgets the searched blocks which are no longer part of the chain, including the inclusion block.
+    async fn drop_reorganized_blocks(&mut self, head_level: i32) -> Result<()> {
+        while let Some((level, hash)) = self.searched.last() {
+            let is_reorganized = *level > head_level || {
+                let block_id = BlockId::Level(*level);
+                self.request
+                    .rpc
+                    .get_block_hash()
+                    .block_id(&block_id)
+                    .send()
+                    .await?
+                    != *hash
+            };
+            if !is_reorganized {
+                break;
+            }
+            if self
+                .inclusion
+                .as_ref()
+                .map_or(false, |inclusion| inclusion.level == *level)
+            {
+                self.inclusion = None;
+            }
+            self.next_level = *level;
+            self.searched.pop();
+        }
+
+        Ok(())
+    }
+
+    fn search(&mut self, block: Block) {
+        let operation_hash = Some(self.request.operation_hash);
+        for (validation_pass, operations) in block.operations.into_iter().enumerate() {
+            for (index, operation) in operations.into_iter().enumerate() {
+                if operation.hash.as_ref() == operation_hash {
+                    self.inclusion = Some(Inclusion {
+                        block_hash: block.hash.clone(),
+                        level: block.header.level,
+                        validation_pass,
+                        index,
+                        confirmations: 0,
+                        operation: OperationWithMetadata {
+                            contents: operation.contents,
+                            signature: operation.signature,
+                        },
+                    });
+                }
+            }
+        }
+        self.searched.push((block.header.level, block.hash));
+    }
+}
+
+#[cfg(all(test, feature = "http"))]
+mod tests {
+    use {
+        super::*, crate::internal::test_blocks::*, httpmock::prelude::*,
+        tezos_core::types::encoded::Encoded,
+    };
+
+    #[tokio::test]
+    async fn test_wait_for_inclusion() -> Result<()> {
+        let server = MockServer::start();
+        let rpc_url = server.base_url();
+
+        mock_block(&server, "head", block(BLOCK_A101, 101, BLOCK_A100, 120));
+        mock_block(&server, BLOCK_A99, block(BLOCK_A99, 99, BLOCK_A99, 120));
+        mock_block(&server, "100", block(BLOCK_A100, 100, BLOCK_A99, 120));
+
+        let client = TezosRpc::new(rpc_url);
+        let operation_hash: OperationHash = OPERATION.try_into()?;
+        let branch: BlockHash = BLOCK_A99.try_into()?;
+        let inclusion = client
+            .wait_for_inclusion(&operation_hash)
+            .branch(&branch)
+            .confirmations(1)
+            .send()
+            .await?;
+
+        assert_eq!(inclusion.block_hash.value(), BLOCK_A100);
+        assert_eq!(inclusion.level, 100);
+        assert_eq!(inclusion.validation_pass, 3);
+        assert_eq!(inclusion.index, 0);
+        assert_eq!(inclusion.confirmations, 1);
+        assert_eq!(inclusion.operation.contents.len(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_inclusion_reorganization() -> Result<()> {
+        let server = MockServer::start();
+        let rpc_url = server.base_url();
+
+        let mocks = vec![
+            mock_block(&server, "head", block(BLOCK_A101, 101, BLOCK_A100, 3)),
+            mock_block(&server, BLOCK_A99, block(BLOCK_A99, 99, BLOCK_A99, 3)),
+            mock_block(&server, "100", block(BLOCK_A100, 100, BLOCK_A99, 3)),
+        ];
+
+        let client = TezosRpc::new(rpc_url);
+        let operation_hash: OperationHash = OPERATION.try_into()?;
+        let branch: BlockHash = BLOCK_A99.try_into()?;
+        let request = client
+            .wait_for_inclusion(&operation_hash)
+            .branch(&branch)
+            .confirmations(2);
+        let mut tracker = InclusionTracker::new(&request).await?;
+
+        assert!(tracker.update().await?.is_none());
+        assert_eq!(tracker.inclusion.as_ref().unwrap().level, 100);
+
+        // The inclusion block is replaced by a block without the operation.
+        for mut mock in mocks {
+            mock.delete();
+        }
+        mock_block(&server, "head", block(BLOCK_B102, 102, BLOCK_B101, 3));
+        mock_block(&server, "100", block(BLOCK_B100, 100, BLOCK_A99, 3));
+        mock_block(&server, "101", block(BLOCK_B101, 101, BLOCK_B100, 3));
+        mock_block(&server, "102", block(BLOCK_B102, 102, BLOCK_B101, 3));
+        server.mock(|when, then| {
+            when.method(GET).path("/chains/main/blocks/100/hash");
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(BLOCK_B100);
+        });
+
+        assert!(matches!(
+            tracker.update().await,
+            Err(Error::OperationExpired)
+        ));
+        assert!(tracker.inclusion.is_none());
+        assert_eq!(tracker.searched.len(), 3);
+
+        Ok(())
+    }
+}
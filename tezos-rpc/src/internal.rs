@@ -1 +1,4 @@
 pub mod estimator;
+pub mod monitor;
+#[cfg(all(test, feature = "http"))]
+pub mod test_blocks;
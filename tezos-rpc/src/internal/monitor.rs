@@ -0,0 +1,231 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use futures::{
+    stream::{self, BoxStream},
+    StreamExt,
+};
+use futures_timer::Delay;
+use serde::de::DeserializeOwned;
+
+use crate::{
+    http::{resilient::jitter, ByteStream, HttpStream},
+    Result,
+};
+
+/// The default number of consecutive reconnections attempted before a monitor stream ends.
+pub(crate) const DEFAULT_MAX_RECONNECTIONS: usize = 5;
+
+/// The delay before the first reconnection, doubled for each following one up to [MAX_RECONNECTION_BACKOFF].
+const INITIAL_RECONNECTION_BACKOFF: Duration = Duration::from_millis(100);
+/// The maximum delay before a reconnection. The delays start over from [INITIAL_RECONNECTION_BACKOFF]
+/// only once a connection has been kept that long, so a node dropping every connection is not flooded.
+const MAX_RECONNECTION_BACKOFF: Duration = Duration::from_secs(5);
+
+/// The number of recently streamed items remembered to skip the ones sent again after a reconnection.
+const RECENT_ITEMS: usize = 1024;
+
+/// A request to a streaming RPC, re-sent each time the connection is lost.
+pub(crate) struct MonitorRequest<'a, HttpClient: HttpStream, D, T> {
+    pub http_client: &'a HttpClient,
+    pub path: String,
+    pub query: Vec<(&'static str, String)>,
+    pub max_reconnections: usize,
+    /// Splits a streamed JSON value into the items of the stream.
+    pub items: fn(D) -> Vec<T>,
+    /// Identifies an item, items with the same key are streamed only once.
+    pub key: fn(&T) -> String,
+}
+
+impl<'a, HttpClient, D, T> MonitorRequest<'a, HttpClient, D, T>
+where
+    HttpClient: HttpStream,
+    D: DeserializeOwned + Send + 'a,
+    T: Send + 'a,
+{
+    /// Opens the stream, failing if the first connection can't be established.
+    pub async fn open(self) -> Result<BoxStream<'a, Result<T>>> {
+        let body = self.connect().await?;
+        let state = MonitorState {
+            request: self,
+            body: Some(body),
+            decoder: JsonDecoder::new(),
+            pending: VecDeque::new(),
+            recent: VecDeque::new(),
+            has_new_items: false,
+            reconnections: 0,
+            backoff_round: 0,
+            connected_at: Instant::now(),
+            is_done: false,
+        };
+
+        Ok(stream::unfold(state, |state| state.next()).boxed())
+    }
+
+    async fn connect(&self) -> Result<ByteStream> {
+        self.http_client
+            .get_stream(self.path.as_str(), &self.query)
+            .await
+    }
+}
+
+struct MonitorState<'a, HttpClient: HttpStream, D, T> {
+    request: MonitorRequest<'a, HttpClient, D, T>,
+    body: Option<ByteStream>,
+    decoder: JsonDecoder,
+    pending: VecDeque<T>,
+    recent: VecDeque<String>,
+    has_new_items: bool,
+    reconnections: usize,
+    backoff_round: u32,
+    connected_at: Instant,
+    is_done: bool,
+}
+
+impl<'a, HttpClient, D, T> MonitorState<'a, HttpClient, D, T>
+where
+    HttpClient: HttpStream,
+    D: DeserializeOwned + Send + 'a,
+    T: Send + 'a,
+{
+    async fn next(mut self) -> Option<(Result<T>, Self)> {
+        loop {
+            if let Some(item) = self.pending.pop_front() {
+                return Some((Ok(item), self));
+            }
+            if self.is_done {
+                return None;
+            }
+
+            match self.body.as_mut() {
+                Some(body) => match body.next().await {
+                    Some(Ok(chunk)) => {
+                        if let Err(error) = self.receive(&chunk) {
+                            self.is_done = true;
+                            return Some((Err(error), self));
+                        }
+                    }
+                    // The connection has been closed or lost, a new one is opened on the next iteration.
+                    Some(Err(_)) | None => {
+                        self.body = None;
+                        self.decoder = JsonDecoder::new();
+                        if self.has_new_items {
+                            self.reconnections = 0;
+                            self.has_new_items = false;
+                        }
+                        if self.connected_at.elapsed() >= MAX_RECONNECTION_BACKOFF {
+                            self.backoff_round = 0;
+                        }
+                    }
+                },
+                None => {
+                    if self.reconnections >= self.request.max_reconnections {
+                        return None;
+                    }
+                    self.reconnections += 1;
+                    Delay::new(self.reconnection_delay()).await;
+                    match self.request.connect().await {
+                        Ok(body) => {
+                            self.body = Some(body);
+                            self.connected_at = Instant::now();
+                        }
+                        Err(error) if self.reconnections >= self.request.max_reconnections => {
+                            self.is_done = true;
+                            return Some((Err(error), self));
+                        }
+                        Err(_) => {}
+                    }
+                }
+            }
+        }
+    }
+
+    fn reconnection_delay(&mut self) -> Duration {
+        let backoff = INITIAL_RECONNECTION_BACKOFF
+            .saturating_mul(1 << self.backoff_round.min(16))
+            .min(MAX_RECONNECTION_BACKOFF);
+        self.backoff_round += 1;
+
+        backoff / 2 + jitter(backoff / 2)
+    }
+
+    fn receive(&mut self, chunk: &[u8]) -> Result<()> {
+        for value in self.decoder.push::<D>(chunk)? {
+            for item in (self.request.items)(value) {
+                let key = (self.request.key)(&item);
+                if self.recent.contains(&key) {
+                    continue;
+                }
+                if self.recent.len() == RECENT_ITEMS {
+                    self.recent.pop_front();
+                }
+                self.recent.push_back(key);
+                self.pending.push_back(item);
+                self.has_new_items = true;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Decodes a sequence of JSON values received in arbitrary chunks.
+#[derive(Debug, Default)]
+pub(crate) struct JsonDecoder {
+    buffer: Vec<u8>,
+}
+
+impl JsonDecoder {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Appends the `chunk` to the received bytes and returns the JSON values it completes.
+    pub fn push<D: DeserializeOwned>(&mut self, chunk: &[u8]) -> Result<Vec<D>> {
+        self.buffer.extend_from_slice(chunk);
+
+        let mut values = vec![];
+        let mut consumed = 0;
+        let mut stream = serde_json::Deserializer::from_slice(&self.buffer).into_iter::<D>();
+        loop {
+            match stream.next() {
+                Some(Ok(value)) => {
+                    values.push(value);
+                    consumed = stream.byte_offset();
+                }
+                Some(Err(error)) if error.is_eof() => break,
+                Some(Err(error)) => return Err(error.into()),
+                None => {
+                    consumed = self.buffer.len();
+                    break;
+                }
+            }
+        }
+        self.buffer.drain(..consumed);
+
+        Ok(values)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_json_decoder() -> Result<()> {
+        let mut decoder = JsonDecoder::new();
+        assert_eq!(decoder.push::<Vec<u8>>(b"[1,")?, Vec::<Vec<u8>>::new());
+        assert_eq!(decoder.push::<Vec<u8>>(b"2]\n[3")?, vec![vec![1, 2]]);
+        assert_eq!(decoder.push::<Vec<u8>>(b"]\n")?, vec![vec![3]]);
+        assert_eq!(
+            decoder.push::<Vec<u8>>(b"[4][5]\n[")?,
+            vec![vec![4], vec![5]]
+        );
+        assert_eq!(decoder.push::<Vec<u8>>(b"6]")?, vec![vec![6]]);
+        assert!(decoder.push::<Vec<u8>>(b"}").is_err());
+
+        Ok(())
+    }
+}
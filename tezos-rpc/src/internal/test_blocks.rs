@@ -0,0 +1,71 @@
+//! The blocks of two competing branches, forking after level 99, shared by the tests following the chain.
+
+use httpmock::prelude::*;
+
+pub const OPERATION: &str = "opBAcEN6eFsMa8bJsrf3Qfrf2w5PgzCxfqwFgkNNnzLKxPZ3UAA";
+pub const BLOCK_A99: &str = "BLq7VzUh9rvH57XxvE22tMsNyBciwzDmDuaGLHnhyhCt5raizQz";
+pub const BLOCK_A100: &str = "BLv7fUU9HigQ2xsseFknSCJz7FoXSPzy6rXjawkae5CDL2HTGbN";
+pub const BLOCK_A101: &str = "BMPQKwLXeWf3b9kevDUKu1wo9qrz9Gg5P8rsv3i6j1hgw7Sfufc";
+pub const BLOCK_B100: &str = "BLshmFSB8HiATMpjoYUz3QdpVmjqbVbGuBRoymgUerpDxeNFyn9";
+pub const BLOCK_B101: &str = "BLywLu2DkmvQUfKR9YSFnynFiRQQCAdty66dDxuuM3XqH2f2QCv";
+pub const BLOCK_B102: &str = "BMRyAdb77oxNxUc22Ur9CA9sQjjVkd6BqBkrKHGhq4dGC2qhxLX";
+
+/// Returns the JSON of a block, [BLOCK_A100] being the only one including [OPERATION].
+pub fn block(
+    hash: &str,
+    level: i32,
+    predecessor: &str,
+    max_operations_ttl: i32,
+) -> serde_json::Value {
+    let mut manager_operations = vec![];
+    if hash == BLOCK_A100 {
+        manager_operations.push(serde_json::json!({
+            "hash": OPERATION,
+            "protocol": "PtJakart2xVj7pYXJBXrqHgd82rdkLey5ZeeGwDgPp9rhQUbSqY",
+            "branch": BLOCK_A99,
+            "contents": [{ "kind": "failing_noop", "arbitrary": "cafe" }],
+            "signature": "sigQh51NhuohSTe11Pp8hXSE9VMn1TbbqZrGKAbLEUus852PPSmXs5Hjf9v3cbwtRewvcYK8F6PLUg85ZBYrUGX5GqgkRFC7"
+        }));
+    }
+
+    serde_json::json!({
+        "protocol": "PtJakart2xVj7pYXJBXrqHgd82rdkLey5ZeeGwDgPp9rhQUbSqY",
+        "chain_id": "NetXdQprcVkpaWU",
+        "hash": hash,
+        "header": {
+            "level": level,
+            "proto": 13,
+            "predecessor": predecessor,
+            "timestamp": "2022-07-04T06:37:44Z",
+            "validation_pass": 4,
+            "operations_hash": "LLoaxj33NkFZLAc9uvXcwNRZKdk6z83Ke5PEKrs32cUCPpkb9cJ8S",
+            "fitness": [],
+            "context": "CoVeQQ6XqvBF4vp8uCSatdWuZsyDx5x3SQwopjwcoWES8RFcjbgZ"
+        },
+        "operations": [[], [], [], manager_operations],
+        "metadata": {
+            "protocol": "PtJakart2xVj7pYXJBXrqHgd82rdkLey5ZeeGwDgPp9rhQUbSqY",
+            "next_protocol": "PtJakart2xVj7pYXJBXrqHgd82rdkLey5ZeeGwDgPp9rhQUbSqY",
+            "test_chain_status": { "status": "not_running" },
+            "max_operations_ttl": max_operations_ttl,
+            "max_operation_data_length": 32768,
+            "max_block_header_length": 289,
+            "max_operation_list_length": []
+        }
+    })
+}
+
+/// Mocks `GET /chains/main/blocks/<block_id>` to respond with the `block`.
+pub fn mock_block<'a>(
+    server: &'a MockServer,
+    block_id: &str,
+    block: serde_json::Value,
+) -> httpmock::Mock<'a> {
+    server.mock(|when, then| {
+        when.method(GET)
+            .path(format!("/chains/main/blocks/{}", block_id));
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(block);
+    })
+}
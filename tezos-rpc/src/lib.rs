@@ -19,6 +19,26 @@
 //! }
 //! ```
 //!
+//! ## Monitor the Chain
+//!
+//! The streaming RPCs return a [Stream](futures::Stream) of typed values, which is transparently
+//! re-opened when the connection to the node is lost:
+//!
+//! ```rust
+//! use futures::TryStreamExt;
+//! use tezos_core::types::encoded::Encoded;
+//! use tezos_rpc::{client::TezosRpc, Result};
+//!
+//! async fn example() -> Result<()> {
+//!     let rpc = TezosRpc::new("https://testnet-tezos.giganode.io".into());
+//!     let mut heads = rpc.monitor_heads().send().await?;
+//!     while let Some(head) = heads.try_next().await? {
+//!         println!("{} at level {}", head.hash.value(), head.header.level);
+//!     }
+//!     Ok(())
+//! }
+//! ```
+//!
 //! ## Estimate the Operation Fee and Inject
 //!
 //! You can estimate the minimum fee required for the operation to be injected with the
@@ -62,12 +82,19 @@
 //! }
 //! ```
 
+#[cfg(feature = "blocking")]
+pub mod blocking;
 pub mod client;
 pub mod constants;
 mod error;
+#[cfg(feature = "fake_node")]
+pub mod fake_node;
+pub mod follower;
 pub mod http;
+pub mod inclusion;
 pub mod models;
 pub mod protocol_rpc;
+pub mod remote_signer;
 pub mod shell_rpc;
 
 mod internal;
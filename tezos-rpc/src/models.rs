@@ -7,4 +7,6 @@ pub mod contract;
 pub mod error;
 pub mod invalid_block;
 pub mod limits;
+pub mod mempool;
+pub mod monitor;
 pub mod operation;
@@ -47,10 +47,13 @@ pub struct Header {
     pub proof_of_work_nonce: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub seed_nonce_hash: Option<NonceHash>,
-    #[serde(default)]
-    pub liquidity_baking_escape_vote: bool,
-    #[serde(default)]
-    pub liquidity_baking_toggle_vote: LiquidityBakingToggleVote,
+    /// Set for the headers produced before Jakarta, which carry it instead of `liquidity_baking_toggle_vote`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub liquidity_baking_escape_vote: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub liquidity_baking_toggle_vote: Option<LiquidityBakingToggleVote>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub adaptive_issuance_vote: Option<AdaptiveIssuanceVote>,
     pub signature: Option<Signature>,
 }
 
@@ -74,8 +77,9 @@ impl From<tezos_operation::block_header::BlockHeader> for Header {
             priority: 0,
             proof_of_work_nonce: value.proof_of_work_nonce.into(),
             seed_nonce_hash: value.seed_nonce_hash,
-            liquidity_baking_escape_vote: Default::default(),
-            liquidity_baking_toggle_vote: value.liquidity_baking_toggle_vote.into(),
+            liquidity_baking_escape_vote: None,
+            liquidity_baking_toggle_vote: Some(value.liquidity_baking_toggle_vote.into()),
+            adaptive_issuance_vote: Some(value.adaptive_issuance_vote.into()),
             signature: Some(value.signature),
         }
     }
@@ -102,7 +106,21 @@ impl TryFrom<Header> for tezos_operation::block_header::BlockHeader {
             payload_round: value.payload_round,
             proof_of_work_nonce: value.proof_of_work_nonce.try_into()?,
             seed_nonce_hash: value.seed_nonce_hash,
-            liquidity_baking_toggle_vote: value.liquidity_baking_toggle_vote.into(),
+            // Before Jakarta the vote was a boolean escape flag: `false` is forged as 0x00, like `On`,
+            // but `true` is forged as 0xff, which no toggle vote can represent.
+            liquidity_baking_toggle_vote: match (
+                value.liquidity_baking_toggle_vote,
+                value.liquidity_baking_escape_vote,
+            ) {
+                (Some(vote), _) => vote.into(),
+                (None, Some(false)) => tezos_operation::block_header::LiquidityBakingToggleVote::On,
+                (None, Some(true)) => return Err(Error::InvalidConversion),
+                (None, None) => LiquidityBakingToggleVote::default().into(),
+            },
+            adaptive_issuance_vote: value
+                .adaptive_issuance_vote
+                .map(|vote| vote.into())
+                .unwrap_or_default(),
             signature: value.signature.ok_or(Error::InvalidConversion)?,
         })
     }
@@ -142,6 +160,34 @@ impl From<LiquidityBakingToggleVote> for tezos_operation::block_header::Liquidit
     }
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AdaptiveIssuanceVote {
+    On,
+    Off,
+    Pass,
+}
+
+impl From<tezos_operation::block_header::AdaptiveIssuanceVote> for AdaptiveIssuanceVote {
+    fn from(value: tezos_operation::block_header::AdaptiveIssuanceVote) -> Self {
+        match value {
+            tezos_operation::block_header::AdaptiveIssuanceVote::On => Self::On,
+            tezos_operation::block_header::AdaptiveIssuanceVote::Off => Self::Off,
+            tezos_operation::block_header::AdaptiveIssuanceVote::Pass => Self::Pass,
+        }
+    }
+}
+
+impl From<AdaptiveIssuanceVote> for tezos_operation::block_header::AdaptiveIssuanceVote {
+    fn from(value: AdaptiveIssuanceVote) -> Self {
+        match value {
+            AdaptiveIssuanceVote::On => Self::On,
+            AdaptiveIssuanceVote::Off => Self::Off,
+            AdaptiveIssuanceVote::Pass => Self::Pass,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct FullHeader {
     pub protocol: ProtocolHash,
@@ -165,11 +211,47 @@ pub struct FullHeader {
     pub proof_of_work_nonce: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub seed_nonce_hash: Option<NonceHash>,
-    #[serde(default)]
-    pub liquidity_baking_escape_vote: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub liquidity_baking_escape_vote: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub liquidity_baking_toggle_vote: Option<LiquidityBakingToggleVote>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub adaptive_issuance_vote: Option<AdaptiveIssuanceVote>,
     pub signature: Option<Signature>,
 }
 
+impl From<FullHeader> for Header {
+    fn from(value: FullHeader) -> Self {
+        Self {
+            level: value.level,
+            proto: value.proto,
+            predecessor: value.predecessor,
+            timestamp: value.timestamp,
+            validation_pass: value.validation_pass,
+            operations_hash: value.operations_hash,
+            fitness: value.fitness,
+            context: value.context,
+            payload_hash: value.payload_hash,
+            payload_round: value.payload_round,
+            priority: value.priority,
+            proof_of_work_nonce: value.proof_of_work_nonce,
+            seed_nonce_hash: value.seed_nonce_hash,
+            liquidity_baking_escape_vote: value.liquidity_baking_escape_vote,
+            liquidity_baking_toggle_vote: value.liquidity_baking_toggle_vote,
+            adaptive_issuance_vote: value.adaptive_issuance_vote,
+            signature: value.signature,
+        }
+    }
+}
+
+impl TryFrom<FullHeader> for tezos_operation::block_header::BlockHeader {
+    type Error = Error;
+
+    fn try_from(value: FullHeader) -> Result<Self> {
+        Header::from(value).try_into()
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct Metadata {
     pub protocol: ProtocolHash,
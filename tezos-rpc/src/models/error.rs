@@ -36,9 +36,15 @@ impl Display for RpcError {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct RpcErrors(Vec<RpcError>);
 
+impl RpcErrors {
+    pub fn errors(&self) -> &[RpcError] {
+        &self.0
+    }
+}
+
 impl From<Vec<RpcError>> for RpcErrors {
     fn from(value: Vec<RpcError>) -> Self {
         Self(value)
@@ -0,0 +1,203 @@
+use {
+    super::{error::RpcErrors, operation::Operation},
+    serde::{Deserialize, Deserializer, Serialize},
+    tezos_core::types::{encoded::OperationHash, mutez::Mutez},
+};
+
+/// The operations of the mempool, classified by the prevalidator.
+///
+/// [`GET /chains/<chain_id>/mempool/pending_operations`](crate::shell_rpc::chains::chain::mempool::pending_operations)
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct PendingOperations {
+    /// Operations successfully validated on top of the current head (`applied` in older versions of the RPC).
+    #[serde(default, alias = "applied")]
+    pub validated: Vec<Operation>,
+    /// Operations that can't be included in any block.
+    #[serde(default)]
+    pub refused: Vec<PendingOperation>,
+    /// Operations whose branch is too old to be included.
+    #[serde(default)]
+    pub outdated: Vec<PendingOperation>,
+    /// Operations that can't be included on the current branch but may be after a reorganization.
+    #[serde(default)]
+    pub branch_refused: Vec<PendingOperation>,
+    /// Operations that can't be included yet but may be in a future block.
+    #[serde(default)]
+    pub branch_delayed: Vec<PendingOperation>,
+    /// Operations not yet classified.
+    #[serde(default)]
+    pub unprocessed: Vec<PendingOperation>,
+}
+
+/// An operation of the mempool, with the errors that prevent it from being validated.
+#[derive(Debug, Serialize, Clone)]
+pub struct PendingOperation {
+    #[serde(flatten)]
+    pub operation: Operation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcErrors>,
+}
+
+impl<'de> Deserialize<'de> for PendingOperation {
+    /// Older versions of the RPC return the operations as `[<operation_hash>, <operation>]` pairs.
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct Object {
+            #[serde(flatten)]
+            operation: Operation,
+            error: Option<RpcErrors>,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Versioned {
+            Object(Object),
+            Pair(OperationHash, Object),
+        }
+
+        let (hash, object) = match Versioned::deserialize(deserializer)? {
+            Versioned::Object(object) => (None, object),
+            Versioned::Pair(hash, object) => (Some(hash), object),
+        };
+        let mut operation = object.operation;
+        if operation.hash.is_none() {
+            operation.hash = hash;
+        }
+
+        Ok(Self {
+            operation,
+            error: object.error,
+        })
+    }
+}
+
+/// The configuration of the mempool filter.
+///
+/// When set, the node resets every field left to `None` to its default value. To change some fields only,
+/// get the current configuration with `include_default` set to `true`, modify it and set the whole value.
+///
+/// [`GET|POST /chains/<chain_id>/mempool/filter`](crate::shell_rpc::chains::chain::mempool::filter)
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct MempoolFilter {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub minimal_fees: Option<Mutez>,
+    /// The `(numerator, denominator)` of the minimal fee per gas unit, in nanotez.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub minimal_nanotez_per_gas_unit: Option<(String, String)>,
+    /// The `(numerator, denominator)` of the minimal fee per byte, in nanotez.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub minimal_nanotez_per_byte: Option<(String, String)>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_script_failure: Option<bool>,
+    /// The `(numerator, denominator)` of the factor by which the fee of an operation must exceed
+    /// the fee of the operation it replaces.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replace_by_fee_factor: Option<(String, String)>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_prechecked_manager_operations: Option<u32>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{error::Error, models::operation::OperationContent};
+    use tezos_core::types::encoded::Encoded;
+
+    #[test]
+    fn test_serde_deserialize() -> Result<(), Error> {
+        let json = r#"{
+            "applied": [{
+                "hash": "opBAcEN6eFsMa8bJsrf3Qfrf2w5PgzCxfqwFgkNNnzLKxPZ3UAA",
+                "branch": "BLPWXKfGv8RvwH9A5nuPYfXX3TX188Hpa2LdPvbLwCJ8QYVYzVh",
+                "contents": [{
+                    "kind": "transaction",
+                    "source": "tz1hob9tzbTxhwicH75CnS1es2PiLodzo1PX",
+                    "fee": "928",
+                    "counter": "1",
+                    "gas_limit": "3171",
+                    "storage_limit": "25",
+                    "amount": "15000000",
+                    "destination": "tz1PwXjsrgYBi9wpe3tFhazJpt7JMTVzBp5c"
+                }],
+                "signature": "sigQh51NhuohSTe11Pp8hXSE9VMn1TbbqZrGKAbLEUus852PPSmXs5Hjf9v3cbwtRewvcYK8F6PLUg85ZBYrUGX5GqgkRFC7"
+            }],
+            "refused": [[
+                "ooG169iWhv7vQccPGcB2EWeAjFWv4wuZ44ThkJ1yVDiSrptw3Jt",
+                {
+                    "protocol": "PtJakart2xVj7pYXJBXrqHgd82rdkLey5ZeeGwDgPp9rhQUbSqY",
+                    "branch": "BLPWXKfGv8RvwH9A5nuPYfXX3TX188Hpa2LdPvbLwCJ8QYVYzVh",
+                    "contents": [{ "kind": "failing_noop", "arbitrary": "cafe" }],
+                    "signature": "sigQh51NhuohSTe11Pp8hXSE9VMn1TbbqZrGKAbLEUus852PPSmXs5Hjf9v3cbwtRewvcYK8F6PLUg85ZBYrUGX5GqgkRFC7",
+                    "error": [{ "kind": "permanent", "id": "proto.013-PtJakart.failing_noop_error" }]
+                }
+            ]],
+            "branch_delayed": [{
+                "hash": "ooG169iWhv7vQccPGcB2EWeAjFWvxcrmQVCi4eWCviUTHeQuH24",
+                "protocol": "PtJakart2xVj7pYXJBXrqHgd82rdkLey5ZeeGwDgPp9rhQUbSqY",
+                "branch": "BLPWXKfGv8RvwH9A5nuPYfXX3TX188Hpa2LdPvbLwCJ8QYVYzVh",
+                "contents": [],
+                "error": [{ "kind": "temporary", "id": "proto.013-PtJakart.contract.counter_in_the_future" }]
+            }]
+        }"#;
+        let pending: PendingOperations = serde_json::from_str(json)?;
+
+        assert_eq!(pending.validated.len(), 1);
+        assert!(matches!(
+            pending.validated[0].contents[0],
+            OperationContent::Transaction(_)
+        ));
+        assert_eq!(
+            pending.refused[0].operation.hash.as_ref().unwrap().value(),
+            "ooG169iWhv7vQccPGcB2EWeAjFWv4wuZ44ThkJ1yVDiSrptw3Jt"
+        );
+        assert!(matches!(
+            pending.refused[0].operation.contents[0],
+            OperationContent::FailingNoop(_)
+        ));
+        let error = pending.refused[0].error.as_ref().unwrap();
+        assert_eq!(
+            error.errors()[0].id,
+            "proto.013-PtJakart.failing_noop_error"
+        );
+        assert_eq!(
+            pending.branch_delayed[0]
+                .operation
+                .hash
+                .as_ref()
+                .unwrap()
+                .value(),
+            "ooG169iWhv7vQccPGcB2EWeAjFWvxcrmQVCi4eWCviUTHeQuH24"
+        );
+        assert!(pending.outdated.is_empty());
+        assert!(pending.unprocessed.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_serde_filter() -> Result<(), Error> {
+        let json = r#"{"minimal_fees":"100","minimal_nanotez_per_gas_unit":["100","1"],"minimal_nanotez_per_byte":["1000","1"],"allow_script_failure":true,"replace_by_fee_factor":["21","20"],"max_prechecked_manager_operations":5000}"#;
+        let filter: MempoolFilter = serde_json::from_str(json)?;
+
+        assert_eq!(filter.minimal_fees, Some(100u8.into()));
+        assert_eq!(
+            filter.replace_by_fee_factor,
+            Some(("21".into(), "20".into()))
+        );
+        assert_eq!(serde_json::to_string(&filter)?, json);
+
+        let filter = MempoolFilter {
+            allow_script_failure: Some(false),
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_string(&filter)?,
+            r#"{"allow_script_failure":false}"#
+        );
+
+        Ok(())
+    }
+}
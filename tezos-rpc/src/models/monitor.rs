@@ -0,0 +1,60 @@
+use {
+    super::block::Header,
+    crate::serde_utils::rfc3339_timestamp,
+    chrono::NaiveDateTime,
+    serde::{Deserialize, Serialize},
+    tezos_core::types::encoded::{BlockHash, ChainId},
+};
+
+/// A new head of the chain, as streamed by [`GET /monitor/bootstrapped`](crate::shell_rpc::monitor::bootstrapped)
+/// while the node is bootstrapping.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct BootstrappedHead {
+    pub block: BlockHash,
+    #[serde(with = "rfc3339_timestamp")]
+    pub timestamp: NaiveDateTime,
+}
+
+/// A new head of the chain, as streamed by [`GET /monitor/heads/<chain_id>`](crate::shell_rpc::monitor::heads).
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct BlockHead {
+    pub hash: BlockHash,
+    #[serde(flatten)]
+    pub header: Header,
+}
+
+/// A block validated by the node, as streamed by [`GET /monitor/validated_blocks`](crate::shell_rpc::monitor::validated_blocks).
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ValidatedBlock {
+    pub chain_id: ChainId,
+    pub hash: BlockHash,
+    pub header: Header,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::error::Error;
+    use tezos_core::types::encoded::Encoded;
+
+    #[test]
+    fn test_serde_deserialize() -> Result<(), Error> {
+        let json = r#"{"hash":"BKvBmAJVpJ8drHMTzDZmFKEYc45xzeyHo1MUNtcomm6FMZkpXDW","level":2504461,"proto":13,"predecessor":"BLqagE7bXgwbLCeL6ooaj57HASQm52yUxxSGLUJrePeKSso3PWq","timestamp":"2022-07-04T06:37:44Z","validation_pass":4,"operations_hash":"LLoaxj33NkFZLAc9uvXcwNRZKdk6z83Ke5PEKrs32cUCPpkb9cJ8S","fitness":["02","0026370d","","ffffffff","00000000"],"context":"CoVeQQ6XqvBF4vp8uCSatdWuZsyDx5x3SQwopjwcoWES8RFcjbgZ","protocol_data":"a1f2b3"}"#;
+        let head: BlockHead = serde_json::from_str(json)?;
+
+        assert_eq!(
+            head.hash.value(),
+            "BKvBmAJVpJ8drHMTzDZmFKEYc45xzeyHo1MUNtcomm6FMZkpXDW"
+        );
+        assert_eq!(head.header.level, 2504461);
+        assert_eq!(head.header.fitness.len(), 5);
+
+        let json = r#"{"block":"BKvBmAJVpJ8drHMTzDZmFKEYc45xzeyHo1MUNtcomm6FMZkpXDW","timestamp":"2022-07-04T06:37:44Z"}"#;
+        let bootstrapped: BootstrappedHead = serde_json::from_str(json)?;
+
+        assert_eq!(bootstrapped.block, head.hash);
+        assert_eq!(bootstrapped.timestamp, head.header.timestamp);
+
+        Ok(())
+    }
+}
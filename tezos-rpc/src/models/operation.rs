@@ -5,7 +5,7 @@ pub mod operation_result;
 
 use {
     self::{
-        operation_contents_and_result::activate_account::ActivateAccount,
+        kind::OperationKind, operation_contents_and_result::activate_account::ActivateAccount,
         operation_contents_and_result::ballot::Ballot,
         operation_contents_and_result::delegation::Delegation,
         operation_contents_and_result::double_baking_evidence::DoubleBakingEvidence,
@@ -32,7 +32,7 @@ use {
         operation_contents_and_result::tx_rollup_submit_batch::TxRollupSubmitBatch,
     },
     crate::{Error, Result},
-    serde::{Deserialize, Serialize},
+    serde::{Deserialize, Deserializer, Serialize},
     tezos_core::types::encoded::{BlockHash, ChainId, OperationHash, ProtocolHash, Signature},
 };
 
@@ -84,7 +84,7 @@ impl From<tezos_operation::operations::SignedOperation> for Operation {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Clone)]
 #[serde(untagged)]
 pub enum OperationContent {
     // Present in alpha protocol
@@ -118,6 +118,72 @@ pub enum OperationContent {
     Unknown(serde_json::Value), // must be the last one
 }
 
+impl<'de> Deserialize<'de> for OperationContent {
+    /// The content is deserialized according to its `kind`, because some variants
+    /// have only optional fields and would otherwise match any kind of content.
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        let kind = value
+            .get("kind")
+            .and_then(|kind| OperationKind::deserialize(kind).ok());
+
+        fn content<T: serde::de::DeserializeOwned>(
+            value: &serde_json::Value,
+            variant: fn(T) -> OperationContent,
+        ) -> Option<OperationContent> {
+            T::deserialize(value).ok().map(variant)
+        }
+
+        let content = match kind {
+            Some(OperationKind::Endorsement) => content(&value, Self::Endorsement),
+            Some(OperationKind::Preendorsement) => content(&value, Self::Preendorsement),
+            Some(OperationKind::SeedNonceRevelation) => content(&value, Self::SeedNonceRevelation),
+            Some(OperationKind::DoubleEndorsementEvidence) => {
+                content(&value, Self::DoubleEndorsementEvidence)
+            }
+            Some(OperationKind::DoublePreendorsementEvidence) => {
+                content(&value, Self::DoublePreendorsementEvidence)
+            }
+            Some(OperationKind::DoubleBakingEvidence) => {
+                content(&value, Self::DoubleBakingEvidence)
+            }
+            Some(OperationKind::ActivateAccount) => content(&value, Self::ActivateAccount),
+            Some(OperationKind::Proposals) => content(&value, Self::Proposals),
+            Some(OperationKind::Ballot) => content(&value, Self::Ballot),
+            Some(OperationKind::Reveal) => content(&value, Self::Reveal),
+            Some(OperationKind::Transaction) => content(&value, Self::Transaction),
+            Some(OperationKind::Origination) => content(&value, Self::Origination),
+            Some(OperationKind::Delegation) => content(&value, Self::Delegation),
+            Some(OperationKind::RegisterGlobalConstant) => {
+                content(&value, Self::RegisterGlobalConstant)
+            }
+            Some(OperationKind::SetDepositsLimit) => content(&value, Self::SetDepositsLimit),
+            Some(OperationKind::FailingNoop) => content(&value, Self::FailingNoop),
+            Some(OperationKind::TxRollupOrigination) => content(&value, Self::TxRollupOrigination),
+            Some(OperationKind::TxRollupSubmitBatch) => content(&value, Self::TxRollupSubmitBatch),
+            Some(OperationKind::TxRollupCommit) => content(&value, Self::TxRollupCommit),
+            Some(OperationKind::TxRollupReturnBond) => content(&value, Self::TxRollupReturnBond),
+            Some(OperationKind::TxRollupFinalizeCommitment) => {
+                content(&value, Self::TxRollupFinalizeCommitment)
+            }
+            Some(OperationKind::TxRollupRemoveCommitment) => {
+                content(&value, Self::TxRollupRemoveCommitment)
+            }
+            Some(OperationKind::TxRollupRejection) => content(&value, Self::TxRollupRejection),
+            Some(OperationKind::TransferTicket) => content(&value, Self::TransferTicket),
+            Some(OperationKind::TxRollupDispatchTickets) => {
+                content(&value, Self::TxRollupDispatchTickets)
+            }
+            _ => None,
+        };
+
+        Ok(content.unwrap_or(Self::Unknown(value)))
+    }
+}
+
 impl From<tezos_operation::operations::OperationContent> for OperationContent {
     fn from(value: tezos_operation::operations::OperationContent) -> Self {
         match value {
@@ -108,8 +108,14 @@ pub fn get<HttpClient: Http>(ctx: &TezosRpcContext<HttpClient>) -> RpcRequestBui
 mod tests {
     use {
         super::*,
-        crate::{client::TezosRpc, error::Error, models::block::TestChainStatusName},
+        crate::{
+            client::TezosRpc,
+            error::Error,
+            models::block::{Header, TestChainStatusName},
+        },
         httpmock::prelude::*,
+        tezos_core::types::encoded::{OperationInclusionProof, OperationListListHash},
+        tezos_operation::block_header::BlockHeader,
     };
 
     #[tokio::test]
@@ -219,13 +225,45 @@ mod tests {
         });
         let client = TezosRpc::new(rpc_url);
 
-        client
+        let block = client
             .get_block()
             .block_id(&block_id)
             .metadata(super::MetadataArg::Always)
             .send()
             .await?;
 
+        assert_eq!(block.header.liquidity_baking_escape_vote, Some(false));
+        assert_eq!(block.header.liquidity_baking_toggle_vote, None);
+        let header: BlockHeader = block.header.clone().try_into()?;
+        assert_eq!(header.hash()?, block.hash);
+
+        let reserialized: Header = serde_json::from_str(&serde_json::to_string(&block.header)?)?;
+        assert_eq!(reserialized, block.header);
+
+        let mut escaped = block.header.clone();
+        escaped.liquidity_baking_escape_vote = Some(true);
+        assert!(matches!(
+            BlockHeader::try_from(escaped),
+            Err(Error::InvalidConversion)
+        ));
+
+        let passes = block
+            .operations
+            .iter()
+            .map(|operations| {
+                operations
+                    .iter()
+                    .map(|operation| operation.hash.clone().unwrap())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            OperationListListHash::from_validation_passes(&passes)?,
+            header.operations_hash
+        );
+        let proof = OperationInclusionProof::new(&passes, 3, 0)?;
+        assert!(proof.verify(&passes[3][0], &header.operations_hash)?);
+
         Ok(())
     }
 
@@ -254,6 +292,13 @@ mod tests {
 
         assert_eq!(block.operations[3].last().unwrap().contents.len(), 6);
 
+        let header: BlockHeader = block.header.try_into()?;
+        assert_eq!(header.hash()?, block.hash);
+        assert_eq!(
+            BlockHeader::from_forged_bytes(header.to_forged_bytes()?)?,
+            header
+        );
+
         Ok(())
     }
 }
@@ -0,0 +1,287 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+use tezos_core::types::encoded::{Encoded, ImplicitAddress, PublicKey, Signature};
+use tezos_operation::signer::Signer;
+
+#[cfg(feature = "http")]
+use crate::http::default::HttpClient;
+use crate::{http::Http, Result};
+
+/// A [Signer] backed by a remote signer speaking the `octez-signer` HTTP protocol.
+///
+/// If the remote signer requires authenticated requests, an authentication [Signer] holding
+/// one of the authorized keys can be set with [RemoteSigner::authenticated_with].
+///
+/// ```rust
+/// use tezos_rpc::remote_signer::RemoteSigner;
+///
+/// let signer = RemoteSigner::new(
+///     "http://localhost:6732".into(),
+///     "tz1PwXjsrgYBi9wpe3tFhazJpt7JMTVzBp5c".try_into().expect("valid conversion to ImplicitAddress"),
+/// );
+/// ```
+pub struct RemoteSigner<HttpClient: Http> {
+    http_client: HttpClient,
+    public_key_hash: ImplicitAddress,
+    authentication: Option<Box<dyn Signer>>,
+}
+
+#[cfg(feature = "http")]
+impl RemoteSigner<HttpClient> {
+    /// Creates a signer for the key identified by `public_key_hash`, served at `endpoint`.
+    pub fn new(endpoint: String, public_key_hash: ImplicitAddress) -> Self {
+        Self::new_signer(endpoint, public_key_hash)
+    }
+}
+
+impl<HttpClient: Http> RemoteSigner<HttpClient> {
+    const AUTHENTICATION_TAG: u8 = 0x04;
+
+    /// Creates a signer for the key identified by `public_key_hash`, served at `endpoint`.
+    pub fn new_signer(endpoint: String, public_key_hash: ImplicitAddress) -> Self {
+        Self {
+            http_client: HttpClient::new(endpoint),
+            public_key_hash,
+            authentication: None,
+        }
+    }
+
+    /// Authenticates the signing requests with signatures produced by the `signer`.
+    pub fn authenticated_with<S: Signer + 'static>(mut self, signer: S) -> Self {
+        self.authentication = Some(Box::new(signer));
+
+        self
+    }
+
+    pub fn public_key_hash(&self) -> &ImplicitAddress {
+        &self.public_key_hash
+    }
+
+    fn path(&self) -> String {
+        format!("/keys/{}", self.public_key_hash.value())
+    }
+
+    /// Fetches the public key of the signer.
+    ///
+    /// [`GET /keys/<pkh>`](https://tezos.gitlab.io/user/key-management.html#signer)
+    pub async fn get_public_key(&self) -> Result<PublicKey> {
+        let response: PublicKeyResponse = self.http_client.get(&self.path()).await?;
+
+        Ok(response.public_key)
+    }
+
+    /// Fetches the keys authorized to authenticate signing requests.
+    ///
+    /// Returns `None` if the remote signer doesn't require authentication.
+    ///
+    /// [`GET /authorized_keys`](https://tezos.gitlab.io/user/key-management.html#signer)
+    pub async fn get_authorized_keys(&self) -> Result<Option<Vec<ImplicitAddress>>> {
+        let response: AuthorizedKeysResponse = self.http_client.get("/authorized_keys").await?;
+
+        Ok(response.authorized_keys)
+    }
+
+    /// Requests a signature of the `bytes`, which should already be prefixed with a watermark.
+    ///
+    /// [`POST /keys/<pkh>?[authentication=<signature>]`](https://tezos.gitlab.io/user/key-management.html#signer)
+    pub async fn sign_bytes(&self, bytes: &[u8]) -> Result<Signature> {
+        let mut query: Vec<(&str, String)> = vec![];
+        if let Some(authentication) = &self.authentication {
+            let signature = authentication
+                .sign(&self.authentication_watermark()?, bytes)
+                .await?;
+            query.push(("authentication", signature.value().into()));
+        }
+
+        let response: SignatureResponse = self
+            .http_client
+            .post(&self.path(), &hex::encode(bytes), Some(&query))
+            .await?;
+
+        Ok(response.signature)
+    }
+
+    fn authentication_watermark(&self) -> Result<Vec<u8>> {
+        Ok([
+            [Self::AUTHENTICATION_TAG].as_slice(),
+            &self.public_key_hash.to_bytes()?,
+        ]
+        .concat())
+    }
+}
+
+#[async_trait]
+impl<HttpClient: Http + Send + Sync> Signer for RemoteSigner<HttpClient> {
+    async fn public_key(&self) -> tezos_operation::Result<PublicKey> {
+        self.get_public_key().await.map_err(into_signer_error)
+    }
+
+    async fn address(&self) -> tezos_operation::Result<ImplicitAddress> {
+        Ok(self.public_key_hash.clone())
+    }
+
+    async fn sign(&self, watermark: &[u8], bytes: &[u8]) -> tezos_operation::Result<Signature> {
+        self.sign_bytes(&[watermark, bytes].concat())
+            .await
+            .map_err(into_signer_error)
+    }
+}
+
+fn into_signer_error(error: crate::Error) -> tezos_operation::Error {
+    match error {
+        crate::Error::Operation { source } => source,
+        _ => tezos_operation::Error::Signer {
+            description: error.to_string(),
+        },
+    }
+}
+
+#[derive(Deserialize)]
+struct PublicKeyResponse {
+    public_key: PublicKey,
+}
+
+#[derive(Deserialize)]
+struct AuthorizedKeysResponse {
+    authorized_keys: Option<Vec<ImplicitAddress>>,
+}
+
+#[derive(Deserialize)]
+struct SignatureResponse {
+    signature: Signature,
+}
+
+#[cfg(all(test, feature = "http"))]
+mod tests {
+    use {super::*, crate::Error, httpmock::prelude::*};
+
+    const PUBLIC_KEY_HASH: &str = "tz1RFxSHjxxyaaXyAvJG3uRQeJUCCm4jk2LX";
+    const PUBLIC_KEY: &str = "edpkttZKC51wemRqL2QxwpMnEKxWnbd35pq47Y6xsCHp5M1f7LN8NP";
+    const SIGNATURE: &str = "edsigtyqcyfipEAqFVexKahDjtQkzFgAkd9MroyYHnxxUVHAi4oSBJSezwiKaoNpH9NkY34cNuR4nrL6s8oPWVstQp9h2f7iGQF";
+
+    fn signer(server: &MockServer) -> RemoteSigner<HttpClient> {
+        RemoteSigner::new(server.base_url(), PUBLIC_KEY_HASH.try_into().unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_public_key() -> Result<()> {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET).path(format!("/keys/{}", PUBLIC_KEY_HASH));
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(serde_json::json!({ "public_key": PUBLIC_KEY }));
+        });
+
+        let signer = signer(&server);
+        assert_eq!(signer.get_public_key().await?.value(), PUBLIC_KEY);
+        assert_eq!(Signer::address(&signer).await?.value(), PUBLIC_KEY_HASH);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_authorized_keys() -> Result<()> {
+        let server = MockServer::start();
+        let mut mock = server.mock(|when, then| {
+            when.method(GET).path("/authorized_keys");
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(serde_json::json!({}));
+        });
+
+        let signer = signer(&server);
+        assert_eq!(signer.get_authorized_keys().await?, None);
+
+        mock.delete();
+        server.mock(|when, then| {
+            when.method(GET).path("/authorized_keys");
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(serde_json::json!({ "authorized_keys": [PUBLIC_KEY_HASH] }));
+        });
+        assert_eq!(
+            signer.get_authorized_keys().await?,
+            Some(vec![PUBLIC_KEY_HASH.try_into()?])
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sign() -> Result<()> {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(POST)
+                .path(format!("/keys/{}", PUBLIC_KEY_HASH))
+                .json_body(serde_json::json!("030102"));
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(serde_json::json!({ "signature": SIGNATURE }));
+        });
+
+        let signer = signer(&server);
+        let signature = Signer::sign(&signer, &[0x03], &[0x01, 0x02]).await?;
+        assert_eq!(signature.value(), SIGNATURE);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sign_rejected() -> Result<()> {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(POST).path(format!("/keys/{}", PUBLIC_KEY_HASH));
+            then.status(403)
+                .header("content-type", "text/plain")
+                .body("Unauthorized");
+        });
+
+        let signer = signer(&server);
+        assert!(matches!(
+            signer.sign_bytes(&[0x03]).await,
+            Err(Error::RpcErrorPlain { .. })
+        ));
+        assert!(matches!(
+            Signer::sign(&signer, &[0x03], &[]).await,
+            Err(tezos_operation::Error::Signer { .. })
+        ));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "ed25519")]
+    #[tokio::test]
+    async fn test_sign_authenticated() -> Result<()> {
+        use tezos_operation::signer::InMemorySigner;
+
+        let authentication = InMemorySigner::new(
+            "edskRv7VyXGVZb8EsrR7D9XKUbbAQNQGtALP6QeB16ZCD7SmmJpzyeneJVg3Mq56YLbxRA1kSdAXiswwPiaVfR3NHGMCXCziuZ".try_into()?,
+            PUBLIC_KEY.try_into()?,
+        );
+        let public_key_hash: ImplicitAddress = PUBLIC_KEY_HASH.try_into()?;
+        let expected_authentication = authentication
+            .sign(
+                &[[0x04].as_slice(), &public_key_hash.to_bytes()?].concat(),
+                &[0x03, 0x01, 0x02],
+            )
+            .await?;
+
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(POST)
+                .path(format!("/keys/{}", PUBLIC_KEY_HASH))
+                .query_param("authentication", expected_authentication.value())
+                .json_body(serde_json::json!("030102"));
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(serde_json::json!({ "signature": SIGNATURE }));
+        });
+
+        let signer = signer(&server).authenticated_with(authentication);
+        let signature = signer.sign_bytes(&[0x03, 0x01, 0x02]).await?;
+        assert_eq!(signature.value(), SIGNATURE);
+
+        Ok(())
+    }
+}
@@ -5,6 +5,7 @@ pub mod chain_id;
 pub mod invalid_blocks;
 pub mod is_bootstrapped;
 pub mod levels;
+pub mod mempool;
 
 use {crate::client::TezosRpcContext, crate::error::Error, serde::Serialize};
 
@@ -0,0 +1,10 @@
+pub mod ban_operation;
+pub mod filter;
+pub mod monitor_operations;
+pub mod pending_operations;
+pub mod request_operations;
+pub mod unban_operation;
+
+fn path<S: AsRef<str>>(chain_id: S) -> String {
+    format!("{}/mempool", super::path(chain_id))
+}
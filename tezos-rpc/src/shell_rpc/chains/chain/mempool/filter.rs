@@ -0,0 +1,187 @@
+use {
+    crate::client::{TezosRpcChainId, TezosRpcContext},
+    crate::error::Error,
+    crate::http::Http,
+    crate::models::mempool::MempoolFilter,
+};
+
+fn path<S: AsRef<str>>(chain_id: S) -> String {
+    format!("{}/filter", super::path(chain_id))
+}
+
+/// A builder to construct the properties of a request to get the configuration of the mempool filter.
+#[derive(Clone, Copy)]
+pub struct GetRPCRequestBuilder<'a, HttpClient: Http> {
+    ctx: &'a TezosRpcContext<HttpClient>,
+    chain_id: &'a TezosRpcChainId,
+    include_default: Option<bool>,
+}
+
+impl<'a, HttpClient: Http> GetRPCRequestBuilder<'a, HttpClient> {
+    pub fn new(ctx: &'a TezosRpcContext<HttpClient>) -> Self {
+        GetRPCRequestBuilder {
+            ctx,
+            chain_id: ctx.chain_id(),
+            include_default: None,
+        }
+    }
+
+    /// Modify chain identifier to be used in the request.
+    pub fn chain_id(mut self, chain_id: &'a TezosRpcChainId) -> Self {
+        self.chain_id = chain_id;
+
+        self
+    }
+
+    /// Include the fields set to their default value (default: `true`).
+    pub fn include_default(mut self, include_default: bool) -> Self {
+        self.include_default = Some(include_default);
+
+        self
+    }
+
+    pub async fn send(&self) -> Result<MempoolFilter, Error> {
+        let mut query: Vec<(&str, String)> = vec![];
+
+        if let Some(include_default) = self.include_default {
+            query.push(("include_default", include_default.to_string()));
+        }
+
+        self.ctx
+            .http_client()
+            .get_with_query(self::path(self.chain_id.value()).as_str(), &query)
+            .await
+    }
+}
+
+/// A builder to construct the properties of a request to set the configuration of the mempool filter.
+///
+/// The node resets the fields of the [MempoolFilter] left to `None` to their default value,
+/// so the whole configuration, e.g. fetched with [GetRPCRequestBuilder::include_default], should be sent.
+#[derive(Clone, Copy)]
+pub struct PostRPCRequestBuilder<'a, HttpClient: Http> {
+    ctx: &'a TezosRpcContext<HttpClient>,
+    chain_id: &'a TezosRpcChainId,
+    filter: &'a MempoolFilter,
+}
+
+impl<'a, HttpClient: Http> PostRPCRequestBuilder<'a, HttpClient> {
+    pub fn new(ctx: &'a TezosRpcContext<HttpClient>, filter: &'a MempoolFilter) -> Self {
+        PostRPCRequestBuilder {
+            ctx,
+            chain_id: ctx.chain_id(),
+            filter,
+        }
+    }
+
+    /// Modify chain identifier to be used in the request.
+    pub fn chain_id(mut self, chain_id: &'a TezosRpcChainId) -> Self {
+        self.chain_id = chain_id;
+
+        self
+    }
+
+    pub async fn send(&self) -> Result<(), Error> {
+        self.ctx
+            .http_client()
+            .post::<_, serde_json::Value, ()>(
+                self::path(self.chain_id.value()).as_str(),
+                self.filter,
+                None,
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Get the configuration of the mempool filter.
+///
+/// Optional query arguments:
+/// * `include_default` : Include the fields set to their default value (default: `true`).
+///
+/// [`GET /chains/<chain_id>/mempool/filter?[include_default]`](https://tezos.gitlab.io/active/rpc.html#get-chains-chain-id-mempool-filter)
+pub fn get<HttpClient: Http>(
+    ctx: &TezosRpcContext<HttpClient>,
+) -> GetRPCRequestBuilder<HttpClient> {
+    GetRPCRequestBuilder::new(ctx)
+}
+
+/// Set the configuration of the mempool filter. The fields left to `None` are reset to their default value.
+///
+/// [`POST /chains/<chain_id>/mempool/filter`](https://tezos.gitlab.io/active/rpc.html#post-chains-chain-id-mempool-filter)
+pub fn post<'a, HttpClient: Http>(
+    ctx: &'a TezosRpcContext<HttpClient>,
+    filter: &'a MempoolFilter,
+) -> PostRPCRequestBuilder<'a, HttpClient> {
+    PostRPCRequestBuilder::new(ctx, filter)
+}
+
+#[cfg(all(test, feature = "http"))]
+mod tests {
+    use {
+        crate::{client::TezosRpc, error::Error, models::mempool::MempoolFilter},
+        httpmock::prelude::*,
+    };
+
+    #[tokio::test]
+    async fn test_get_filter() -> Result<(), Error> {
+        let server = MockServer::start();
+        let rpc_url = server.base_url();
+
+        server.mock(|when, then| {
+            when.method(GET)
+                .path(super::path("main"))
+                .query_param("include_default", "false");
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(serde_json::json!({
+                    "minimal_fees": "200",
+                    "allow_script_failure": false
+                }));
+        });
+        let client = TezosRpc::new(rpc_url);
+
+        let filter = client
+            .get_mempool_filter()
+            .include_default(false)
+            .send()
+            .await?;
+
+        assert_eq!(
+            filter,
+            MempoolFilter {
+                minimal_fees: Some(200u8.into()),
+                allow_script_failure: Some(false),
+                ..Default::default()
+            }
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_set_filter() -> Result<(), Error> {
+        let server = MockServer::start();
+        let rpc_url = server.base_url();
+
+        server.mock(|when, then| {
+            when.method(POST)
+                .path(super::path("main"))
+                .json_body(serde_json::json!({
+                    "minimal_nanotez_per_gas_unit": ["50", "1"]
+                }));
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(serde_json::json!({}));
+        });
+        let client = TezosRpc::new(rpc_url);
+
+        let filter = MempoolFilter {
+            minimal_nanotez_per_gas_unit: Some(("50".into(), "1".into())),
+            ..Default::default()
+        };
+
+        client.set_mempool_filter(&filter).send().await
+    }
+}
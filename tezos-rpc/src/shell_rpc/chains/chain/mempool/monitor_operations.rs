@@ -0,0 +1,210 @@
+use {
+    crate::client::{TezosRpcChainId, TezosRpcContext},
+    crate::error::Error,
+    crate::http::HttpStream,
+    crate::internal::monitor::{MonitorRequest, DEFAULT_MAX_RECONNECTIONS},
+    crate::models::operation::Operation,
+    futures::stream::BoxStream,
+    tezos_core::types::encoded::Encoded,
+};
+
+fn path<S: AsRef<str>>(chain_id: S) -> String {
+    format!("{}/monitor_operations", super::path(chain_id))
+}
+
+/// A builder to construct the properties of a request to monitor the operations of the mempool.
+#[derive(Clone, Copy)]
+pub struct RpcRequestBuilder<'a, HttpClient: HttpStream> {
+    ctx: &'a TezosRpcContext<HttpClient>,
+    chain_id: &'a TezosRpcChainId,
+    validated: Option<bool>,
+    refused: Option<bool>,
+    outdated: Option<bool>,
+    branch_refused: Option<bool>,
+    branch_delayed: Option<bool>,
+    max_reconnections: usize,
+}
+
+impl<'a, HttpClient: HttpStream> RpcRequestBuilder<'a, HttpClient> {
+    pub fn new(ctx: &'a TezosRpcContext<HttpClient>) -> Self {
+        RpcRequestBuilder {
+            ctx,
+            chain_id: ctx.chain_id(),
+            validated: None,
+            refused: None,
+            outdated: None,
+            branch_refused: None,
+            branch_delayed: None,
+            max_reconnections: DEFAULT_MAX_RECONNECTIONS,
+        }
+    }
+
+    /// Modify chain identifier to be used in the request.
+    pub fn chain_id(mut self, chain_id: &'a TezosRpcChainId) -> Self {
+        self.chain_id = chain_id;
+
+        self
+    }
+
+    /// Include the validated operations (default: `true`).
+    pub fn validated(mut self, validated: bool) -> Self {
+        self.validated = Some(validated);
+
+        self
+    }
+
+    /// Include the refused operations (default: `false`).
+    pub fn refused(mut self, refused: bool) -> Self {
+        self.refused = Some(refused);
+
+        self
+    }
+
+    /// Include the outdated operations (default: `false`).
+    pub fn outdated(mut self, outdated: bool) -> Self {
+        self.outdated = Some(outdated);
+
+        self
+    }
+
+    /// Include the branch refused operations (default: `false`).
+    pub fn branch_refused(mut self, branch_refused: bool) -> Self {
+        self.branch_refused = Some(branch_refused);
+
+        self
+    }
+
+    /// Include the branch delayed operations (default: `true`).
+    pub fn branch_delayed(mut self, branch_delayed: bool) -> Self {
+        self.branch_delayed = Some(branch_delayed);
+
+        self
+    }
+
+    /// Modify the number of consecutive reconnections attempted when the connection is lost, before the stream ends.
+    pub fn max_reconnections(mut self, max_reconnections: usize) -> Self {
+        self.max_reconnections = max_reconnections;
+
+        self
+    }
+
+    /// Opens the stream of the operations entering the mempool.
+    ///
+    /// The node closes the stream when a new head is selected, it's then transparently re-opened
+    /// and the operations already streamed aren't streamed again.
+    pub async fn send(&self) -> Result<BoxStream<'a, Result<Operation, Error>>, Error> {
+        let flags = [
+            ("validated", self.validated),
+            ("refused", self.refused),
+            ("outdated", self.outdated),
+            ("branch_refused", self.branch_refused),
+            ("branch_delayed", self.branch_delayed),
+        ];
+
+        MonitorRequest {
+            http_client: self.ctx.http_client(),
+            path: self::path(self.chain_id.value()),
+            query: flags
+                .into_iter()
+                .filter_map(|(name, value)| value.map(|value| (name, value.to_string())))
+                .collect(),
+            max_reconnections: self.max_reconnections,
+            items: |operations: Vec<Operation>| operations,
+            key: |operation: &Operation| match &operation.hash {
+                Some(hash) => hash.value().into(),
+                None => serde_json::to_string(operation).unwrap_or_default(),
+            },
+        }
+        .open()
+        .await
+    }
+}
+
+/// Monitor the operations entering the mempool, streamed in batches as they are classified.
+///
+/// Optional query arguments:
+/// * `validated` : Include validated operations (default: `true`).
+/// * `refused` : Include refused operations (default: `false`).
+/// * `outdated` : Include outdated operations (default: `false`).
+/// * `branch_refused` : Include branch refused operations (default: `false`).
+/// * `branch_delayed` : Include branch delayed operations (default: `true`).
+///
+/// [`GET /chains/<chain_id>/mempool/monitor_operations?[validated]&[refused]&[outdated]&[branch_refused]&[branch_delayed]`](https://tezos.gitlab.io/active/rpc.html#get-chains-chain-id-mempool-monitor-operations)
+pub fn get<HttpClient: HttpStream>(
+    ctx: &TezosRpcContext<HttpClient>,
+) -> RpcRequestBuilder<HttpClient> {
+    RpcRequestBuilder::new(ctx)
+}
+
+#[cfg(all(test, feature = "http"))]
+mod tests {
+    use {
+        crate::{client::TezosRpc, error::Error, models::operation::OperationContent},
+        futures::StreamExt,
+        httpmock::prelude::*,
+        tezos_core::types::encoded::Encoded,
+    };
+
+    fn operation(hash: &str, counter: u32) -> serde_json::Value {
+        serde_json::json!({
+            "hash": hash,
+            "protocol": "PtJakart2xVj7pYXJBXrqHgd82rdkLey5ZeeGwDgPp9rhQUbSqY",
+            "branch": "BLPWXKfGv8RvwH9A5nuPYfXX3TX188Hpa2LdPvbLwCJ8QYVYzVh",
+            "contents": [{
+                "kind": "transaction",
+                "source": "tz1hob9tzbTxhwicH75CnS1es2PiLodzo1PX",
+                "fee": "928",
+                "counter": counter.to_string(),
+                "gas_limit": "3171",
+                "storage_limit": "25",
+                "amount": "15000000",
+                "destination": "tz1PwXjsrgYBi9wpe3tFhazJpt7JMTVzBp5c"
+            }],
+            "signature": "sigQh51NhuohSTe11Pp8hXSE9VMn1TbbqZrGKAbLEUus852PPSmXs5Hjf9v3cbwtRewvcYK8F6PLUg85ZBYrUGX5GqgkRFC7"
+        })
+    }
+
+    const OPERATION_1: &str = "opBAcEN6eFsMa8bJsrf3Qfrf2w5PgzCxfqwFgkNNnzLKxPZ3UAA";
+    const OPERATION_2: &str = "ooG169iWhv7vQccPGcB2EWeAjFWv4wuZ44ThkJ1yVDiSrptw3Jt";
+
+    #[tokio::test]
+    async fn test_monitor_operations() -> Result<(), Error> {
+        let server = MockServer::start();
+        let rpc_url = server.base_url();
+
+        server.mock(|when, then| {
+            when.method(GET)
+                .path(super::path("main"))
+                .query_param("refused", "true")
+                .query_param("branch_delayed", "false");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(format!(
+                    "{}\n{}\n",
+                    serde_json::json!([operation(OPERATION_1, 1)]),
+                    serde_json::json!([operation(OPERATION_1, 1), operation(OPERATION_2, 2)]),
+                ));
+        });
+        let client = TezosRpc::new(rpc_url);
+
+        let operations: Vec<_> = client
+            .monitor_mempool_operations()
+            .refused(true)
+            .branch_delayed(false)
+            .max_reconnections(1)
+            .send()
+            .await?
+            .collect()
+            .await;
+
+        assert_eq!(operations.len(), 2);
+        let operation = operations[1].as_ref().unwrap();
+        assert_eq!(operation.hash.as_ref().unwrap().value(), OPERATION_2);
+        assert!(matches!(
+            &operation.contents[0],
+            OperationContent::Transaction(transaction) if transaction.counter == "2"
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,196 @@
+use {
+    crate::client::{TezosRpcChainId, TezosRpcContext},
+    crate::error::Error,
+    crate::http::Http,
+    crate::models::mempool::PendingOperations,
+};
+
+fn path<S: AsRef<str>>(chain_id: S) -> String {
+    format!("{}/pending_operations", super::path(chain_id))
+}
+
+/// A builder to construct the properties of a request to get the operations of the mempool.
+#[derive(Clone, Copy)]
+pub struct RpcRequestBuilder<'a, HttpClient: Http> {
+    ctx: &'a TezosRpcContext<HttpClient>,
+    chain_id: &'a TezosRpcChainId,
+    version: Option<u8>,
+    validated: Option<bool>,
+    refused: Option<bool>,
+    outdated: Option<bool>,
+    branch_refused: Option<bool>,
+    branch_delayed: Option<bool>,
+}
+
+impl<'a, HttpClient: Http> RpcRequestBuilder<'a, HttpClient> {
+    pub fn new(ctx: &'a TezosRpcContext<HttpClient>) -> Self {
+        RpcRequestBuilder {
+            ctx,
+            chain_id: ctx.chain_id(),
+            version: None,
+            validated: None,
+            refused: None,
+            outdated: None,
+            branch_refused: None,
+            branch_delayed: None,
+        }
+    }
+
+    /// Modify chain identifier to be used in the request.
+    pub fn chain_id(mut self, chain_id: &'a TezosRpcChainId) -> Self {
+        self.chain_id = chain_id;
+
+        self
+    }
+
+    /// Modify the version of the response format, all the versions are supported by [PendingOperations].
+    pub fn version(mut self, version: u8) -> Self {
+        self.version = Some(version);
+
+        self
+    }
+
+    /// Include the validated operations (default: `true`).
+    pub fn validated(mut self, validated: bool) -> Self {
+        self.validated = Some(validated);
+
+        self
+    }
+
+    /// Include the refused operations (default: `true`).
+    pub fn refused(mut self, refused: bool) -> Self {
+        self.refused = Some(refused);
+
+        self
+    }
+
+    /// Include the outdated operations (default: `true`).
+    pub fn outdated(mut self, outdated: bool) -> Self {
+        self.outdated = Some(outdated);
+
+        self
+    }
+
+    /// Include the branch refused operations (default: `true`).
+    pub fn branch_refused(mut self, branch_refused: bool) -> Self {
+        self.branch_refused = Some(branch_refused);
+
+        self
+    }
+
+    /// Include the branch delayed operations (default: `true`).
+    pub fn branch_delayed(mut self, branch_delayed: bool) -> Self {
+        self.branch_delayed = Some(branch_delayed);
+
+        self
+    }
+
+    pub async fn send(&self) -> Result<PendingOperations, Error> {
+        let mut query: Vec<(&str, String)> = vec![];
+
+        if let Some(version) = self.version {
+            query.push(("version", version.to_string()));
+        }
+        let flags = [
+            ("validated", self.validated),
+            ("refused", self.refused),
+            ("outdated", self.outdated),
+            ("branch_refused", self.branch_refused),
+            ("branch_delayed", self.branch_delayed),
+        ];
+        for (name, value) in flags {
+            if let Some(value) = value {
+                query.push((name, value.to_string()));
+            }
+        }
+
+        self.ctx
+            .http_client()
+            .get_with_query(self::path(self.chain_id.value()).as_str(), &query)
+            .await
+    }
+}
+
+/// Get the operations of the mempool, classified as validated, refused, outdated, branch refused,
+/// branch delayed or unprocessed.
+///
+/// Optional query arguments:
+/// * `version` : The version of the response format.
+/// * `validated` : Include validated operations (default: `true`).
+/// * `refused` : Include refused operations (default: `true`).
+/// * `outdated` : Include outdated operations (default: `true`).
+/// * `branch_refused` : Include branch refused operations (default: `true`).
+/// * `branch_delayed` : Include branch delayed operations (default: `true`).
+///
+/// [`GET /chains/<chain_id>/mempool/pending_operations?[version=<int>]&[validated]&[refused]&[outdated]&[branch_refused]&[branch_delayed]`](https://tezos.gitlab.io/active/rpc.html#get-chains-chain-id-mempool-pending-operations)
+pub fn get<HttpClient: Http>(ctx: &TezosRpcContext<HttpClient>) -> RpcRequestBuilder<HttpClient> {
+    RpcRequestBuilder::new(ctx)
+}
+
+#[cfg(all(test, feature = "http"))]
+mod tests {
+    use {
+        crate::{client::TezosRpc, error::Error},
+        httpmock::prelude::*,
+        tezos_core::types::encoded::Encoded,
+    };
+
+    #[tokio::test]
+    async fn test_get_pending_operations() -> Result<(), Error> {
+        let server = MockServer::start();
+        let rpc_url = server.base_url();
+
+        server.mock(|when, then| {
+            when.method(GET)
+                .path(super::path("main"))
+                .query_param("version", "2")
+                .query_param("outdated", "false");
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(serde_json::json!({
+                    "validated": [],
+                    "refused": [],
+                    "branch_refused": [],
+                    "branch_delayed": [{
+                        "hash": "ooG169iWhv7vQccPGcB2EWeAjFWvxcrmQVCi4eWCviUTHeQuH24",
+                        "protocol": "PtJakart2xVj7pYXJBXrqHgd82rdkLey5ZeeGwDgPp9rhQUbSqY",
+                        "branch": "BLPWXKfGv8RvwH9A5nuPYfXX3TX188Hpa2LdPvbLwCJ8QYVYzVh",
+                        "contents": [{
+                            "kind": "reveal",
+                            "source": "tz1hob9tzbTxhwicH75CnS1es2PiLodzo1PX",
+                            "fee": "374",
+                            "counter": "2",
+                            "gas_limit": "1100",
+                            "storage_limit": "0",
+                            "public_key": "edpkttZKC51wemRqL2QxwpMnEKxWnbd35pq47Y6xsCHp5M1f7LN8NP"
+                        }],
+                        "signature": "sigQh51NhuohSTe11Pp8hXSE9VMn1TbbqZrGKAbLEUus852PPSmXs5Hjf9v3cbwtRewvcYK8F6PLUg85ZBYrUGX5GqgkRFC7",
+                        "error": [{ "kind": "temporary", "id": "proto.013-PtJakart.contract.counter_in_the_future" }]
+                    }],
+                    "unprocessed": []
+                }));
+        });
+        let client = TezosRpc::new(rpc_url);
+
+        let pending = client
+            .get_pending_operations()
+            .version(2)
+            .outdated(false)
+            .send()
+            .await?;
+
+        assert!(pending.validated.is_empty());
+        assert_eq!(pending.branch_delayed.len(), 1);
+        let operation = &pending.branch_delayed[0];
+        assert_eq!(
+            operation.operation.hash.as_ref().unwrap().value(),
+            "ooG169iWhv7vQccPGcB2EWeAjFWvxcrmQVCi4eWCviUTHeQuH24"
+        );
+        assert_eq!(
+            operation.error.as_ref().unwrap().errors()[0].kind,
+            "temporary"
+        );
+
+        Ok(())
+    }
+}
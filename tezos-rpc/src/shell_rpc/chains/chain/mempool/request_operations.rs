@@ -0,0 +1,103 @@
+use {
+    crate::client::{TezosRpcChainId, TezosRpcContext},
+    crate::error::Error,
+    crate::http::Http,
+};
+
+fn path<S: AsRef<str>>(chain_id: S) -> String {
+    format!("{}/request_operations", super::path(chain_id))
+}
+
+/// A builder to construct the properties of a request to ask the peers for their mempool operations.
+#[derive(Clone, Copy)]
+pub struct RpcRequestBuilder<'a, HttpClient: Http> {
+    ctx: &'a TezosRpcContext<HttpClient>,
+    chain_id: &'a TezosRpcChainId,
+    peer_id: Option<&'a str>,
+}
+
+impl<'a, HttpClient: Http> RpcRequestBuilder<'a, HttpClient> {
+    pub fn new(ctx: &'a TezosRpcContext<HttpClient>) -> Self {
+        RpcRequestBuilder {
+            ctx,
+            chain_id: ctx.chain_id(),
+            peer_id: None,
+        }
+    }
+
+    /// Modify chain identifier to be used in the request.
+    pub fn chain_id(mut self, chain_id: &'a TezosRpcChainId) -> Self {
+        self.chain_id = chain_id;
+
+        self
+    }
+
+    /// Ask a single peer, identified by its crypto-box public key hash, instead of all the connected peers.
+    pub fn peer_id(mut self, peer_id: &'a str) -> Self {
+        self.peer_id = Some(peer_id);
+
+        self
+    }
+
+    pub async fn send(&self) -> Result<(), Error> {
+        let mut query: Vec<(&str, String)> = vec![];
+
+        if let Some(peer_id) = self.peer_id {
+            query.push(("peer_id", peer_id.into()));
+        }
+
+        self.ctx
+            .http_client()
+            .post::<_, serde_json::Value, _>(
+                self::path(self.chain_id.value()).as_str(),
+                &serde_json::json!({}),
+                Some(&query),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Request the operations of the mempool of the connected peers.
+///
+/// Optional query arguments:
+/// * `peer_id` : The peer to request the operations from, all the connected peers by default.
+///
+/// [`POST /chains/<chain_id>/mempool/request_operations?[peer_id=<P2p_peer.Id>]`](https://tezos.gitlab.io/active/rpc.html#post-chains-chain-id-mempool-request-operations)
+pub fn post<HttpClient: Http>(ctx: &TezosRpcContext<HttpClient>) -> RpcRequestBuilder<HttpClient> {
+    RpcRequestBuilder::new(ctx)
+}
+
+#[cfg(all(test, feature = "http"))]
+mod tests {
+    use {
+        crate::{client::TezosRpc, error::Error},
+        httpmock::prelude::*,
+    };
+
+    #[tokio::test]
+    async fn test_request_operations() -> Result<(), Error> {
+        let server = MockServer::start();
+        let rpc_url = server.base_url();
+
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path(super::path("main"))
+                .query_param("peer_id", "idtJunmtBmtxLkGVWwwQGR3fUV3HQH");
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(serde_json::json!({}));
+        });
+        let client = TezosRpc::new(rpc_url);
+
+        client
+            .request_operations()
+            .peer_id("idtJunmtBmtxLkGVWwwQGR3fUV3HQH")
+            .send()
+            .await?;
+        mock.assert();
+
+        Ok(())
+    }
+}
@@ -0,0 +1,91 @@
+use {
+    crate::client::{TezosRpcChainId, TezosRpcContext},
+    crate::error::Error,
+    crate::http::Http,
+    tezos_core::types::encoded::OperationHash,
+};
+
+fn path<S: AsRef<str>>(chain_id: S) -> String {
+    format!("{}/unban_operation", super::path(chain_id))
+}
+
+/// A builder to construct the properties of a request to remove an operation from the set of banned operations.
+#[derive(Clone, Copy)]
+pub struct RpcRequestBuilder<'a, HttpClient: Http> {
+    ctx: &'a TezosRpcContext<HttpClient>,
+    chain_id: &'a TezosRpcChainId,
+    operation_hash: &'a OperationHash,
+}
+
+impl<'a, HttpClient: Http> RpcRequestBuilder<'a, HttpClient> {
+    pub fn new(ctx: &'a TezosRpcContext<HttpClient>, operation_hash: &'a OperationHash) -> Self {
+        RpcRequestBuilder {
+            ctx,
+            chain_id: ctx.chain_id(),
+            operation_hash,
+        }
+    }
+
+    /// Modify chain identifier to be used in the request.
+    pub fn chain_id(mut self, chain_id: &'a TezosRpcChainId) -> Self {
+        self.chain_id = chain_id;
+
+        self
+    }
+
+    pub async fn send(&self) -> Result<(), Error> {
+        self.ctx
+            .http_client()
+            .post::<_, serde_json::Value, ()>(
+                self::path(self.chain_id.value()).as_str(),
+                self.operation_hash,
+                None,
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Remove an operation from the set of banned operations, so it can be added to the mempool again.
+///
+/// [`POST /chains/<chain_id>/mempool/unban_operation`](https://tezos.gitlab.io/active/rpc.html#post-chains-chain-id-mempool-unban-operation)
+pub fn post<'a, HttpClient: Http>(
+    ctx: &'a TezosRpcContext<HttpClient>,
+    operation_hash: &'a OperationHash,
+) -> RpcRequestBuilder<'a, HttpClient> {
+    RpcRequestBuilder::new(ctx, operation_hash)
+}
+
+#[cfg(all(test, feature = "http"))]
+mod tests {
+    use {
+        crate::{client::TezosRpc, error::Error},
+        httpmock::prelude::*,
+        tezos_core::types::encoded::OperationHash,
+    };
+
+    #[tokio::test]
+    async fn test_unban_operation() -> Result<(), Error> {
+        let server = MockServer::start();
+        let rpc_url = server.base_url();
+
+        let operation_hash: OperationHash = "opBAcEN6eFsMa8bJsrf3Qfrf2w5PgzCxfqwFgkNNnzLKxPZ3UAA"
+            .try_into()
+            .unwrap();
+
+        server.mock(|when, then| {
+            when.method(POST)
+                .path(super::path("main"))
+                .json_body(serde_json::json!(
+                    "opBAcEN6eFsMa8bJsrf3Qfrf2w5PgzCxfqwFgkNNnzLKxPZ3UAA"
+                ));
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(serde_json::json!({}));
+        });
+        let client = TezosRpc::new(rpc_url);
+
+        client.unban_operation(&operation_hash).send().await
+    }
+}
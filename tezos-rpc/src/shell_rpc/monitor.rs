@@ -0,0 +1,7 @@
+pub mod bootstrapped;
+pub mod heads;
+pub mod validated_blocks;
+
+fn path() -> &'static str {
+    "/monitor"
+}
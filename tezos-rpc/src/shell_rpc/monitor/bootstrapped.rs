@@ -0,0 +1,102 @@
+use {
+    crate::client::TezosRpcContext,
+    crate::error::Error,
+    crate::http::HttpStream,
+    crate::internal::monitor::{MonitorRequest, DEFAULT_MAX_RECONNECTIONS},
+    crate::models::monitor::BootstrappedHead,
+    futures::stream::BoxStream,
+    tezos_core::types::encoded::Encoded,
+};
+
+fn path() -> String {
+    format!("{}/bootstrapped", super::path())
+}
+
+/// A builder to construct the properties of a request to monitor the bootstrapping of the node.
+#[derive(Clone, Copy)]
+pub struct RpcRequestBuilder<'a, HttpClient: HttpStream> {
+    ctx: &'a TezosRpcContext<HttpClient>,
+    max_reconnections: usize,
+}
+
+impl<'a, HttpClient: HttpStream> RpcRequestBuilder<'a, HttpClient> {
+    pub fn new(ctx: &'a TezosRpcContext<HttpClient>) -> Self {
+        RpcRequestBuilder {
+            ctx,
+            max_reconnections: DEFAULT_MAX_RECONNECTIONS,
+        }
+    }
+
+    /// Modify the number of consecutive reconnections attempted when the connection is lost, before the stream ends.
+    pub fn max_reconnections(mut self, max_reconnections: usize) -> Self {
+        self.max_reconnections = max_reconnections;
+
+        self
+    }
+
+    /// Opens the stream of the heads received while bootstrapping. The stream ends once the node is bootstrapped.
+    pub async fn send(&self) -> Result<BoxStream<'a, Result<BootstrappedHead, Error>>, Error> {
+        MonitorRequest {
+            http_client: self.ctx.http_client(),
+            path: self::path(),
+            query: vec![],
+            max_reconnections: self.max_reconnections,
+            items: |head| vec![head],
+            key: |head: &BootstrappedHead| head.block.value().into(),
+        }
+        .open()
+        .await
+    }
+}
+
+/// Monitor the heads received by the node while it's bootstrapping, the stream is closed by the node once it's bootstrapped.
+///
+/// [`GET /monitor/bootstrapped`](https://tezos.gitlab.io/shell/rpc.html#get-monitor-bootstrapped)
+pub fn get<HttpClient: HttpStream>(
+    ctx: &TezosRpcContext<HttpClient>,
+) -> RpcRequestBuilder<HttpClient> {
+    RpcRequestBuilder::new(ctx)
+}
+
+#[cfg(all(test, feature = "http"))]
+mod tests {
+    use {
+        crate::{client::TezosRpc, error::Error},
+        futures::TryStreamExt,
+        httpmock::prelude::*,
+        tezos_core::types::encoded::Encoded,
+    };
+
+    #[tokio::test]
+    async fn test_monitor_bootstrapped() -> Result<(), Error> {
+        let server = MockServer::start();
+        let rpc_url = server.base_url();
+
+        server.mock(|when, then| {
+            when.method(GET).path(super::path());
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(concat!(
+                    r#"{"block":"BLqagE7bXgwbLCeL6ooaj57HASQm52yUxxSGLUJrePeKSso3PWq","timestamp":"2022-07-04T06:37:14Z"}"#,
+                    r#"{"block":"BKvBmAJVpJ8drHMTzDZmFKEYc45xzeyHo1MUNtcomm6FMZkpXDW","timestamp":"2022-07-04T06:37:44Z"}"#,
+                ));
+        });
+        let client = TezosRpc::new(rpc_url);
+
+        let heads: Vec<_> = client
+            .monitor_bootstrapped()
+            .max_reconnections(0)
+            .send()
+            .await?
+            .try_collect()
+            .await?;
+
+        assert_eq!(heads.len(), 2);
+        assert_eq!(
+            heads[1].block.value(),
+            "BKvBmAJVpJ8drHMTzDZmFKEYc45xzeyHo1MUNtcomm6FMZkpXDW"
+        );
+
+        Ok(())
+    }
+}
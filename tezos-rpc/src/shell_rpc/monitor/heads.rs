@@ -0,0 +1,195 @@
+use {
+    crate::client::{TezosRpcChainId, TezosRpcContext},
+    crate::error::Error,
+    crate::http::HttpStream,
+    crate::internal::monitor::{MonitorRequest, DEFAULT_MAX_RECONNECTIONS},
+    crate::models::monitor::BlockHead,
+    futures::stream::BoxStream,
+    tezos_core::types::encoded::{Encoded, ProtocolHash},
+};
+
+fn path<S: AsRef<str>>(chain_id: S) -> String {
+    format!("{}/heads/{}", super::path(), chain_id.as_ref())
+}
+
+/// A builder to construct the properties of a request to monitor the new heads of a chain.
+#[derive(Clone, Copy)]
+pub struct RpcRequestBuilder<'a, HttpClient: HttpStream> {
+    ctx: &'a TezosRpcContext<HttpClient>,
+    chain_id: &'a TezosRpcChainId,
+    next_protocols: &'a [ProtocolHash],
+    max_reconnections: usize,
+}
+
+impl<'a, HttpClient: HttpStream> RpcRequestBuilder<'a, HttpClient> {
+    pub fn new(ctx: &'a TezosRpcContext<HttpClient>) -> Self {
+        RpcRequestBuilder {
+            ctx,
+            chain_id: ctx.chain_id(),
+            next_protocols: &[],
+            max_reconnections: DEFAULT_MAX_RECONNECTIONS,
+        }
+    }
+
+    /// Modify chain identifier to be used in the request.
+    pub fn chain_id(mut self, chain_id: &'a TezosRpcChainId) -> Self {
+        self.chain_id = chain_id;
+
+        self
+    }
+
+    /// Only monitor the heads whose metadata's next protocol is one of the given protocols.
+    pub fn next_protocols(mut self, next_protocols: &'a [ProtocolHash]) -> Self {
+        self.next_protocols = next_protocols;
+
+        self
+    }
+
+    /// Modify the number of consecutive reconnections attempted when the connection is lost, before the stream ends.
+    pub fn max_reconnections(mut self, max_reconnections: usize) -> Self {
+        self.max_reconnections = max_reconnections;
+
+        self
+    }
+
+    /// Opens the stream of the new heads.
+    ///
+    /// The connection is transparently re-opened if it's lost, and the heads already streamed aren't streamed again.
+    pub async fn send(&self) -> Result<BoxStream<'a, Result<BlockHead, Error>>, Error> {
+        MonitorRequest {
+            http_client: self.ctx.http_client(),
+            path: self::path(self.chain_id.value()),
+            query: self
+                .next_protocols
+                .iter()
+                .map(|protocol| ("next_protocol", protocol.value().into()))
+                .collect(),
+            max_reconnections: self.max_reconnections,
+            items: |head| vec![head],
+            key: |head: &BlockHead| head.hash.value().into(),
+        }
+        .open()
+        .await
+    }
+}
+
+/// Monitor all the new heads of a chain, as they are validated.
+///
+/// Optional query arguments:
+/// * `next_protocol` : Only the heads whose metadata's next protocol is one of the given protocols are streamed.
+///
+/// [`GET /monitor/heads/<chain_id>?[next_protocol=<Protocol_hash>]*`](https://tezos.gitlab.io/shell/rpc.html#get-monitor-heads-chain-id)
+pub fn get<HttpClient: HttpStream>(
+    ctx: &TezosRpcContext<HttpClient>,
+) -> RpcRequestBuilder<HttpClient> {
+    RpcRequestBuilder::new(ctx)
+}
+
+#[cfg(all(test, feature = "http"))]
+mod tests {
+    use {
+        crate::{client::TezosRpc, error::Error},
+        futures::StreamExt,
+        httpmock::prelude::*,
+        tezos_core::types::encoded::Encoded,
+    };
+
+    fn head(hash: &str, level: i32, predecessor: &str) -> String {
+        serde_json::json!({
+            "hash": hash,
+            "level": level,
+            "proto": 13,
+            "predecessor": predecessor,
+            "timestamp": "2022-07-04T06:37:44Z",
+            "validation_pass": 4,
+            "operations_hash": "LLoaxj33NkFZLAc9uvXcwNRZKdk6z83Ke5PEKrs32cUCPpkb9cJ8S",
+            "fitness": ["02", "0026370d", "", "ffffffff", "00000000"],
+            "context": "CoVeQQ6XqvBF4vp8uCSatdWuZsyDx5x3SQwopjwcoWES8RFcjbgZ",
+            "protocol_data": "00"
+        })
+        .to_string()
+    }
+
+    const BLOCK_1: &str = "BLPWXKfGv8RvwH9A5nuPYfXX3TX188Hpa2LdPvbLwCJ8QYVYzVh";
+    const BLOCK_2: &str = "BLqagE7bXgwbLCeL6ooaj57HASQm52yUxxSGLUJrePeKSso3PWq";
+    const BLOCK_3: &str = "BKvBmAJVpJ8drHMTzDZmFKEYc45xzeyHo1MUNtcomm6FMZkpXDW";
+
+    #[tokio::test]
+    async fn test_monitor_heads() -> Result<(), Error> {
+        let server = MockServer::start();
+        let rpc_url = server.base_url();
+
+        let mut mock = server.mock(|when, then| {
+            when.method(GET).path(super::path("main")).query_param(
+                "next_protocol",
+                "PtJakart2xVj7pYXJBXrqHgd82rdkLey5ZeeGwDgPp9rhQUbSqY",
+            );
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(format!(
+                    "{}\n{}\n",
+                    head(BLOCK_1, 1, BLOCK_3),
+                    head(BLOCK_2, 2, BLOCK_1)
+                ));
+        });
+        let client = TezosRpc::new(rpc_url);
+        let protocols = ["PtJakart2xVj7pYXJBXrqHgd82rdkLey5ZeeGwDgPp9rhQUbSqY".try_into()?];
+        let mut heads = client
+            .monitor_heads()
+            .next_protocols(&protocols)
+            .send()
+            .await?;
+
+        let first = heads.next().await.unwrap()?;
+        let second = heads.next().await.unwrap()?;
+        assert_eq!(first.hash.value(), BLOCK_1);
+        assert_eq!(second.hash.value(), BLOCK_2);
+        assert_eq!(second.header.predecessor, first.hash);
+
+        // the connection is re-opened and the current head, sent again, is skipped
+        mock.delete();
+        mock = server.mock(|when, then| {
+            when.method(GET).path(super::path("main"));
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(format!(
+                    "{}\n{}\n",
+                    head(BLOCK_2, 2, BLOCK_1),
+                    head(BLOCK_3, 3, BLOCK_2)
+                ));
+        });
+        let third = heads.next().await.unwrap()?;
+        assert_eq!(third.hash.value(), BLOCK_3);
+        assert_eq!(third.header.level, 3);
+
+        // the stream ends after the maximum number of reconnections without new heads,
+        // each one delayed by at least half of its doubling backoff (100 + 200 + 400 + 800 + 1600 ms)
+        let started = std::time::Instant::now();
+        assert!(heads.next().await.is_none());
+        mock.assert_hits(1 + crate::internal::monitor::DEFAULT_MAX_RECONNECTIONS);
+        assert!(started.elapsed() >= std::time::Duration::from_millis(3100));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_monitor_heads_error() -> Result<(), Error> {
+        let server = MockServer::start();
+        let rpc_url = server.base_url();
+
+        server.mock(|when, then| {
+            when.method(GET).path(super::path("main"));
+            then.status(404)
+                .header("content-type", "text/plain")
+                .body("Not found");
+        });
+        let client = TezosRpc::new(rpc_url);
+
+        assert!(matches!(
+            client.monitor_heads().send().await,
+            Err(Error::RpcErrorPlain { .. })
+        ));
+
+        Ok(())
+    }
+}
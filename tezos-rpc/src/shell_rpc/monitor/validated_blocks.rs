@@ -0,0 +1,167 @@
+use {
+    crate::client::{TezosRpcChainId, TezosRpcContext},
+    crate::error::Error,
+    crate::http::HttpStream,
+    crate::internal::monitor::{MonitorRequest, DEFAULT_MAX_RECONNECTIONS},
+    crate::models::monitor::ValidatedBlock,
+    futures::stream::BoxStream,
+    tezos_core::types::encoded::{Encoded, ProtocolHash},
+};
+
+fn path() -> String {
+    format!("{}/validated_blocks", super::path())
+}
+
+/// A builder to construct the properties of a request to monitor the blocks validated by the node.
+#[derive(Clone, Copy)]
+pub struct RpcRequestBuilder<'a, HttpClient: HttpStream> {
+    ctx: &'a TezosRpcContext<HttpClient>,
+    chain_ids: &'a [TezosRpcChainId],
+    protocols: &'a [ProtocolHash],
+    next_protocols: &'a [ProtocolHash],
+    max_reconnections: usize,
+}
+
+impl<'a, HttpClient: HttpStream> RpcRequestBuilder<'a, HttpClient> {
+    pub fn new(ctx: &'a TezosRpcContext<HttpClient>) -> Self {
+        RpcRequestBuilder {
+            ctx,
+            chain_ids: &[],
+            protocols: &[],
+            next_protocols: &[],
+            max_reconnections: DEFAULT_MAX_RECONNECTIONS,
+        }
+    }
+
+    /// Only monitor the blocks of the given chains.
+    pub fn chain_ids(mut self, chain_ids: &'a [TezosRpcChainId]) -> Self {
+        self.chain_ids = chain_ids;
+
+        self
+    }
+
+    /// Only monitor the blocks of the given protocols.
+    pub fn protocols(mut self, protocols: &'a [ProtocolHash]) -> Self {
+        self.protocols = protocols;
+
+        self
+    }
+
+    /// Only monitor the blocks whose metadata's next protocol is one of the given protocols.
+    pub fn next_protocols(mut self, next_protocols: &'a [ProtocolHash]) -> Self {
+        self.next_protocols = next_protocols;
+
+        self
+    }
+
+    /// Modify the number of consecutive reconnections attempted when the connection is lost, before the stream ends.
+    pub fn max_reconnections(mut self, max_reconnections: usize) -> Self {
+        self.max_reconnections = max_reconnections;
+
+        self
+    }
+
+    /// Opens the stream of the validated blocks.
+    ///
+    /// The connection is transparently re-opened if it's lost, and the blocks already streamed aren't streamed again.
+    pub async fn send(&self) -> Result<BoxStream<'a, Result<ValidatedBlock, Error>>, Error> {
+        let chains = self
+            .chain_ids
+            .iter()
+            .map(|chain_id| ("chain", chain_id.value().into()));
+        let protocols = self
+            .protocols
+            .iter()
+            .map(|protocol| ("protocol", protocol.value().into()));
+        let next_protocols = self
+            .next_protocols
+            .iter()
+            .map(|protocol| ("next_protocol", protocol.value().into()));
+
+        MonitorRequest {
+            http_client: self.ctx.http_client(),
+            path: self::path(),
+            query: chains.chain(protocols).chain(next_protocols).collect(),
+            max_reconnections: self.max_reconnections,
+            items: |block| vec![block],
+            key: |block: &ValidatedBlock| block.hash.value().into(),
+        }
+        .open()
+        .await
+    }
+}
+
+/// Monitor all the blocks that are successfully validated by the node, and selected as head or not.
+///
+/// Optional query arguments:
+/// * `chain` : Only the blocks of the given chains are streamed.
+/// * `protocol` : Only the blocks of the given protocols are streamed.
+/// * `next_protocol` : Only the blocks whose metadata's next protocol is one of the given protocols are streamed.
+///
+/// [`GET /monitor/validated_blocks?[chain=<chain_id>]*&[protocol=<Protocol_hash>]*&[next_protocol=<Protocol_hash>]*`](https://tezos.gitlab.io/shell/rpc.html#get-monitor-validated-blocks)
+pub fn get<HttpClient: HttpStream>(
+    ctx: &TezosRpcContext<HttpClient>,
+) -> RpcRequestBuilder<HttpClient> {
+    RpcRequestBuilder::new(ctx)
+}
+
+#[cfg(all(test, feature = "http"))]
+mod tests {
+    use {
+        crate::{
+            client::{TezosRpc, TezosRpcChainId},
+            error::Error,
+        },
+        futures::StreamExt,
+        httpmock::prelude::*,
+        tezos_core::types::encoded::Encoded,
+    };
+
+    #[tokio::test]
+    async fn test_monitor_validated_blocks() -> Result<(), Error> {
+        let server = MockServer::start();
+        let rpc_url = server.base_url();
+
+        let block = serde_json::json!({
+            "chain_id": "NetXdQprcVkpaWU",
+            "hash": "BKvBmAJVpJ8drHMTzDZmFKEYc45xzeyHo1MUNtcomm6FMZkpXDW",
+            "header": {
+                "level": 2504461,
+                "proto": 13,
+                "predecessor": "BLqagE7bXgwbLCeL6ooaj57HASQm52yUxxSGLUJrePeKSso3PWq",
+                "timestamp": "2022-07-04T06:37:44Z",
+                "validation_pass": 4,
+                "operations_hash": "LLoaxj33NkFZLAc9uvXcwNRZKdk6z83Ke5PEKrs32cUCPpkb9cJ8S",
+                "fitness": ["02", "0026370d", "", "ffffffff", "00000000"],
+                "context": "CoVeQQ6XqvBF4vp8uCSatdWuZsyDx5x3SQwopjwcoWES8RFcjbgZ",
+                "protocol_data": "00"
+            },
+            "operations": [[], [], [], []]
+        });
+        server.mock(|when, then| {
+            when.method(GET)
+                .path(super::path())
+                .query_param("chain", "main");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(block.to_string());
+        });
+        let client = TezosRpc::new(rpc_url);
+        let chain_ids = [TezosRpcChainId::Main];
+
+        let blocks: Vec<_> = client
+            .monitor_validated_blocks()
+            .chain_ids(&chain_ids)
+            .send()
+            .await?
+            .collect()
+            .await;
+
+        assert_eq!(blocks.len(), 1);
+        let block = blocks[0].as_ref().unwrap();
+        assert_eq!(block.chain_id.value(), "NetXdQprcVkpaWU");
+        assert_eq!(block.header.level, 2504461);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,435 @@
+//! Transport of operations to and from an offline (air-gapped) signing device.
+//!
+//! An [OperationRequest] wraps the forged [UnsignedOperation] with the context the offline device needs to
+//! review it: the public key expected to sign it, the chain it's meant for and human-readable metadata.
+//! The device answers with an [OperationResponse] holding the [SignedOperation]. Both containers are versioned
+//! CBOR maps, transferred as [Ur]s, either as a single QR code or as an animated QR code of fountain-coded parts.
+//!
+//! ```rust
+//! use tezos_operation::operations::{Transaction, UnsignedOperation};
+//! use tezos_signer::airgap::{ur::UrDecoder, OperationRequest};
+//!
+//! fn example() -> tezos_signer::Result<()> {
+//!     let operation = UnsignedOperation::new(
+//!         "BLyKu3tnc9NCuiFfCqfeVGPCoZTyW63dYh2XAYxkM7fQYKCqsju".try_into()?,
+//!         vec![Transaction::new(
+//!             "tz1RFxSHjxxyaaXyAvJG3uRQeJUCCm4jk2LX".try_into()?,
+//!             417u32.into(),
+//!             2336132u32.into(),
+//!             1527u32.into(),
+//!             357u32.into(),
+//!             1000u32.into(),
+//!             "tz1PwXjsrgYBi9wpe3tFhazJpt7JMTVzBp5c".try_into()?,
+//!             None,
+//!         )
+//!         .into()],
+//!     );
+//!     let request = OperationRequest::new(
+//!         operation,
+//!         "edpkttZKC51wemRqL2QxwpMnEKxWnbd35pq47Y6xsCHp5M1f7LN8NP".try_into()?,
+//!         "NetXdQprcVkpaWU".try_into()?,
+//!     )
+//!     .with_metadata("dapp", "Example");
+//!
+//!     // on the online side, display the parts as an animated QR code
+//!     let mut encoder = request.encoder(100)?;
+//!     let part = encoder.next_part();
+//!
+//!     // on the offline side, scan the parts until the request is complete
+//!     let mut decoder = UrDecoder::new();
+//!     decoder.receive(&part)?;
+//!     if let Some(ur) = decoder.result() {
+//!         let request = OperationRequest::from_ur(ur)?;
+//!         request.validate()?;
+//!         // sign with `request.sign_using(&signer)` and display `response.encoder(100)?`
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+mod bytewords;
+mod cbor;
+mod fountain;
+pub mod ur;
+
+use std::collections::BTreeMap;
+
+use tezos_core::types::encoded::{ChainId, Encoded, PublicKey};
+use tezos_operation::{
+    operations::{Operation, SignedOperation, UnsignedOperation},
+    signer::Signer,
+};
+
+use self::{
+    cbor::{CborReader, CborWriter},
+    ur::{Ur, UrEncoder},
+};
+use crate::{Error, Result};
+
+/// The version of the [OperationRequest] and [OperationResponse] containers.
+pub const VERSION: u64 = 1;
+
+const KEY_VERSION: u64 = 1;
+const KEY_OPERATION: u64 = 2;
+const KEY_PUBLIC_KEY: u64 = 3;
+const KEY_CHAIN_ID: u64 = 4;
+const KEY_METADATA: u64 = 5;
+
+/// An operation to be signed by an offline device.
+///
+/// Its CBOR encoding is a map of `1: version`, `2: forged operation`, `3: public key bytes`,
+/// `4: chain id bytes` and, if not empty, `5: metadata` (a map of text to text).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperationRequest {
+    pub operation: UnsignedOperation,
+    /// The public key expected to sign the operation.
+    pub public_key: PublicKey,
+    pub chain_id: ChainId,
+    /// Human-readable details displayed by the offline device, e.g. the name of the requesting dApp.
+    pub metadata: BTreeMap<String, String>,
+}
+
+impl OperationRequest {
+    pub const UR_TYPE: &'static str = "tezos-sign-request";
+
+    pub fn new(operation: UnsignedOperation, public_key: PublicKey, chain_id: ChainId) -> Self {
+        Self {
+            operation,
+            public_key,
+            chain_id,
+            metadata: BTreeMap::new(),
+        }
+    }
+
+    pub fn with_metadata(mut self, key: &str, value: &str) -> Self {
+        self.metadata.insert(key.into(), value.into());
+        self
+    }
+
+    /// Checks that all the manager contents of the operation have the requested public key as source.
+    pub fn validate(&self) -> Result<()> {
+        let address = self.public_key.bs58_address()?;
+        let is_valid = self
+            .operation
+            .contents()
+            .iter()
+            .filter_map(|content| content.source())
+            .all(|source| source.value() == address);
+        if !is_valid {
+            return Err(Error::SourceMismatch);
+        }
+
+        Ok(())
+    }
+
+    pub fn to_cbor(&self) -> Result<Vec<u8>> {
+        let mut writer = CborWriter::new();
+        writer
+            .map(if self.metadata.is_empty() { 4 } else { 5 })
+            .unsigned(KEY_VERSION)
+            .unsigned(VERSION)
+            .unsigned(KEY_OPERATION)
+            .bytes(&self.operation.to_forged_bytes()?)
+            .unsigned(KEY_PUBLIC_KEY)
+            .bytes(&self.public_key.to_bytes()?)
+            .unsigned(KEY_CHAIN_ID)
+            .bytes(&self.chain_id.to_bytes()?);
+        if !self.metadata.is_empty() {
+            writer.unsigned(KEY_METADATA).map(self.metadata.len());
+            for (key, value) in &self.metadata {
+                writer.text(key).text(value);
+            }
+        }
+
+        Ok(writer.into_bytes())
+    }
+
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self> {
+        let mut operation = None;
+        let mut public_key = None;
+        let mut chain_id = None;
+        let mut metadata = BTreeMap::new();
+        read_map(bytes, |key, reader| {
+            match key {
+                KEY_OPERATION => {
+                    operation = Some(UnsignedOperation::from_forged_bytes(reader.bytes()?)?)
+                }
+                KEY_PUBLIC_KEY => public_key = Some(PublicKey::from_bytes(reader.bytes()?)?),
+                KEY_CHAIN_ID => chain_id = Some(ChainId::from_bytes(reader.bytes()?)?),
+                KEY_METADATA => {
+                    for _ in 0..reader.map()? {
+                        metadata.insert(reader.text()?.into(), reader.text()?.into());
+                    }
+                }
+                _ => reader.skip()?,
+            }
+            Ok(())
+        })?;
+
+        Ok(Self {
+            operation: operation.ok_or(Error::InvalidCbor)?,
+            public_key: public_key.ok_or(Error::InvalidCbor)?,
+            chain_id: chain_id.ok_or(Error::InvalidCbor)?,
+            metadata,
+        })
+    }
+
+    pub fn to_ur(&self) -> Result<Ur> {
+        Ur::new(Self::UR_TYPE, self.to_cbor()?)
+    }
+
+    pub fn from_ur(ur: &Ur) -> Result<Self> {
+        if ur.ur_type() != Self::UR_TYPE {
+            return Err(Error::InvalidUr);
+        }
+
+        Self::from_cbor(ur.cbor())
+    }
+
+    /// Creates an encoder of the request's QR code parts, of at most `max_fragment_len` bytes each.
+    pub fn encoder(&self, max_fragment_len: usize) -> Result<UrEncoder> {
+        UrEncoder::new(&self.to_ur()?, max_fragment_len)
+    }
+
+    /// Validates the request and signs its operation with the `signer`, which must hold the requested public key.
+    pub async fn sign_using<S>(&self, signer: &S) -> Result<OperationResponse>
+    where
+        S: Signer + ?Sized,
+    {
+        self.validate()?;
+        if signer.public_key().await? != self.public_key {
+            return Err(Error::UnknownKey);
+        }
+        let operation = self
+            .operation
+            .clone()
+            .into_signed_operation_using(signer)
+            .await?;
+
+        Ok(OperationResponse::new(operation))
+    }
+}
+
+/// The answer of an offline device to an [OperationRequest].
+///
+/// Its CBOR encoding is a map of `1: version` and `2: injectable operation bytes`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperationResponse {
+    pub operation: SignedOperation,
+}
+
+impl OperationResponse {
+    pub const UR_TYPE: &'static str = "tezos-signature";
+
+    pub fn new(operation: SignedOperation) -> Self {
+        Self { operation }
+    }
+
+    /// Checks that the response signs the operation of the `request`.
+    pub fn matches(&self, request: &OperationRequest) -> bool {
+        self.operation.branch == request.operation.branch
+            && self.operation.contents == request.operation.contents
+    }
+
+    /// Checks that the response signs the operation of the `request` with the requested public key.
+    pub fn verify(&self, request: &OperationRequest) -> Result<bool> {
+        Ok(self.matches(request) && self.operation.verify(&request.public_key)?)
+    }
+
+    pub fn to_cbor(&self) -> Result<Vec<u8>> {
+        let injectable = [
+            self.operation.to_forged_bytes()?,
+            self.operation.signature.to_bytes()?,
+        ]
+        .concat();
+        let mut writer = CborWriter::new();
+        writer
+            .map(2)
+            .unsigned(KEY_VERSION)
+            .unsigned(VERSION)
+            .unsigned(KEY_OPERATION)
+            .bytes(&injectable);
+
+        Ok(writer.into_bytes())
+    }
+
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self> {
+        let mut operation = None;
+        read_map(bytes, |key, reader| {
+            match key {
+                KEY_OPERATION => {
+                    operation = Some(SignedOperation::from_injectable_bytes(reader.bytes()?)?)
+                }
+                _ => reader.skip()?,
+            }
+            Ok(())
+        })?;
+
+        Ok(Self::new(operation.ok_or(Error::InvalidCbor)?))
+    }
+
+    pub fn to_ur(&self) -> Result<Ur> {
+        Ur::new(Self::UR_TYPE, self.to_cbor()?)
+    }
+
+    pub fn from_ur(ur: &Ur) -> Result<Self> {
+        if ur.ur_type() != Self::UR_TYPE {
+            return Err(Error::InvalidUr);
+        }
+
+        Self::from_cbor(ur.cbor())
+    }
+
+    /// Creates an encoder of the response's QR code parts, of at most `max_fragment_len` bytes each.
+    pub fn encoder(&self, max_fragment_len: usize) -> Result<UrEncoder> {
+        UrEncoder::new(&self.to_ur()?, max_fragment_len)
+    }
+}
+
+/// Reads a versioned container, calling `read_value` for each key other than the version.
+fn read_map<F>(bytes: &[u8], mut read_value: F) -> Result<()>
+where
+    F: FnMut(u64, &mut CborReader) -> Result<()>,
+{
+    let mut reader = CborReader::new(bytes);
+    let mut version = None;
+    for _ in 0..reader.map()? {
+        match reader.unsigned()? {
+            KEY_VERSION => version = Some(reader.unsigned()?),
+            key => read_value(key, &mut reader)?,
+        }
+    }
+    if !reader.is_empty() {
+        return Err(Error::InvalidCbor);
+    }
+
+    match version {
+        Some(VERSION) => Ok(()),
+        Some(_) => Err(Error::UnsupportedVersion),
+        None => Err(Error::InvalidCbor),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tezos_operation::operations::{OperationContent, Transaction};
+
+    use super::*;
+    use crate::airgap::ur::UrDecoder;
+
+    fn transaction(source: &str) -> OperationContent {
+        Transaction::new(
+            source.try_into().unwrap(),
+            417u32.into(),
+            2336132u32.into(),
+            1527u32.into(),
+            357u32.into(),
+            1000u32.into(),
+            "tz1PwXjsrgYBi9wpe3tFhazJpt7JMTVzBp5c".try_into().unwrap(),
+            None,
+        )
+        .into()
+    }
+
+    fn request_from(source: &str) -> OperationRequest {
+        OperationRequest::new(
+            UnsignedOperation::new(
+                "BLyKu3tnc9NCuiFfCqfeVGPCoZTyW63dYh2XAYxkM7fQYKCqsju"
+                    .try_into()
+                    .unwrap(),
+                vec![transaction(source)],
+            ),
+            "edpkttZKC51wemRqL2QxwpMnEKxWnbd35pq47Y6xsCHp5M1f7LN8NP"
+                .try_into()
+                .unwrap(),
+            "NetXdQprcVkpaWU".try_into().unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_request() -> Result<()> {
+        let request = request_from("tz1RFxSHjxxyaaXyAvJG3uRQeJUCCm4jk2LX")
+            .with_metadata("dapp", "Example")
+            .with_metadata("network", "mainnet");
+        request.validate()?;
+
+        let cbor = request.to_cbor()?;
+        assert_eq!(&cbor[..3], &[0xa5, 0x01, 0x01]);
+        assert_eq!(OperationRequest::from_cbor(&cbor)?, request);
+
+        let mut encoder = request.encoder(50)?;
+        assert!(!encoder.is_single_part());
+        let mut decoder = UrDecoder::new();
+        while !decoder.is_complete() {
+            decoder.receive(&encoder.next_part())?;
+        }
+        let decoded = OperationRequest::from_ur(decoder.result().unwrap())?;
+        assert_eq!(decoded, request);
+
+        assert!(matches!(
+            request_from("tz1PwXjsrgYBi9wpe3tFhazJpt7JMTVzBp5c").validate(),
+            Err(Error::SourceMismatch)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_container_errors() -> Result<()> {
+        let request = request_from("tz1RFxSHjxxyaaXyAvJG3uRQeJUCCm4jk2LX");
+        let mut cbor = request.to_cbor()?;
+        cbor[2] = 0x02;
+        assert!(matches!(
+            OperationRequest::from_cbor(&cbor),
+            Err(Error::UnsupportedVersion)
+        ));
+        assert!(matches!(
+            OperationRequest::from_cbor(&[0xa1, 0x01, 0x01]),
+            Err(Error::InvalidCbor)
+        ));
+        assert!(matches!(
+            OperationResponse::from_ur(&request.to_ur()?),
+            Err(Error::InvalidUr)
+        ));
+
+        // unknown keys are ignored
+        let mut writer = CborWriter::new();
+        writer.map(5).unsigned(9).text("future");
+        let cbor = [writer.into_bytes(), request.to_cbor()?[1..].to_vec()].concat();
+        assert_eq!(OperationRequest::from_cbor(&cbor)?, request);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "ed25519")]
+    #[tokio::test]
+    async fn test_sign_using() -> Result<()> {
+        use tezos_operation::signer::InMemorySigner;
+
+        let signer = InMemorySigner::new(
+            "edskRv7VyXGVZb8EsrR7D9XKUbbAQNQGtALP6QeB16ZCD7SmmJpzyeneJVg3Mq56YLbxRA1kSdAXiswwPiaVfR3NHGMCXCziuZ".try_into()?,
+            "edpkttZKC51wemRqL2QxwpMnEKxWnbd35pq47Y6xsCHp5M1f7LN8NP".try_into()?,
+        );
+        let request = request_from("tz1RFxSHjxxyaaXyAvJG3uRQeJUCCm4jk2LX");
+        let response = request.sign_using(&signer).await?;
+        assert!(response.verify(&request)?);
+
+        let decoded = OperationResponse::from_ur(&response.to_ur()?.to_string().parse()?)?;
+        assert_eq!(decoded, response);
+        assert!(decoded.verify(&request)?);
+
+        let unknown: PublicKey =
+            "edpkuBknW28nW72KG6RoHtYW7p12T6GKc7nAbwYX5m8Wd9sDVC9yav".try_into()?;
+        let other = OperationRequest {
+            public_key: unknown.clone(),
+            ..request_from(&unknown.bs58_address()?)
+        };
+        assert!(!response.matches(&other));
+        assert!(matches!(
+            other.sign_using(&signer).await,
+            Err(Error::UnknownKey)
+        ));
+
+        Ok(())
+    }
+}
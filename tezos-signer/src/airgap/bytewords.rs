@@ -0,0 +1,100 @@
+//! The minimal Bytewords encoding used by URs: each byte is encoded as the first and last letters
+//! of its word, and the data is followed by its CRC32 checksum.
+
+use crate::{Error, Result};
+
+use super::fountain::crc32;
+
+const WORDS: [&str; 256] = [
+    "able", "acid", "also", "apex", "aqua", "arch", "atom", "aunt", "away", "axis", "back", "bald",
+    "barn", "belt", "beta", "bias", "blue", "body", "brag", "brew", "bulb", "buzz", "calm", "cash",
+    "cats", "chef", "city", "claw", "code", "cola", "cook", "cost", "crux", "curl", "cusp", "cyan",
+    "dark", "data", "days", "deli", "dice", "diet", "door", "down", "draw", "drop", "drum", "dull",
+    "duty", "each", "easy", "echo", "edge", "epic", "even", "exam", "exit", "eyes", "fact", "fair",
+    "fern", "figs", "film", "fish", "fizz", "flap", "flew", "flux", "foxy", "free", "frog", "fuel",
+    "fund", "gala", "game", "gear", "gems", "gift", "girl", "glow", "good", "gray", "grim", "guru",
+    "gush", "gyro", "half", "hang", "hard", "hawk", "heat", "help", "high", "hill", "holy", "hope",
+    "horn", "huts", "iced", "idea", "idle", "inch", "inky", "into", "iris", "iron", "item", "jade",
+    "jazz", "join", "jolt", "jowl", "judo", "jugs", "jump", "junk", "jury", "keep", "keno", "kept",
+    "keys", "kick", "kiln", "king", "kite", "kiwi", "knob", "lamb", "lava", "lazy", "leaf", "legs",
+    "liar", "limp", "lion", "list", "logo", "loud", "love", "luau", "luck", "lung", "main", "many",
+    "math", "maze", "memo", "menu", "meow", "mild", "mint", "miss", "monk", "nail", "navy", "need",
+    "news", "next", "noon", "note", "numb", "obey", "oboe", "omit", "onyx", "open", "oval", "owls",
+    "paid", "part", "peck", "play", "plus", "poem", "pool", "pose", "puff", "puma", "purr", "quad",
+    "quiz", "race", "ramp", "real", "redo", "rich", "road", "rock", "roof", "ruby", "ruin", "runs",
+    "rust", "safe", "saga", "scar", "sets", "silk", "skew", "slot", "soap", "solo", "song", "stub",
+    "surf", "swan", "taco", "task", "taxi", "tent", "tied", "time", "tiny", "toil", "tomb", "toys",
+    "trip", "tuna", "twin", "ugly", "undo", "unit", "urge", "user", "vast", "very", "veto", "vial",
+    "vibe", "view", "visa", "void", "vows", "wall", "wand", "warm", "wasp", "wave", "waxy", "webs",
+    "what", "when", "whiz", "wolf", "work", "yank", "yawn", "yell", "yoga", "yurt", "zaps", "zero",
+    "zest", "zinc", "zone", "zoom",
+];
+
+/// Encodes the `data` with its checksum as minimal Bytewords.
+pub fn encode(data: &[u8]) -> String {
+    let checksum = crc32(data).to_be_bytes();
+    data.iter()
+        .chain(checksum.iter())
+        .flat_map(|byte| {
+            let word = WORDS[*byte as usize].as_bytes();
+            [word[0] as char, word[3] as char]
+        })
+        .collect()
+}
+
+/// Decodes minimal Bytewords, as created by [encode], and checks their checksum.
+pub fn decode(value: &str) -> Result<Vec<u8>> {
+    let value = value.to_ascii_lowercase();
+    if !value.is_ascii() || value.len() % 2 == 1 {
+        return Err(Error::InvalidUr);
+    }
+    let bytes = value
+        .as_bytes()
+        .chunks(2)
+        .map(|letters| {
+            WORDS
+                .iter()
+                .position(|word| {
+                    let word = word.as_bytes();
+                    word[0] == letters[0] && word[3] == letters[1]
+                })
+                .map(|index| index as u8)
+                .ok_or(Error::InvalidUr)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if bytes.len() < 4 {
+        return Err(Error::InvalidUr);
+    }
+    let (data, checksum) = bytes.split_at(bytes.len() - 4);
+    if crc32(data).to_be_bytes() != checksum {
+        return Err(Error::InvalidUr);
+    }
+
+    Ok(data.to_vec())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_encode_decode() -> Result<()> {
+        let data = [0x00, 0x01, 0x02, 0x80, 0xff];
+        // able acid also lava zoom + jade need echo taxi
+        assert_eq!(encode(&data), "aeadaolazmjendeoti");
+        assert_eq!(decode("aeadaolazmjendeoti")?, data);
+        assert_eq!(decode("AEADAOLAZMJENDEOTI")?, data);
+
+        let invalid = [
+            "aeadaolazmjendeotx",
+            "aeadaolazmjendeot",
+            "aeadaolazmjendeotj",
+            "aeadao",
+        ];
+        for value in invalid {
+            assert!(matches!(decode(value), Err(Error::InvalidUr)));
+        }
+
+        Ok(())
+    }
+}
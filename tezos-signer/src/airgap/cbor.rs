@@ -0,0 +1,194 @@
+use crate::{Error, Result};
+
+const UNSIGNED: u8 = 0;
+const BYTES: u8 = 2;
+const TEXT: u8 = 3;
+const ARRAY: u8 = 4;
+const MAP: u8 = 5;
+
+/// A minimal CBOR writer, supporting the items used by the air-gapped containers and the UR parts.
+#[derive(Debug, Default)]
+pub(crate) struct CborWriter {
+    bytes: Vec<u8>,
+}
+
+impl CborWriter {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    pub fn unsigned(&mut self, value: u64) -> &mut Self {
+        self.head(UNSIGNED, value)
+    }
+
+    pub fn bytes(&mut self, value: &[u8]) -> &mut Self {
+        self.head(BYTES, value.len() as u64);
+        self.bytes.extend_from_slice(value);
+        self
+    }
+
+    pub fn text(&mut self, value: &str) -> &mut Self {
+        self.head(TEXT, value.len() as u64);
+        self.bytes.extend_from_slice(value.as_bytes());
+        self
+    }
+
+    pub fn array(&mut self, len: usize) -> &mut Self {
+        self.head(ARRAY, len as u64)
+    }
+
+    pub fn map(&mut self, len: usize) -> &mut Self {
+        self.head(MAP, len as u64)
+    }
+
+    fn head(&mut self, major: u8, value: u64) -> &mut Self {
+        let major = major << 5;
+        match value {
+            0..=23 => self.bytes.push(major | value as u8),
+            24..=0xff => self.bytes.extend_from_slice(&[major | 24, value as u8]),
+            0x100..=0xffff => {
+                self.bytes.push(major | 25);
+                self.bytes.extend_from_slice(&(value as u16).to_be_bytes());
+            }
+            0x10000..=0xffff_ffff => {
+                self.bytes.push(major | 26);
+                self.bytes.extend_from_slice(&(value as u32).to_be_bytes());
+            }
+            _ => {
+                self.bytes.push(major | 27);
+                self.bytes.extend_from_slice(&value.to_be_bytes());
+            }
+        }
+        self
+    }
+}
+
+/// A minimal CBOR reader, the counterpart of [CborWriter].
+#[derive(Debug)]
+pub(crate) struct CborReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> CborReader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    pub fn unsigned(&mut self) -> Result<u64> {
+        self.head(UNSIGNED)
+    }
+
+    pub fn bytes(&mut self) -> Result<&'a [u8]> {
+        let len = self.head(BYTES)?;
+        self.take(len)
+    }
+
+    pub fn text(&mut self) -> Result<&'a str> {
+        let len = self.head(TEXT)?;
+        std::str::from_utf8(self.take(len)?).map_err(|_| Error::InvalidCbor)
+    }
+
+    pub fn array(&mut self) -> Result<usize> {
+        Ok(self.head(ARRAY)? as usize)
+    }
+
+    pub fn map(&mut self) -> Result<usize> {
+        Ok(self.head(MAP)? as usize)
+    }
+
+    /// Skips the next item, e.g. the value of an unknown map key.
+    pub fn skip(&mut self) -> Result<()> {
+        let major = self.bytes.first().ok_or(Error::InvalidCbor)? >> 5;
+        match major {
+            UNSIGNED => self.unsigned().map(|_| ()),
+            BYTES => self.bytes().map(|_| ()),
+            TEXT => self.text().map(|_| ()),
+            ARRAY => (0..self.array()?).try_for_each(|_| self.skip()),
+            MAP => (0..self.map()? * 2).try_for_each(|_| self.skip()),
+            _ => Err(Error::InvalidCbor),
+        }
+    }
+
+    fn head(&mut self, major: u8) -> Result<u64> {
+        let (first, rest) = self.bytes.split_first().ok_or(Error::InvalidCbor)?;
+        if first >> 5 != major {
+            return Err(Error::InvalidCbor);
+        }
+        self.bytes = rest;
+        let size = match first & 0x1f {
+            value @ 0..=23 => return Ok(value as u64),
+            24 => 1,
+            25 => 2,
+            26 => 4,
+            27 => 8,
+            _ => return Err(Error::InvalidCbor),
+        };
+
+        Ok(self
+            .take(size)?
+            .iter()
+            .fold(0u64, |value, byte| (value << 8) | *byte as u64))
+    }
+
+    fn take(&mut self, len: u64) -> Result<&'a [u8]> {
+        let len = usize::try_from(len).map_err(|_| Error::InvalidCbor)?;
+        if len > self.bytes.len() {
+            return Err(Error::InvalidCbor);
+        }
+        let (value, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_write_read() -> Result<()> {
+        let mut writer = CborWriter::new();
+        writer
+            .array(2)
+            .unsigned(500)
+            .map(1)
+            .text("a")
+            .bytes(&[0x01, 0x02]);
+        let bytes = writer.into_bytes();
+        assert_eq!(
+            bytes,
+            vec![0x82, 0x19, 0x01, 0xf4, 0xa1, 0x61, 0x61, 0x42, 0x01, 0x02]
+        );
+
+        let mut reader = CborReader::new(&bytes);
+        assert_eq!(reader.array()?, 2);
+        assert_eq!(reader.unsigned()?, 500);
+        assert_eq!(reader.map()?, 1);
+        assert_eq!(reader.text()?, "a");
+        assert_eq!(reader.bytes()?, &[0x01, 0x02]);
+        assert!(reader.is_empty());
+
+        let mut reader = CborReader::new(&bytes);
+        reader.skip()?;
+        assert!(reader.is_empty());
+        assert!(matches!(
+            CborReader::new(&bytes).unsigned(),
+            Err(Error::InvalidCbor)
+        ));
+        assert!(matches!(
+            CborReader::new(&[0x42, 0x01]).bytes(),
+            Err(Error::InvalidCbor)
+        ));
+
+        Ok(())
+    }
+}
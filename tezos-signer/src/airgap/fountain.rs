@@ -0,0 +1,546 @@
+//! The fountain code used by multi-part URs: once the message's fragments have been sent once, the
+//! encoder keeps emitting parts mixing pseudo-randomly chosen fragments, so that a receiver scanning an
+//! animated QR code can recover the message from any sufficient subset of the parts.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use sha2::{Digest, Sha256};
+
+use super::cbor::{CborReader, CborWriter};
+use crate::{Error, Result};
+
+const MIN_FRAGMENT_LEN: usize = 10;
+/// The maximum length of a received message, the requests carry a forged operation of at most 32 KiB.
+const MAX_MESSAGE_LEN: usize = 64 * 1024;
+/// The maximum number of mixed parts kept by a [FountainDecoder], per fragment of the message.
+const MAX_MIXED_PER_FRAGMENT: usize = 4;
+
+/// Splits a message into fragments of at most `max_fragment_len` bytes and emits an endless sequence of parts.
+#[derive(Debug, Clone)]
+pub struct FountainEncoder {
+    message_len: usize,
+    checksum: u32,
+    fragments: Vec<Vec<u8>>,
+    seq: u32,
+}
+
+impl FountainEncoder {
+    pub fn new(message: &[u8], max_fragment_len: usize) -> Result<Self> {
+        if message.is_empty() || max_fragment_len < MIN_FRAGMENT_LEN {
+            return Err(Error::InvalidUr);
+        }
+        let fragment_len = fragment_len(message.len(), MIN_FRAGMENT_LEN, max_fragment_len);
+        let mut padded = message.to_vec();
+        padded.resize(
+            (message.len() + fragment_len - 1) / fragment_len * fragment_len,
+            0,
+        );
+
+        Ok(Self {
+            message_len: message.len(),
+            checksum: crc32(message),
+            fragments: padded.chunks(fragment_len).map(|c| c.to_vec()).collect(),
+            seq: 0,
+        })
+    }
+
+    /// The number of fragments the message has been split into.
+    pub fn seq_len(&self) -> usize {
+        self.fragments.len()
+    }
+
+    pub fn next_part(&mut self) -> FountainPart {
+        self.seq = self.seq.wrapping_add(1).max(1);
+        let indexes = choose_fragments(self.seq, self.seq_len(), self.checksum);
+        let data = indexes
+            .iter()
+            .fold(vec![0u8; self.fragments[0].len()], |mut data, index| {
+                xor_into(&mut data, &self.fragments[*index]);
+                data
+            });
+
+        FountainPart {
+            seq: self.seq,
+            seq_len: self.seq_len(),
+            message_len: self.message_len,
+            checksum: self.checksum,
+            data,
+        }
+    }
+}
+
+/// A part emitted by a [FountainEncoder]: a single fragment if `seq <= seq_len`, or a mix of fragments otherwise.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FountainPart {
+    pub seq: u32,
+    pub seq_len: usize,
+    pub message_len: usize,
+    pub checksum: u32,
+    pub data: Vec<u8>,
+}
+
+impl FountainPart {
+    pub fn to_cbor(&self) -> Vec<u8> {
+        let mut writer = CborWriter::new();
+        writer
+            .array(5)
+            .unsigned(self.seq as u64)
+            .unsigned(self.seq_len as u64)
+            .unsigned(self.message_len as u64)
+            .unsigned(self.checksum as u64)
+            .bytes(&self.data);
+
+        writer.into_bytes()
+    }
+
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self> {
+        let mut reader = CborReader::new(bytes);
+        if reader.array()? != 5 {
+            return Err(Error::InvalidCbor);
+        }
+        let seq = u32::try_from(reader.unsigned()?).map_err(|_| Error::InvalidCbor)?;
+        let seq_len = reader.unsigned()? as usize;
+        let message_len = reader.unsigned()? as usize;
+        let checksum = u32::try_from(reader.unsigned()?).map_err(|_| Error::InvalidCbor)?;
+        let data = reader.bytes()?.to_vec();
+        if !reader.is_empty() || seq == 0 || seq_len == 0 || data.is_empty() {
+            return Err(Error::InvalidCbor);
+        }
+        // The fragments chosen by a part are allocated from `seq_len`, bounded by the message length.
+        if message_len == 0
+            || message_len > MAX_MESSAGE_LEN
+            || seq_len > (message_len / MIN_FRAGMENT_LEN).max(1)
+        {
+            return Err(Error::InvalidUr);
+        }
+
+        Ok(Self {
+            seq,
+            seq_len,
+            message_len,
+            checksum,
+            data,
+        })
+    }
+
+    fn indexes(&self) -> BTreeSet<usize> {
+        choose_fragments(self.seq, self.seq_len, self.checksum)
+            .into_iter()
+            .collect()
+    }
+}
+
+/// Collects [FountainPart]s, in any order, until the message can be recovered.
+#[derive(Debug, Default, Clone)]
+pub struct FountainDecoder {
+    expected: Option<(usize, usize, u32, usize)>,
+    received: BTreeSet<u32>,
+    simple: BTreeMap<usize, Vec<u8>>,
+    mixed: Vec<(BTreeSet<usize>, Vec<u8>)>,
+    message: Option<Vec<u8>>,
+}
+
+impl FountainDecoder {
+    /// Receives a part and returns `true` if it brought new information.
+    pub fn receive(&mut self, part: FountainPart) -> Result<bool> {
+        let expected = (
+            part.seq_len,
+            part.message_len,
+            part.checksum,
+            part.data.len(),
+        );
+        match self.expected {
+            Some(current) if current != expected => return Err(Error::InvalidUr),
+            Some(_) => {}
+            None => {
+                // Checked again here for the parts which weren't decoded with [FountainPart::from_cbor].
+                let data_len = part.data.len();
+                if data_len == 0
+                    || part.message_len > MAX_MESSAGE_LEN
+                    || part.seq_len != (part.message_len + data_len - 1) / data_len
+                {
+                    return Err(Error::InvalidUr);
+                }
+                self.expected = Some(expected)
+            }
+        }
+        if self.is_complete() || !self.received.insert(part.seq) {
+            return Ok(false);
+        }
+
+        let mut queue = vec![(part.indexes(), part.data)];
+        while let Some((indexes, data)) = queue.pop() {
+            let (indexes, data) = self.reduce_by_simple(indexes, data);
+            match indexes.len() {
+                0 => {}
+                1 => {
+                    let index = *indexes.iter().next().unwrap();
+                    let mixed = std::mem::take(&mut self.mixed);
+                    for (mixed_indexes, mut mixed_data) in mixed {
+                        if mixed_indexes.contains(&index) {
+                            xor_into(&mut mixed_data, &data);
+                            let mut mixed_indexes = mixed_indexes;
+                            mixed_indexes.remove(&index);
+                            queue.push((mixed_indexes, mixed_data));
+                        } else {
+                            self.mixed.push((mixed_indexes, mixed_data));
+                        }
+                    }
+                    self.simple.insert(index, data);
+                }
+                _ => {
+                    if !self.mixed.iter().any(|(mixed, _)| *mixed == indexes) {
+                        if self.mixed.len() >= expected.0 * MAX_MIXED_PER_FRAGMENT {
+                            self.mixed.remove(0);
+                        }
+                        self.mixed.push((indexes, data));
+                    }
+                }
+            }
+        }
+
+        if self.simple.len() == expected.0 {
+            let mut message = self.simple.values().flatten().copied().collect::<Vec<_>>();
+            message.truncate(expected.1);
+            if crc32(&message) != expected.2 {
+                return Err(Error::InvalidUr);
+            }
+            self.message = Some(message);
+        }
+
+        Ok(true)
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.message.is_some()
+    }
+
+    /// The ratio of the fragments already recovered, between `0.0` and `1.0`.
+    pub fn progress(&self) -> f64 {
+        match self.expected {
+            Some((seq_len, ..)) => self.simple.len() as f64 / seq_len as f64,
+            None => 0.0,
+        }
+    }
+
+    pub fn message(&self) -> Option<&[u8]> {
+        self.message.as_deref()
+    }
+
+    fn reduce_by_simple(
+        &self,
+        indexes: BTreeSet<usize>,
+        mut data: Vec<u8>,
+    ) -> (BTreeSet<usize>, Vec<u8>) {
+        let indexes = indexes
+            .into_iter()
+            .filter(|index| match self.simple.get(index) {
+                Some(fragment) => {
+                    xor_into(&mut data, fragment);
+                    false
+                }
+                None => true,
+            })
+            .collect();
+
+        (indexes, data)
+    }
+}
+
+/// Computes the CRC-32 (ISO-HDLC) checksum of `data`.
+pub(crate) fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(u32::MAX, |crc, byte| {
+        (0..8).fold(crc ^ *byte as u32, |crc, _| {
+            (crc >> 1) ^ (0xEDB8_8320 & (crc & 1).wrapping_neg())
+        })
+    })
+}
+
+fn fragment_len(message_len: usize, min_fragment_len: usize, max_fragment_len: usize) -> usize {
+    let max_count = (message_len / min_fragment_len).max(1);
+    (1..=max_count)
+        .map(|count| (message_len + count - 1) / count)
+        .find(|len| *len <= max_fragment_len)
+        .unwrap_or_else(|| (message_len + max_count - 1) / max_count)
+}
+
+fn choose_fragments(seq: u32, seq_len: usize, checksum: u32) -> Vec<usize> {
+    if seq as usize <= seq_len {
+        return vec![seq as usize - 1];
+    }
+
+    let seed = [seq.to_be_bytes(), checksum.to_be_bytes()].concat();
+    let mut rng = Xoshiro256::new(&seed);
+    let degree = AliasSampler::new(
+        &(1..=seq_len)
+            .map(|index| 1.0 / index as f64)
+            .collect::<Vec<_>>(),
+    )
+    .next(&mut rng)
+        + 1;
+
+    let mut remaining = (0..seq_len).collect::<Vec<_>>();
+    let mut shuffled = Vec::with_capacity(seq_len);
+    while !remaining.is_empty() {
+        let index = rng.next_int(0, remaining.len() as u64 - 1) as usize;
+        shuffled.push(remaining.remove(index));
+    }
+    shuffled.truncate(degree);
+    shuffled.sort_unstable();
+
+    shuffled
+}
+
+/// Creates a pseudo-random message, as used by the reference test vectors.
+#[cfg(test)]
+pub(crate) fn message(len: usize, seed: &str) -> Vec<u8> {
+    let mut rng = Xoshiro256::new(seed.as_bytes());
+    (0..len).map(|_| rng.next_int(0, 255) as u8).collect()
+}
+
+fn xor_into(target: &mut [u8], source: &[u8]) {
+    target
+        .iter_mut()
+        .zip(source)
+        .for_each(|(target, source)| *target ^= source);
+}
+
+/// The xoshiro256** generator, seeded with the SHA-256 digest of a seed.
+#[derive(Debug, Clone)]
+struct Xoshiro256 {
+    state: [u64; 4],
+}
+
+impl Xoshiro256 {
+    fn new(seed: &[u8]) -> Self {
+        let digest = Sha256::digest(seed);
+        let mut state = [0u64; 4];
+        for (word, chunk) in state.iter_mut().zip(digest.chunks(8)) {
+            *word = u64::from_be_bytes(chunk.try_into().unwrap());
+        }
+
+        Self { state }
+    }
+
+    fn next(&mut self) -> u64 {
+        let state = &mut self.state;
+        let result = state[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = state[1] << 17;
+        state[2] ^= state[0];
+        state[3] ^= state[1];
+        state[1] ^= state[2];
+        state[0] ^= state[3];
+        state[2] ^= t;
+        state[3] = state[3].rotate_left(45);
+
+        result
+    }
+
+    fn next_double(&mut self) -> f64 {
+        self.next() as f64 / (u64::MAX as f64 + 1.0)
+    }
+
+    fn next_int(&mut self, low: u64, high: u64) -> u64 {
+        (self.next_double() * (high - low + 1) as f64) as u64 + low
+    }
+}
+
+/// Walker's alias method, as implemented by Vose, for sampling indexes with the given probabilities.
+#[derive(Debug)]
+struct AliasSampler {
+    probabilities: Vec<f64>,
+    aliases: Vec<usize>,
+}
+
+impl AliasSampler {
+    fn new(probabilities: &[f64]) -> Self {
+        let len = probabilities.len();
+        let total: f64 = probabilities.iter().sum();
+        let mut scaled = probabilities
+            .iter()
+            .map(|probability| probability * len as f64 / total)
+            .collect::<Vec<_>>();
+        let (mut small, mut large): (Vec<usize>, Vec<usize>) =
+            (0..len).rev().partition(|index| scaled[*index] < 1.0);
+
+        let mut sampler = Self {
+            probabilities: vec![0.0; len],
+            aliases: vec![0; len],
+        };
+        while let (Some(&less), Some(&more)) = (small.last(), large.last()) {
+            small.pop();
+            large.pop();
+            sampler.probabilities[less] = scaled[less];
+            sampler.aliases[less] = more;
+            scaled[more] += scaled[less] - 1.0;
+            if scaled[more] < 1.0 {
+                small.push(more);
+            } else {
+                large.push(more);
+            }
+        }
+        for index in small.into_iter().chain(large) {
+            sampler.probabilities[index] = 1.0;
+        }
+
+        sampler
+    }
+
+    fn next(&self, rng: &mut Xoshiro256) -> usize {
+        let r1 = rng.next_double();
+        let r2 = rng.next_double();
+        let index = (self.probabilities.len() as f64 * r1) as usize;
+        if r2 < self.probabilities[index] {
+            index
+        } else {
+            self.aliases[index]
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b"Hello, world!"), 0xebe6c6e6);
+        assert_eq!(crc32(b"Wolf"), 0x598c84dc);
+    }
+
+    #[test]
+    fn test_xoshiro() {
+        let mut rng = Xoshiro256::new(b"Wolf");
+        let values = (0..10).map(|_| rng.next() % 100).collect::<Vec<_>>();
+        assert_eq!(values, vec![42, 81, 85, 8, 82, 84, 76, 73, 70, 88]);
+    }
+
+    #[test]
+    fn test_choose_fragments() {
+        let message = message(1024, "Wolf");
+        let checksum = crc32(&message);
+        let fragments = (1..=30)
+            .map(|seq| choose_fragments(seq, 11, checksum))
+            .collect::<Vec<_>>();
+        let expected: Vec<Vec<usize>> = vec![
+            vec![0],
+            vec![1],
+            vec![2],
+            vec![3],
+            vec![4],
+            vec![5],
+            vec![6],
+            vec![7],
+            vec![8],
+            vec![9],
+            vec![10],
+            vec![9],
+            vec![2, 5, 6, 8, 9, 10],
+            vec![8],
+            vec![1, 5],
+            vec![1],
+            vec![0, 2, 4, 5, 8, 10],
+            vec![5],
+            vec![2],
+            vec![2],
+            vec![0, 1, 3, 4, 5, 7, 9, 10],
+            vec![0, 1, 2, 3, 5, 6, 8, 9, 10],
+            vec![0, 2, 4, 5, 7, 8, 9, 10],
+            vec![3, 5],
+            vec![4],
+            vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10],
+            vec![0, 1, 3, 4, 5, 6, 7, 9, 10],
+            vec![6],
+            vec![5, 6],
+            vec![7],
+        ];
+        assert_eq!(fragments, expected);
+    }
+
+    #[test]
+    fn test_fragment_len() {
+        assert_eq!(fragment_len(12345, 1005, 1955), 1764);
+        assert_eq!(fragment_len(12345, 1005, 30000), 12345);
+        assert_eq!(fragment_len(259, 10, 30), 29);
+    }
+
+    #[test]
+    fn test_encode_decode() -> Result<()> {
+        let message = message(1024, "Wolf");
+        let mut encoder = FountainEncoder::new(&message, 100)?;
+        assert_eq!(encoder.seq_len(), 11);
+
+        let mut decoder = FountainDecoder::default();
+        let mut seq = 0;
+        while !decoder.is_complete() {
+            let part = FountainPart::from_cbor(&encoder.next_part().to_cbor())?;
+            seq += 1;
+            // Simulates a lossy channel dropping every other simple part.
+            if seq <= 11 && seq % 2 == 0 {
+                continue;
+            }
+            decoder.receive(part)?;
+            assert!(seq < 100);
+        }
+        assert!(seq > 11);
+        assert_eq!(decoder.progress(), 1.0);
+        assert_eq!(decoder.message(), Some(message.as_slice()));
+
+        let mut other = FountainEncoder::new(&message[1..], 100)?;
+        assert!(matches!(
+            decoder.receive(other.next_part()),
+            Err(Error::InvalidUr)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_oversized_part() {
+        let part = FountainPart {
+            seq: u32::MAX,
+            seq_len: 4,
+            message_len: 1 << 32,
+            checksum: 0,
+            data: vec![0],
+        };
+
+        assert!(matches!(
+            FountainPart::from_cbor(&part.to_cbor()),
+            Err(Error::InvalidUr)
+        ));
+    }
+
+    #[test]
+    fn test_empty_part() {
+        let part = FountainPart {
+            seq: 1,
+            seq_len: 1,
+            message_len: 10,
+            checksum: 0,
+            data: vec![],
+        };
+
+        assert!(matches!(
+            FountainDecoder::default().receive(part),
+            Err(Error::InvalidUr)
+        ));
+    }
+
+    #[test]
+    fn test_mixed_parts_cap() -> Result<()> {
+        let message = message(1024, "Wolf");
+        let mut encoder = FountainEncoder::new(&message, 100)?;
+        let mut decoder = FountainDecoder::default();
+        for seq in 1..=1000 {
+            let part = encoder.next_part();
+            // Only the mixed parts of more than one fragment, which can't be reduced on their own.
+            if seq > 11 && part.indexes().len() > 1 {
+                decoder.receive(part)?;
+                assert!(decoder.mixed.len() <= 11 * MAX_MIXED_PER_FRAGMENT);
+            }
+        }
+
+        Ok(())
+    }
+}
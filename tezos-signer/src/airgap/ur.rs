@@ -0,0 +1,320 @@
+//! Uniform Resources (URs): typed CBOR payloads encoded as case-insensitive text, suitable for QR codes.
+//! Payloads too large for a single QR code are split into fountain-coded parts, meant to be displayed
+//! as an animated QR code.
+
+use std::{fmt, str::FromStr};
+
+use super::{
+    bytewords,
+    fountain::{FountainDecoder, FountainEncoder, FountainPart},
+};
+use crate::{Error, Result};
+
+const SCHEME: &str = "ur:";
+
+/// A typed CBOR payload, displayed as a single-part `ur:<type>/<bytewords>` string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ur {
+    ur_type: String,
+    cbor: Vec<u8>,
+}
+
+impl Ur {
+    pub fn new(ur_type: &str, cbor: Vec<u8>) -> Result<Self> {
+        let is_valid = !ur_type.is_empty()
+            && ur_type
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+        if !is_valid {
+            return Err(Error::InvalidUr);
+        }
+
+        Ok(Self {
+            ur_type: ur_type.into(),
+            cbor,
+        })
+    }
+
+    pub fn ur_type(&self) -> &str {
+        &self.ur_type
+    }
+
+    pub fn cbor(&self) -> &[u8] {
+        &self.cbor
+    }
+}
+
+impl fmt::Display for Ur {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}{}/{}",
+            SCHEME,
+            self.ur_type,
+            bytewords::encode(&self.cbor)
+        )
+    }
+}
+
+impl FromStr for Ur {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match Component::parse(value)? {
+            Component::Single { ur_type, body } => Self::new(&ur_type, bytewords::decode(&body)?),
+            Component::Part { .. } => Err(Error::InvalidUr),
+        }
+    }
+}
+
+impl TryFrom<&str> for Ur {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self> {
+        value.parse()
+    }
+}
+
+/// Encodes a [Ur] as a sequence of `ur:<type>/<seq>-<seq_len>/<bytewords>` parts of at most
+/// `max_fragment_len` payload bytes each. A [Ur] fitting into a single fragment is always encoded as a single part.
+#[derive(Debug, Clone)]
+pub struct UrEncoder {
+    ur: Ur,
+    fountain: FountainEncoder,
+}
+
+impl UrEncoder {
+    pub fn new(ur: &Ur, max_fragment_len: usize) -> Result<Self> {
+        Ok(Self {
+            ur: ur.clone(),
+            fountain: FountainEncoder::new(&ur.cbor, max_fragment_len)?,
+        })
+    }
+
+    pub fn is_single_part(&self) -> bool {
+        self.fountain.seq_len() == 1
+    }
+
+    /// The number of parts needed at the very least to decode the [Ur].
+    pub fn seq_len(&self) -> usize {
+        self.fountain.seq_len()
+    }
+
+    /// Returns the next part to display. Parts keep being generated past [seq_len](Self::seq_len),
+    /// so that a decoder which missed some of them can still complete.
+    pub fn next_part(&mut self) -> String {
+        if self.is_single_part() {
+            return self.ur.to_string();
+        }
+        let part = self.fountain.next_part();
+
+        format!(
+            "{}{}/{}-{}/{}",
+            SCHEME,
+            self.ur.ur_type,
+            part.seq,
+            part.seq_len,
+            bytewords::encode(&part.to_cbor())
+        )
+    }
+}
+
+/// Decodes a [Ur] from its parts, received in any order and possibly with duplicates.
+#[derive(Debug, Default, Clone)]
+pub struct UrDecoder {
+    ur_type: Option<String>,
+    fountain: FountainDecoder,
+    result: Option<Ur>,
+}
+
+impl UrDecoder {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Receives a part, e.g. the content of a scanned QR code, and returns `true` if it brought new information.
+    pub fn receive(&mut self, part: &str) -> Result<bool> {
+        if self.is_complete() {
+            return Ok(false);
+        }
+
+        let (ur_type, cbor) = match Component::parse(part)? {
+            Component::Single { ur_type, body } => (ur_type, bytewords::decode(&body)?),
+            Component::Part {
+                ur_type,
+                seq,
+                seq_len,
+                body,
+            } => {
+                self.check_type(&ur_type)?;
+                let part = FountainPart::from_cbor(&bytewords::decode(&body)?)?;
+                if part.seq != seq || part.seq_len != seq_len {
+                    return Err(Error::InvalidUr);
+                }
+                let is_new = self.fountain.receive(part)?;
+                if let Some(message) = self.fountain.message() {
+                    self.result = Some(Ur::new(&ur_type, message.to_vec())?);
+                }
+
+                return Ok(is_new);
+            }
+        };
+        self.check_type(&ur_type)?;
+        self.result = Some(Ur::new(&ur_type, cbor)?);
+
+        Ok(true)
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.result.is_some()
+    }
+
+    /// The estimated ratio of the [Ur] already received, between `0.0` and `1.0`.
+    pub fn progress(&self) -> f64 {
+        if self.is_complete() {
+            1.0
+        } else {
+            self.fountain.progress()
+        }
+    }
+
+    pub fn result(&self) -> Option<&Ur> {
+        self.result.as_ref()
+    }
+
+    fn check_type(&mut self, ur_type: &str) -> Result<()> {
+        match &self.ur_type {
+            Some(expected) if expected != ur_type => Err(Error::InvalidUr),
+            Some(_) => Ok(()),
+            None => {
+                self.ur_type = Some(ur_type.into());
+                Ok(())
+            }
+        }
+    }
+}
+
+enum Component {
+    Single {
+        ur_type: String,
+        body: String,
+    },
+    Part {
+        ur_type: String,
+        seq: u32,
+        seq_len: usize,
+        body: String,
+    },
+}
+
+impl Component {
+    fn parse(value: &str) -> Result<Self> {
+        let value = value.trim().to_ascii_lowercase();
+        let value = value.strip_prefix(SCHEME).ok_or(Error::InvalidUr)?;
+        let components = value.split('/').collect::<Vec<_>>();
+        match components.as_slice() {
+            [ur_type, body] => Ok(Self::Single {
+                ur_type: ur_type.to_string(),
+                body: body.to_string(),
+            }),
+            [ur_type, sequence, body] => {
+                let (seq, seq_len) = sequence.split_once('-').ok_or(Error::InvalidUr)?;
+                Ok(Self::Part {
+                    ur_type: ur_type.to_string(),
+                    seq: seq.parse().map_err(|_| Error::InvalidUr)?,
+                    seq_len: seq_len.parse().map_err(|_| Error::InvalidUr)?,
+                    body: body.to_string(),
+                })
+            }
+            _ => Err(Error::InvalidUr),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::{cbor::CborWriter, fountain};
+    use super::*;
+
+    fn bytes_ur(len: usize) -> Result<Ur> {
+        let mut writer = CborWriter::new();
+        writer.bytes(&fountain::message(len, "Wolf"));
+
+        Ur::new("bytes", writer.into_bytes())
+    }
+
+    #[test]
+    fn test_single_part() -> Result<()> {
+        let mut writer = CborWriter::new();
+        writer.bytes(&[0x00, 0x01, 0x02, 0x80, 0xff]);
+        let ur = Ur::new("bytes", writer.into_bytes())?;
+        assert_eq!(ur.to_string(), "ur:bytes/feaeadaolazmfxwyzepa");
+        assert_eq!(Ur::try_from("UR:BYTES/FEAEADAOLAZMFXWYZEPA")?, ur);
+
+        let mut encoder = UrEncoder::new(&ur, 100)?;
+        assert!(encoder.is_single_part());
+        assert_eq!(encoder.next_part(), "ur:bytes/feaeadaolazmfxwyzepa");
+
+        let mut decoder = UrDecoder::new();
+        assert!(decoder.receive("ur:bytes/feaeadaolazmfxwyzepa")?);
+        assert_eq!(decoder.result(), Some(&ur));
+
+        let invalid = [
+            "bytes/feaeadaolazmfxwyzepa",
+            "ur:bytes/feaeadaolazmfxwyzepb",
+            "ur:bytes/1-1/feaeadaolazmfxwyzepa",
+            "ur:by_tes/feaeadaolazmfxwyzepa",
+        ];
+        for value in invalid {
+            assert!(matches!(Ur::try_from(value), Err(Error::InvalidUr)));
+        }
+        assert!(matches!(Ur::new("Bytes", vec![]), Err(Error::InvalidUr)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_multi_part_encode() -> Result<()> {
+        let mut encoder = UrEncoder::new(&bytes_ur(256)?, 30)?;
+        assert!(!encoder.is_single_part());
+        assert_eq!(encoder.seq_len(), 9);
+
+        let parts = (0..11).map(|_| encoder.next_part()).collect::<Vec<_>>();
+        assert_eq!(parts[0], "ur:bytes/1-9/lpadascfadaxcywenbpljkhdcahkadaemejtswhhylkepmykhhtsytsnoyoyaxaedsuttydmmhhpktpmsrjtdkgslpgh");
+        assert_eq!(parts[1], "ur:bytes/2-9/lpaoascfadaxcywenbpljkhdcagwdpfnsboxgwlbaawzuefywkdplrsrjynbvygabwjldapfcsgmghhkhstlrdcxaefz");
+        assert_eq!(parts[2], "ur:bytes/3-9/lpaxascfadaxcywenbpljkhdcahelbknlkuejnbadmssfhfrdpsbiegecpasvssovlgeykssjykklronvsjksopdzmol");
+        assert_eq!(parts[8], "ur:bytes/9-9/lpasascfadaxcywenbpljkhdcajskecpmdckihdyhphfotjojtfmlnwmadspaxrkytbztpbauotbgtgtaeaevtgavtny");
+        assert_eq!(parts[9], "ur:bytes/10-9/lpbkascfadaxcywenbpljkhdcahkadaemejtswhhylkepmykhhtsytsnoyoyaxaedsuttydmmhhpktpmsrjtwdkiplzs");
+        assert_eq!(parts[10], "ur:bytes/11-9/lpbdascfadaxcywenbpljkhdcahelbknlkuejnbadmssfhfrdpsbiegecpasvssovlgeykssjykklronvsjkvetiiapk");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_multi_part_decode() -> Result<()> {
+        let ur = bytes_ur(32767)?;
+        let mut encoder = UrEncoder::new(&ur, 1000)?;
+        let mut decoder = UrDecoder::new();
+        let mut count = 0;
+        while !decoder.is_complete() {
+            let part = encoder.next_part();
+            count += 1;
+            // Simulates a scanner missing every third frame.
+            if count % 3 == 0 {
+                continue;
+            }
+            assert!(decoder.progress() < 1.0);
+            decoder.receive(&part.to_uppercase())?;
+            assert!(count < 500);
+        }
+        assert_eq!(decoder.progress(), 1.0);
+        assert_eq!(decoder.result(), Some(&ur));
+
+        let mut decoder = UrDecoder::new();
+        decoder.receive(&encoder.next_part())?;
+        let part = encoder.next_part().replace("ur:bytes/", "ur:crypto-psbt/");
+        assert!(matches!(decoder.receive(&part), Err(Error::InvalidUr)));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,202 @@
+use std::collections::HashMap;
+
+use tezos_core::types::encoded::{Encoded, ImplicitAddress, PublicKey, Signature};
+use tezos_operation::signer::Signer;
+
+use crate::{
+    high_watermark::HighWatermarks, policy::SigningPolicy, request::SigningRequest, Error, Result,
+};
+
+/// The signing logic of a remote signer: it decodes the payloads, enforces the [SigningPolicy],
+/// refuses to double sign and signs with the [Signer] registered for the requested key.
+///
+/// The daemon is independent of the transport, see the `server` module (`server` feature) for the HTTP server
+/// speaking the `octez-signer` protocol.
+///
+/// ```rust
+/// use tezos_signer::{daemon::SignerDaemon, high_watermark::HighWatermarks, policy::SigningPolicy};
+/// use tezos_operation::signer::InMemorySigner;
+///
+/// async fn example() -> tezos_signer::Result<()> {
+///     let mut daemon = SignerDaemon::new(SigningPolicy::default(), HighWatermarks::in_memory());
+///     let address = daemon.add_signer(InMemorySigner::new(
+///         "edskRv7VyXGVZb8EsrR7D9XKUbbAQNQGtALP6QeB16ZCD7SmmJpzyeneJVg3Mq56YLbxRA1kSdAXiswwPiaVfR3NHGMCXCziuZ".try_into()?,
+///         "edpkttZKC51wemRqL2QxwpMnEKxWnbd35pq47Y6xsCHp5M1f7LN8NP".try_into()?,
+///     )).await?;
+///     let signature = daemon.sign(&address, &[0x05, 0x01, 0x00]).await?;
+///     Ok(())
+/// }
+/// ```
+pub struct SignerDaemon {
+    signers: HashMap<String, Box<dyn Signer>>,
+    policy: SigningPolicy,
+    high_watermarks: HighWatermarks,
+}
+
+impl SignerDaemon {
+    pub fn new(policy: SigningPolicy, high_watermarks: HighWatermarks) -> Self {
+        Self {
+            signers: HashMap::new(),
+            policy,
+            high_watermarks,
+        }
+    }
+
+    /// Registers the `signer` and returns the address it's served at.
+    pub async fn add_signer<S: Signer + 'static>(&mut self, signer: S) -> Result<ImplicitAddress> {
+        let address = signer.address().await?;
+        self.signers
+            .insert(address.value().into(), Box::new(signer));
+
+        Ok(address)
+    }
+
+    pub fn policy(&self) -> &SigningPolicy {
+        &self.policy
+    }
+
+    pub fn high_watermarks(&self) -> &HighWatermarks {
+        &self.high_watermarks
+    }
+
+    /// Returns the public key registered for `public_key_hash`.
+    pub async fn public_key(&self, public_key_hash: &ImplicitAddress) -> Result<PublicKey> {
+        Ok(self.signer(public_key_hash)?.public_key().await?)
+    }
+
+    /// Signs the `bytes`, prefixed with a magic byte, with the key registered for `public_key_hash`.
+    pub async fn sign(&self, public_key_hash: &ImplicitAddress, bytes: &[u8]) -> Result<Signature> {
+        let signer = self.signer(public_key_hash)?;
+        let request = SigningRequest::decode(bytes)?;
+        self.policy.check(&request)?;
+        self.high_watermarks
+            .check_and_update(public_key_hash, &request, bytes)?;
+
+        let (magic_byte, payload) = bytes.split_at(1);
+        Ok(signer.sign(magic_byte, payload).await?)
+    }
+
+    fn signer(&self, public_key_hash: &ImplicitAddress) -> Result<&dyn Signer> {
+        self.signers
+            .get(public_key_hash.value())
+            .map(|signer| signer.as_ref())
+            .ok_or(Error::UnknownKey)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tezos_core::types::encoded::ChainId;
+    use tezos_operation::{
+        operations::{Endorsement, Operation, UnsignedOperation},
+        signer::InMemorySigner,
+    };
+
+    use super::*;
+    use crate::request::magic_bytes;
+
+    fn signer() -> InMemorySigner {
+        InMemorySigner::new(
+            "edskRv7VyXGVZb8EsrR7D9XKUbbAQNQGtALP6QeB16ZCD7SmmJpzyeneJVg3Mq56YLbxRA1kSdAXiswwPiaVfR3NHGMCXCziuZ".try_into().unwrap(),
+            "edpkttZKC51wemRqL2QxwpMnEKxWnbd35pq47Y6xsCHp5M1f7LN8NP".try_into().unwrap(),
+        )
+    }
+
+    fn attestation(level: i32, round: i32) -> Vec<u8> {
+        let chain_id: ChainId = "NetXdQprcVkpaWU".try_into().unwrap();
+        let operation = UnsignedOperation::new(
+            "BLyKu3tnc9NCuiFfCqfeVGPCoZTyW63dYh2XAYxkM7fQYKCqsju"
+                .try_into()
+                .unwrap(),
+            vec![Endorsement::new(
+                1,
+                level,
+                round,
+                "vh32fG1tMNPtzZiKPHinfLPSAU3m2piFSgud4jBdaGSKJQH6q7Xd"
+                    .try_into()
+                    .unwrap(),
+            )
+            .into()],
+        );
+        [
+            [magic_bytes::ATTESTATION].as_slice(),
+            &chain_id.to_bytes().unwrap(),
+            &operation.to_forged_bytes().unwrap(),
+        ]
+        .concat()
+    }
+
+    #[tokio::test]
+    async fn test_unknown_key() -> Result<()> {
+        let daemon = SignerDaemon::new(Default::default(), Default::default());
+        let address: ImplicitAddress = "tz1RFxSHjxxyaaXyAvJG3uRQeJUCCm4jk2LX".try_into()?;
+
+        assert!(matches!(
+            daemon.public_key(&address).await,
+            Err(Error::UnknownKey)
+        ));
+        assert!(matches!(
+            daemon.sign(&address, &[magic_bytes::MICHELINE]).await,
+            Err(Error::UnknownKey)
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_refuses_before_signing() -> Result<()> {
+        let mut daemon = SignerDaemon::new(
+            SigningPolicy {
+                allowed_magic_bytes: Some(vec![magic_bytes::ATTESTATION]),
+                ..Default::default()
+            },
+            Default::default(),
+        );
+        let address = daemon.add_signer(signer()).await?;
+        assert_eq!(
+            daemon.public_key(&address).await?,
+            signer().public_key().await?
+        );
+
+        assert!(matches!(
+            daemon.sign(&address, &[magic_bytes::MICHELINE, 0x00]).await,
+            Err(Error::PolicyViolation { .. })
+        ));
+        assert!(matches!(
+            daemon.sign(&address, &[0x42]).await,
+            Err(Error::InvalidPayload)
+        ));
+
+        daemon.high_watermarks().check_and_update(
+            &address,
+            &SigningRequest::decode(&attestation(10, 0))?,
+            &attestation(10, 0),
+        )?;
+        assert!(matches!(
+            daemon.sign(&address, &attestation(9, 0)).await,
+            Err(Error::DoubleSigning { .. })
+        ));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "ed25519")]
+    #[tokio::test]
+    async fn test_sign() -> Result<()> {
+        let mut daemon = SignerDaemon::new(Default::default(), Default::default());
+        let address = daemon.add_signer(signer()).await?;
+
+        let bytes = attestation(10, 0);
+        let signature = daemon.sign(&address, &bytes).await?;
+        assert_eq!(signature, signer().sign(&bytes[..1], &bytes[1..]).await?);
+        // the same attestation can be signed again, but not one below the high-watermark
+        assert_eq!(daemon.sign(&address, &bytes).await?, signature);
+        assert!(daemon.sign(&address, &attestation(10, 1)).await.is_ok());
+        assert!(matches!(
+            daemon.sign(&address, &attestation(10, 0)).await,
+            Err(Error::DoubleSigning { .. })
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,352 @@
+//! Conversions between the ECDSA formats produced by HSMs and key management services
+//! and the Tezos `secp256k1` and `p256` key and signature types.
+
+use num_bigint::BigUint;
+use tezos_core::types::encoded::{
+    Encoded, P256PublicKey, P256Signature, PublicKey, Secp256K1PublicKey, Secp256K1Signature,
+    Signature,
+};
+
+use crate::{Error, Result};
+
+const SCALAR_SIZE: usize = 32;
+const EC_PUBLIC_KEY_OID: [u8; 7] = [0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01];
+
+/// An elliptic curve supported for ECDSA signing by Tezos.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Curve {
+    /// The `secp256k1` curve of `tz2` addresses.
+    Secp256K1,
+    /// The `P-256` (`secp256r1`) curve of `tz3` addresses.
+    P256,
+}
+
+impl Curve {
+    const SECP256_K1_OID: [u8; 5] = [0x2b, 0x81, 0x04, 0x00, 0x0a];
+    const P256_OID: [u8; 8] = [0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07];
+
+    /// Recognizes the curve from its DER encoded object identifier, with or without the tag and length.
+    pub fn from_oid(oid: &[u8]) -> Result<Self> {
+        let oid = match oid {
+            [0x06, length, rest @ ..] if *length as usize == rest.len() => rest,
+            _ => oid,
+        };
+        if oid == Self::SECP256_K1_OID {
+            return Ok(Self::Secp256K1);
+        }
+        if oid == Self::P256_OID {
+            return Ok(Self::P256);
+        }
+
+        Err(Error::UnsupportedCurve)
+    }
+
+    fn order(&self) -> BigUint {
+        let order = match self {
+            Self::Secp256K1 => "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141",
+            Self::P256 => "ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551",
+        };
+
+        BigUint::parse_bytes(order.as_bytes(), 16).unwrap()
+    }
+
+    /// Creates the Tezos public key from an uncompressed (`0x04 || x || y`) or compressed point.
+    pub fn public_key(&self, point: &[u8]) -> Result<PublicKey> {
+        let point = compress_point(point)?;
+        let public_key: PublicKey = match self {
+            Self::Secp256K1 => Secp256K1PublicKey::from_bytes(&point)?.into(),
+            Self::P256 => P256PublicKey::from_bytes(&point)?.into(),
+        };
+
+        Ok(public_key)
+    }
+
+    /// Creates the Tezos signature from a DER or raw (`r || s`) ECDSA signature, normalized to a low `s`.
+    pub fn signature(&self, signature: &[u8]) -> Result<Signature> {
+        let raw = if signature.len() == 2 * SCALAR_SIZE {
+            signature.to_vec()
+        } else {
+            der_to_raw(signature)?
+        };
+        let raw = self.normalize_s(&raw)?;
+        let signature: Signature = match self {
+            Self::Secp256K1 => Secp256K1Signature::from_bytes(&raw)?.into(),
+            Self::P256 => P256Signature::from_bytes(&raw)?.into(),
+        };
+
+        Ok(signature)
+    }
+
+    /// Replaces `s` with `n - s` in a raw `r || s` signature if `s` is above half of the curve order `n`,
+    /// as only low `s` signatures are accepted by Tezos.
+    pub fn normalize_s(&self, raw: &[u8]) -> Result<Vec<u8>> {
+        if raw.len() != 2 * SCALAR_SIZE {
+            return Err(Error::InvalidSignature);
+        }
+        let (r, s) = raw.split_at(SCALAR_SIZE);
+        let order = self.order();
+        let s = BigUint::from_bytes_be(s);
+        if s > (&order >> 1) {
+            return Ok([r, &to_scalar(&(order - s))?].concat());
+        }
+
+        Ok(raw.to_vec())
+    }
+}
+
+/// Converts a DER encoded ECDSA signature (`SEQUENCE { INTEGER r, INTEGER s }`) into its raw `r || s` form.
+pub fn der_to_raw(der: &[u8]) -> Result<Vec<u8>> {
+    let mut reader = DerReader::new(der);
+    let mut sequence = DerReader::new(reader.read(0x30)?);
+    let r = sequence.read(0x02)?;
+    let s = sequence.read(0x02)?;
+    if !reader.is_empty() || !sequence.is_empty() {
+        return Err(Error::InvalidSignature);
+    }
+
+    Ok([
+        to_scalar(&BigUint::from_bytes_be(r))?,
+        to_scalar(&BigUint::from_bytes_be(s))?,
+    ]
+    .concat())
+}
+
+/// Compresses an uncompressed (`0x04 || x || y`) elliptic curve point to `0x02 || x` or `0x03 || x`,
+/// depending on the parity of `y`. Compressed points are returned as they are.
+pub fn compress_point(point: &[u8]) -> Result<Vec<u8>> {
+    match point {
+        [0x04, coordinates @ ..] if coordinates.len() == 2 * SCALAR_SIZE => {
+            let (x, y) = coordinates.split_at(SCALAR_SIZE);
+            let prefix = if y[SCALAR_SIZE - 1] & 1 == 0 {
+                0x02
+            } else {
+                0x03
+            };
+
+            Ok([[prefix].as_slice(), x].concat())
+        }
+        [0x02 | 0x03, ..] if point.len() == SCALAR_SIZE + 1 => Ok(point.to_vec()),
+        _ => Err(Error::InvalidPublicKey),
+    }
+}
+
+/// Reads the curve and the public key from a DER encoded `SubjectPublicKeyInfo`,
+/// the format key management services export public keys in.
+pub fn parse_spki(spki: &[u8]) -> Result<(Curve, PublicKey)> {
+    let mut reader = DerReader::new(spki);
+    let mut info = DerReader::new(reader.read(0x30)?);
+    let mut algorithm = DerReader::new(info.read(0x30)?);
+    if algorithm.read(0x06)? != EC_PUBLIC_KEY_OID {
+        return Err(Error::UnsupportedCurve);
+    }
+    let curve = Curve::from_oid(algorithm.read(0x06)?)?;
+    let point = match info.read(0x03)? {
+        [0x00, point @ ..] => point,
+        _ => return Err(Error::InvalidPublicKey),
+    };
+
+    Ok((curve, curve.public_key(point)?))
+}
+
+/// Extracts the point from a DER `OCTET STRING`, the way PKCS#11 tokens export `CKA_EC_POINT`.
+pub fn unwrap_octet_string(value: &[u8]) -> Result<&[u8]> {
+    let mut reader = DerReader::new(value);
+    let point = reader.read(0x04).map_err(|_| Error::InvalidPublicKey)?;
+    if !reader.is_empty() {
+        return Err(Error::InvalidPublicKey);
+    }
+
+    Ok(point)
+}
+
+fn to_scalar(value: &BigUint) -> Result<Vec<u8>> {
+    let bytes = value.to_bytes_be();
+    if bytes.len() > SCALAR_SIZE {
+        return Err(Error::InvalidSignature);
+    }
+
+    Ok([vec![0u8; SCALAR_SIZE - bytes.len()], bytes].concat())
+}
+
+/// A minimal reader of the DER tag-length-value encoding.
+pub(crate) struct DerReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> DerReader<'a> {
+    pub(crate) fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Reads the value of the next element, which has to be tagged with `tag`.
+    pub(crate) fn read(&mut self, tag: u8) -> Result<&'a [u8]> {
+        let (actual_tag, rest) = self.bytes.split_first().ok_or(Error::InvalidDer)?;
+        if *actual_tag != tag {
+            return Err(Error::InvalidDer);
+        }
+        let (first, rest) = rest.split_first().ok_or(Error::InvalidDer)?;
+        let (length, rest) = if first & 0x80 == 0 {
+            (*first as usize, rest)
+        } else {
+            let size = (first & 0x7f) as usize;
+            if size == 0 || size > std::mem::size_of::<usize>() || rest.len() < size {
+                return Err(Error::InvalidDer);
+            }
+            let (length, rest) = rest.split_at(size);
+            (
+                length
+                    .iter()
+                    .fold(0usize, |length, byte| (length << 8) | *byte as usize),
+                rest,
+            )
+        };
+        if rest.len() < length {
+            return Err(Error::InvalidDer);
+        }
+        let (value, rest) = rest.split_at(length);
+        self.bytes = rest;
+
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SECP256_K1_G: &str = "0479be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8";
+    const P256_G: &str = "046b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c2964fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5";
+
+    #[test]
+    fn test_curve_from_oid() -> Result<()> {
+        assert_eq!(
+            Curve::from_oid(&hex::decode("06052b8104000a").unwrap())?,
+            Curve::Secp256K1
+        );
+        assert_eq!(
+            Curve::from_oid(&hex::decode("2a8648ce3d030107").unwrap())?,
+            Curve::P256
+        );
+        assert!(matches!(
+            Curve::from_oid(&hex::decode("06032b6570").unwrap()),
+            Err(Error::UnsupportedCurve)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_der_to_raw() -> Result<()> {
+        // `r` is padded with a zero byte in DER, `s` is a byte shorter than a scalar
+        let der = hex::decode("3044022100e1b6b6b6b6b6b6b6b6b6b6b6b6b6b6b6b6b6b6b6b6b6b6b6b6b6b6b6b6b6b6b6021f5e5e5e5e5e5e5e5e5e5e5e5e5e5e5e5e5e5e5e5e5e5e5e5e5e5e5e5e5e5e5e").unwrap();
+
+        assert_eq!(
+            hex::encode(der_to_raw(&der)?),
+            "e1b6b6b6b6b6b6b6b6b6b6b6b6b6b6b6b6b6b6b6b6b6b6b6b6b6b6b6b6b6b6b6005e5e5e5e5e5e5e5e5e5e5e5e5e5e5e5e5e5e5e5e5e5e5e5e5e5e5e5e5e5e5e"
+        );
+        assert!(matches!(der_to_raw(&der[..20]), Err(Error::InvalidDer)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_normalize_s() -> Result<()> {
+        let r = [0x01u8; 32];
+        let low_s = [0x02u8; 32];
+        let low = [r.as_slice(), &low_s].concat();
+        assert_eq!(Curve::Secp256K1.normalize_s(&low)?, low);
+
+        let order = Curve::Secp256K1.order();
+        let high_s = to_scalar(&(&order - BigUint::from_bytes_be(&low_s)))?;
+        let high = [r.as_slice(), &high_s].concat();
+        assert_eq!(Curve::Secp256K1.normalize_s(&high)?, low);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_compress_point() -> Result<()> {
+        let compressed = compress_point(&hex::decode(SECP256_K1_G).unwrap())?;
+        assert_eq!(
+            hex::encode(&compressed),
+            format!("02{}", &SECP256_K1_G[2..66])
+        );
+        assert_eq!(compress_point(&compressed)?, compressed);
+
+        let compressed = compress_point(&hex::decode(P256_G).unwrap())?;
+        assert_eq!(hex::encode(&compressed), format!("03{}", &P256_G[2..66]));
+
+        assert!(matches!(
+            compress_point(&[0x04, 0x01]),
+            Err(Error::InvalidPublicKey)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_public_key() -> Result<()> {
+        let values = vec![
+            (
+                Curve::Secp256K1,
+                SECP256_K1_G,
+                "sppk7aEFdrScsCDxdaQ7Ev1JxpWZESrEK6UsWRhr79JfGKkPYGTsudN",
+                "tz2BCeQSi5ETyKJsob61pWCoQvoGtsrJBEt2",
+            ),
+            (
+                Curve::P256,
+                P256_G,
+                "p2pk67L57Q7vcgLkMrKXctFRKs5JSLR6qjiw1riJaFyakWpTv9QSkRf",
+                "tz3bqAfFRnSA6dfPRG8XR6MBMmo6HZTTG44V",
+            ),
+        ];
+        for (curve, point, public_key, address) in values {
+            let octet_string = [[0x04, 0x41].as_slice(), &hex::decode(point).unwrap()].concat();
+            let actual = curve.public_key(unwrap_octet_string(&octet_string)?)?;
+            assert_eq!(actual.value(), public_key);
+            assert_eq!(actual.bs58_address()?, address);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_spki() -> Result<()> {
+        let spki = hex::decode(format!(
+            "3056301006072a8648ce3d020106052b8104000a034200{}",
+            SECP256_K1_G
+        ))
+        .unwrap();
+        let (curve, public_key) = parse_spki(&spki)?;
+        assert_eq!(curve, Curve::Secp256K1);
+        assert_eq!(
+            public_key.value(),
+            "sppk7aEFdrScsCDxdaQ7Ev1JxpWZESrEK6UsWRhr79JfGKkPYGTsudN"
+        );
+
+        let spki = hex::decode(format!(
+            "3059301306072a8648ce3d020106082a8648ce3d030107034200{}",
+            P256_G
+        ))
+        .unwrap();
+        let (curve, public_key) = parse_spki(&spki)?;
+        assert_eq!(curve, Curve::P256);
+        assert_eq!(
+            public_key.bs58_address()?,
+            "tz3bqAfFRnSA6dfPRG8XR6MBMmo6HZTTG44V"
+        );
+
+        // an Ed25519 key
+        let spki = hex::decode(
+            "302a300506032b6570032100d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+        )
+        .unwrap();
+        assert!(matches!(parse_spki(&spki), Err(Error::UnsupportedCurve)));
+
+        Ok(())
+    }
+}
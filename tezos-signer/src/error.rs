@@ -0,0 +1,78 @@
+use std::result;
+
+use derive_more::{Display, Error as DError, From};
+
+#[derive(DError, Display, Debug, From)]
+pub enum Error {
+    Core {
+        source: tezos_core::Error,
+    },
+    Operation {
+        source: tezos_operation::Error,
+    },
+    Io {
+        source: std::io::Error,
+    },
+    Serialization {
+        source: serde_json::Error,
+    },
+    #[cfg(feature = "server")]
+    Server {
+        source: hyper::Error,
+    },
+    #[cfg(feature = "pkcs11")]
+    Library {
+        source: libloading::Error,
+    },
+    #[cfg(feature = "kms")]
+    Http {
+        source: reqwest::Error,
+    },
+    UnknownKey,
+    InvalidPayload,
+    PayloadTooLarge,
+    #[from(ignore)]
+    PolicyViolation {
+        description: String,
+    },
+    #[from(ignore)]
+    DoubleSigning {
+        description: String,
+    },
+    #[from(ignore)]
+    InvalidConfig {
+        description: String,
+    },
+    UnsupportedCurve,
+    InvalidDer,
+    InvalidPublicKey,
+    InvalidSignature,
+    InvalidUr,
+    InvalidCbor,
+    UnsupportedVersion,
+    SourceMismatch,
+    #[cfg(feature = "pkcs11")]
+    #[from(ignore)]
+    Pkcs11 {
+        description: String,
+    },
+    #[cfg(feature = "kms")]
+    #[from(ignore)]
+    Kms {
+        description: String,
+    },
+}
+
+impl From<Error> for tezos_operation::Error {
+    fn from(error: Error) -> Self {
+        match error {
+            Error::Core { source } => source.into(),
+            Error::Operation { source } => source,
+            _ => tezos_operation::Error::Signer {
+                description: error.to_string(),
+            },
+        }
+    }
+}
+
+pub type Result<T> = result::Result<T, Error>;
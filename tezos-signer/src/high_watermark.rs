@@ -0,0 +1,282 @@
+use std::{
+    collections::HashMap,
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use serde::{Deserialize, Serialize};
+use tezos_core::types::encoded::{Encoded, ImplicitAddress};
+
+use crate::{
+    request::{ConsensusRequest, SigningRequest},
+    Error, Result,
+};
+
+/// The kind of a consensus payload tracked by [HighWatermarks].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConsensusKind {
+    Block,
+    Preattestation,
+    Attestation,
+}
+
+/// The highest level and round signed for a key, a chain and a [ConsensusKind].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HighWatermark {
+    pub public_key_hash: String,
+    pub chain_id: String,
+    pub kind: ConsensusKind,
+    pub level: i32,
+    pub round: i32,
+    /// The hex encoded payload signed at `level` and `round`, which can be signed again.
+    pub payload: String,
+}
+
+type HighWatermarkKey = (String, String, ConsensusKind);
+
+/// The high-watermarks of the signed blocks and consensus operations, used to refuse double signing.
+///
+/// A block or a consensus operation is only signed if its level and round are higher than those
+/// previously signed by the same key on the same chain, or if the very same payload is signed again.
+#[derive(Debug)]
+pub struct HighWatermarks {
+    watermarks: Mutex<HashMap<HighWatermarkKey, HighWatermark>>,
+    path: Option<PathBuf>,
+}
+
+impl HighWatermarks {
+    /// Creates high-watermarks which are kept in memory only.
+    pub fn in_memory() -> Self {
+        Self {
+            watermarks: Mutex::new(HashMap::new()),
+            path: None,
+        }
+    }
+
+    /// Creates high-watermarks which are persisted as JSON in the file at `path`,
+    /// loading the file content if it already exists.
+    pub fn persistent<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let watermarks: Vec<HighWatermark> = if path.exists() {
+            serde_json::from_slice(&fs::read(&path)?)?
+        } else {
+            vec![]
+        };
+
+        Ok(Self {
+            watermarks: Mutex::new(
+                watermarks
+                    .into_iter()
+                    .map(|watermark| (Self::key_of(&watermark), watermark))
+                    .collect(),
+            ),
+            path: Some(path),
+        })
+    }
+
+    /// Returns the current high-watermark for the given key, chain and kind.
+    pub fn get(
+        &self,
+        public_key_hash: &ImplicitAddress,
+        chain_id: &str,
+        kind: ConsensusKind,
+    ) -> Option<HighWatermark> {
+        let watermarks = self.watermarks.lock().unwrap();
+
+        watermarks
+            .get(&(public_key_hash.value().into(), chain_id.into(), kind))
+            .cloned()
+    }
+
+    /// Checks that signing the `bytes` of the `request` with `public_key_hash` is not a double signing
+    /// and raises the high-watermark accordingly.
+    ///
+    /// Requests other than blocks and consensus operations are always accepted.
+    pub fn check_and_update(
+        &self,
+        public_key_hash: &ImplicitAddress,
+        request: &SigningRequest,
+        bytes: &[u8],
+    ) -> Result<()> {
+        let (kind, consensus) = match request {
+            SigningRequest::Block(consensus) => (ConsensusKind::Block, consensus),
+            SigningRequest::Preattestation(consensus) => (ConsensusKind::Preattestation, consensus),
+            SigningRequest::Attestation(consensus) => (ConsensusKind::Attestation, consensus),
+            _ => return Ok(()),
+        };
+        let ConsensusRequest {
+            chain_id,
+            level,
+            round,
+        } = consensus;
+        let candidate = HighWatermark {
+            public_key_hash: public_key_hash.value().into(),
+            chain_id: chain_id.value().into(),
+            kind,
+            level: *level,
+            round: *round,
+            payload: hex::encode(bytes),
+        };
+
+        let mut watermarks = self.watermarks.lock().unwrap();
+        let key = Self::key_of(&candidate);
+        if let Some(current) = watermarks.get(&key) {
+            if (candidate.level, candidate.round) == (current.level, current.round)
+                && candidate.payload == current.payload
+            {
+                return Ok(());
+            }
+            if (candidate.level, candidate.round) <= (current.level, current.round) {
+                return Err(Error::DoubleSigning {
+                    description: format!(
+                        "level {} and round {} are not above the high-watermark at level {} and round {}",
+                        candidate.level, candidate.round, current.level, current.round
+                    ),
+                });
+            }
+        }
+        // The high-watermark is raised only once persisted, so a failed write can't be followed by a signature.
+        if let Some(path) = &self.path {
+            let mut persisted = watermarks
+                .iter()
+                .filter(|(current, _)| **current != key)
+                .map(|(_, watermark)| watermark)
+                .collect::<Vec<_>>();
+            persisted.push(&candidate);
+            Self::persist(path, persisted)?;
+        }
+        watermarks.insert(key, candidate);
+
+        Ok(())
+    }
+
+    fn key_of(watermark: &HighWatermark) -> HighWatermarkKey {
+        (
+            watermark.public_key_hash.clone(),
+            watermark.chain_id.clone(),
+            watermark.kind,
+        )
+    }
+
+    fn persist(path: &Path, watermarks: Vec<&HighWatermark>) -> Result<()> {
+        // Write to a temporary file first, so a crash can't leave the high-watermarks corrupted.
+        let temporary_path = path.with_extension("tmp");
+        let mut file = fs::File::create(&temporary_path)?;
+        file.write_all(&serde_json::to_vec(&watermarks)?)?;
+        file.sync_all()?;
+        fs::rename(temporary_path, path)?;
+
+        Ok(())
+    }
+}
+
+impl Default for HighWatermarks {
+    fn default() -> Self {
+        Self::in_memory()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn attestation(level: i32, round: i32) -> SigningRequest {
+        SigningRequest::Attestation(ConsensusRequest {
+            chain_id: "NetXdQprcVkpaWU".try_into().unwrap(),
+            level,
+            round,
+        })
+    }
+
+    #[test]
+    fn test_check_and_update() -> Result<()> {
+        let watermarks = HighWatermarks::in_memory();
+        let baker: ImplicitAddress = "tz1PwXjsrgYBi9wpe3tFhazJpt7JMTVzBp5c".try_into()?;
+        let other_baker: ImplicitAddress = "tz1RFxSHjxxyaaXyAvJG3uRQeJUCCm4jk2LX".try_into()?;
+
+        watermarks.check_and_update(&baker, &attestation(10, 1), &[0x01])?;
+        // the very same payload can be signed again
+        watermarks.check_and_update(&baker, &attestation(10, 1), &[0x01])?;
+        // another payload at the same level and round is a double signing
+        assert!(matches!(
+            watermarks.check_and_update(&baker, &attestation(10, 1), &[0x02]),
+            Err(Error::DoubleSigning { .. })
+        ));
+        assert!(matches!(
+            watermarks.check_and_update(&baker, &attestation(10, 0), &[0x03]),
+            Err(Error::DoubleSigning { .. })
+        ));
+        assert!(matches!(
+            watermarks.check_and_update(&baker, &attestation(9, 5), &[0x04]),
+            Err(Error::DoubleSigning { .. })
+        ));
+        watermarks.check_and_update(&baker, &attestation(10, 2), &[0x05])?;
+        watermarks.check_and_update(&baker, &attestation(11, 0), &[0x06])?;
+
+        // the watermarks are tracked per key and per kind
+        watermarks.check_and_update(&other_baker, &attestation(10, 0), &[0x07])?;
+        watermarks.check_and_update(
+            &baker,
+            &SigningRequest::Preattestation(ConsensusRequest {
+                chain_id: "NetXdQprcVkpaWU".try_into()?,
+                level: 10,
+                round: 0,
+            }),
+            &[0x08],
+        )?;
+
+        let current = watermarks
+            .get(&baker, "NetXdQprcVkpaWU", ConsensusKind::Attestation)
+            .unwrap();
+        assert_eq!((current.level, current.round), (11, 0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_persistent() -> Result<()> {
+        let path = std::env::temp_dir().join(format!(
+            "tezos-signer-high-watermarks-{}.json",
+            std::process::id()
+        ));
+        let baker: ImplicitAddress = "tz1PwXjsrgYBi9wpe3tFhazJpt7JMTVzBp5c".try_into()?;
+
+        HighWatermarks::persistent(&path)?.check_and_update(
+            &baker,
+            &attestation(10, 1),
+            &[0x01],
+        )?;
+        let reloaded = HighWatermarks::persistent(&path)?;
+        let result = reloaded.check_and_update(&baker, &attestation(10, 0), &[0x02]);
+        fs::remove_file(&path)?;
+
+        assert!(matches!(result, Err(Error::DoubleSigning { .. })));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_persist_failure() -> Result<()> {
+        let path = std::env::temp_dir()
+            .join(format!("tezos-signer-missing-{}", std::process::id()))
+            .join("high_watermarks.json");
+        let baker: ImplicitAddress = "tz1PwXjsrgYBi9wpe3tFhazJpt7JMTVzBp5c".try_into()?;
+        let watermarks = HighWatermarks::persistent(&path)?;
+
+        for _ in 0..2 {
+            assert!(matches!(
+                watermarks.check_and_update(&baker, &attestation(10, 1), &[0x01]),
+                Err(Error::Io { .. })
+            ));
+        }
+        assert_eq!(
+            watermarks.get(&baker, "NetXdQprcVkpaWU", ConsensusKind::Attestation),
+            None
+        );
+
+        Ok(())
+    }
+}
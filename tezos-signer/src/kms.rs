@@ -0,0 +1,525 @@
+//! A [Signer] backed by an ECDSA key stored in AWS KMS or in a service implementing its API, e.g. LocalStack.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use hmac::{Hmac, Mac};
+use reqwest::{header::CONTENT_TYPE, Client, Url};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tezos_core::{
+    internal::crypto::blake2b,
+    types::encoded::{PublicKey, Signature},
+};
+use tezos_operation::signer::Signer;
+
+use crate::{
+    ecdsa::{parse_spki, Curve},
+    Error, Result,
+};
+
+const SERVICE: &str = "kms";
+const CONTENT_TYPE_JSON: &str = "application/x-amz-json-1.1";
+const MESSAGE_HASH_SIZE: usize = 32;
+
+/// The AWS credentials used to sign the KMS requests.
+#[derive(Clone)]
+pub struct KmsCredentials {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    /// The session token of temporary credentials.
+    pub session_token: Option<String>,
+}
+
+impl KmsCredentials {
+    pub fn new(access_key_id: String, secret_access_key: String) -> Self {
+        Self {
+            access_key_id,
+            secret_access_key,
+            session_token: None,
+        }
+    }
+
+    /// Reads the credentials from the `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY`
+    /// and `AWS_SESSION_TOKEN` environment variables.
+    pub fn from_env() -> Result<Self> {
+        let variable = |name: &str| {
+            std::env::var(name).map_err(|_| Error::Kms {
+                description: format!("{} is not set", name),
+            })
+        };
+
+        Ok(Self {
+            access_key_id: variable("AWS_ACCESS_KEY_ID")?,
+            secret_access_key: variable("AWS_SECRET_ACCESS_KEY")?,
+            session_token: std::env::var("AWS_SESSION_TOKEN").ok(),
+        })
+    }
+}
+
+impl std::fmt::Debug for KmsCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KmsCredentials")
+            .field("access_key_id", &self.access_key_id)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A [Signer] backed by an `ECC_SECG_P256K1` (`tz2`) or `ECC_NIST_P256` (`tz3`) KMS key.
+///
+/// The public key is fetched with `GetPublicKey` when the signer is created. Payloads are hashed
+/// with `blake2b` like the [InMemorySigner](tezos_operation::signer::InMemorySigner) does,
+/// and the hash is signed with `Sign` as an `ECDSA_SHA_256` digest.
+///
+/// ```rust,no_run
+/// use tezos_operation::signer::Signer;
+/// use tezos_signer::kms::{KmsCredentials, KmsSigner};
+///
+/// async fn example() -> tezos_signer::Result<()> {
+///     let signer = KmsSigner::new_with_endpoint(
+///         "http://localhost:4566",
+///         "us-east-1",
+///         KmsCredentials::new("test".into(), "test".into()),
+///         "alias/baker",
+///     )
+///     .await?;
+///     let address = signer.address().await?;
+///     let signature = signer.sign(&[0x05], &[0x01, 0x00]).await?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct KmsSigner {
+    client: KmsClient,
+    key_id: String,
+    curve: Curve,
+    public_key: PublicKey,
+}
+
+impl KmsSigner {
+    /// Creates a signer for the key `key_id` (a key id, a key ARN or an alias) of the AWS KMS in `region`.
+    pub async fn new(region: &str, credentials: KmsCredentials, key_id: &str) -> Result<Self> {
+        let endpoint = format!("https://kms.{}.amazonaws.com", region);
+
+        Self::new_with_endpoint(&endpoint, region, credentials, key_id).await
+    }
+
+    /// Creates a signer for the key `key_id` of the KMS served at `endpoint`.
+    pub async fn new_with_endpoint(
+        endpoint: &str,
+        region: &str,
+        credentials: KmsCredentials,
+        key_id: &str,
+    ) -> Result<Self> {
+        let client = KmsClient::new(endpoint, region, credentials)?;
+        let response: GetPublicKeyResponse =
+            client.call("GetPublicKey", &KeyRequest { key_id }).await?;
+        let (curve, public_key) = parse_spki(&decode_base64(&response.public_key)?)?;
+
+        Ok(Self {
+            client,
+            key_id: key_id.into(),
+            curve,
+            public_key,
+        })
+    }
+
+    pub fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    pub fn curve(&self) -> Curve {
+        self.curve
+    }
+
+    /// Signs the `bytes` prefixed with the `watermark` with the KMS key.
+    pub async fn sign_bytes(&self, watermark: &[u8], bytes: &[u8]) -> Result<Signature> {
+        let message = blake2b(&[watermark, bytes].concat(), MESSAGE_HASH_SIZE)?;
+        let response: SignResponse = self
+            .client
+            .call(
+                "Sign",
+                &SignRequest {
+                    key_id: &self.key_id,
+                    message: base64::encode(message),
+                    message_type: "DIGEST",
+                    signing_algorithm: "ECDSA_SHA_256",
+                },
+            )
+            .await?;
+
+        self.curve.signature(&decode_base64(&response.signature)?)
+    }
+}
+
+#[async_trait]
+impl Signer for KmsSigner {
+    async fn public_key(&self) -> tezos_operation::Result<PublicKey> {
+        Ok(self.public_key.clone())
+    }
+
+    async fn sign(&self, watermark: &[u8], bytes: &[u8]) -> tezos_operation::Result<Signature> {
+        Ok(self.sign_bytes(watermark, bytes).await?)
+    }
+}
+
+fn decode_base64(value: &str) -> Result<Vec<u8>> {
+    base64::decode(value).map_err(|error| Error::Kms {
+        description: error.to_string(),
+    })
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct KeyRequest<'a> {
+    key_id: &'a str,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct SignRequest<'a> {
+    key_id: &'a str,
+    message: String,
+    message_type: &'a str,
+    signing_algorithm: &'a str,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct GetPublicKeyResponse {
+    public_key: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct SignResponse {
+    signature: String,
+}
+
+#[derive(Deserialize)]
+struct ErrorResponse {
+    #[serde(rename = "__type")]
+    kind: Option<String>,
+    #[serde(alias = "Message")]
+    message: Option<String>,
+}
+
+/// A client of the KMS JSON API, signing its requests with AWS Signature Version 4.
+#[derive(Debug)]
+struct KmsClient {
+    http_client: Client,
+    endpoint: Url,
+    host: String,
+    region: String,
+    credentials: KmsCredentials,
+}
+
+impl KmsClient {
+    fn new(endpoint: &str, region: &str, credentials: KmsCredentials) -> Result<Self> {
+        let endpoint = Url::parse(endpoint).map_err(|error| Error::Kms {
+            description: error.to_string(),
+        })?;
+        let host = match (endpoint.host_str(), endpoint.port()) {
+            (Some(host), Some(port)) => format!("{}:{}", host, port),
+            (Some(host), None) => host.into(),
+            (None, _) => {
+                return Err(Error::Kms {
+                    description: format!("invalid endpoint {}", endpoint),
+                })
+            }
+        };
+
+        Ok(Self {
+            http_client: Client::new(),
+            endpoint,
+            host,
+            region: region.into(),
+            credentials,
+        })
+    }
+
+    async fn call<B: Serialize, T: DeserializeOwned>(&self, action: &str, body: &B) -> Result<T> {
+        let body = serde_json::to_vec(body)?;
+        let timestamp = timestamp(SystemTime::now())?;
+        let target = format!("TrentService.{}", action);
+
+        let mut headers = vec![
+            ("content-type", CONTENT_TYPE_JSON),
+            ("host", self.host.as_str()),
+            ("x-amz-date", timestamp.as_str()),
+            ("x-amz-target", target.as_str()),
+        ];
+        if let Some(session_token) = &self.credentials.session_token {
+            headers.push(("x-amz-security-token", session_token.as_str()));
+        }
+        let authorization = authorization(
+            &self.credentials,
+            &self.region,
+            SERVICE,
+            "POST",
+            self.endpoint.path(),
+            &headers,
+            &body,
+            &timestamp,
+        );
+
+        let mut request = self
+            .http_client
+            .post(self.endpoint.clone())
+            .header(CONTENT_TYPE, CONTENT_TYPE_JSON)
+            .header("authorization", authorization);
+        for (name, value) in headers {
+            if name != "content-type" && name != "host" {
+                request = request.header(name, value);
+            }
+        }
+        let response = request.body(body).send().await?;
+        let status = response.status();
+        let bytes = response.bytes().await?;
+        if !status.is_success() {
+            let description = match serde_json::from_slice::<ErrorResponse>(&bytes) {
+                Ok(ErrorResponse { kind, message }) => format!(
+                    "{}: {}",
+                    kind.unwrap_or_else(|| status.to_string()),
+                    message.unwrap_or_default()
+                ),
+                Err(_) => format!("{}: {}", status, String::from_utf8_lossy(&bytes)),
+            };
+            return Err(Error::Kms { description });
+        }
+
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+}
+
+/// Formats `time` as the `x-amz-date` ISO 8601 basic format, e.g. `20150830T123600Z`.
+fn timestamp(time: SystemTime) -> Result<String> {
+    let seconds = time
+        .duration_since(UNIX_EPOCH)
+        .map_err(|error| Error::Kms {
+            description: error.to_string(),
+        })?
+        .as_secs();
+    let time = NaiveDateTime::from_timestamp_opt(seconds as i64, 0).ok_or(Error::Kms {
+        description: "invalid system time".into(),
+    })?;
+
+    Ok(time.format("%Y%m%dT%H%M%SZ").to_string())
+}
+
+/// Creates the AWS Signature Version 4 `Authorization` header of a request without query parameters.
+///
+/// The `headers` are all the signed headers and have to contain `host` and `x-amz-date`.
+#[allow(clippy::too_many_arguments)]
+fn authorization(
+    credentials: &KmsCredentials,
+    region: &str,
+    service: &str,
+    method: &str,
+    path: &str,
+    headers: &[(&str, &str)],
+    payload: &[u8],
+    timestamp: &str,
+) -> String {
+    let mut headers: Vec<(String, &str)> = headers
+        .iter()
+        .map(|(name, value)| (name.to_lowercase(), value.trim()))
+        .collect();
+    headers.sort();
+    let canonical_headers: String = headers
+        .iter()
+        .map(|(name, value)| format!("{}:{}\n", name, value))
+        .collect();
+    let signed_headers = headers
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>()
+        .join(";");
+    let canonical_request = format!(
+        "{}\n{}\n\n{}\n{}\n{}",
+        method,
+        if path.is_empty() { "/" } else { path },
+        canonical_headers,
+        signed_headers,
+        hex::encode(Sha256::digest(payload))
+    );
+
+    let date = &timestamp[..8];
+    let scope = format!("{}/{}/{}/aws4_request", date, region, service);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        timestamp,
+        scope,
+        hex::encode(Sha256::digest(canonical_request.as_bytes()))
+    );
+
+    let key = [date, region, service, "aws4_request"].iter().fold(
+        format!("AWS4{}", credentials.secret_access_key).into_bytes(),
+        |key, value| hmac_sha256(&key, value.as_bytes()),
+    );
+    let signature = hex::encode(hmac_sha256(&key, string_to_sign.as_bytes()));
+
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        credentials.access_key_id, scope, signed_headers, signature
+    )
+}
+
+fn hmac_sha256(key: &[u8], message: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any size");
+    mac.update(message);
+
+    mac.finalize().into_bytes().to_vec()
+}
+
+#[cfg(test)]
+mod test {
+    use httpmock::prelude::*;
+    use tezos_core::types::encoded::{Encoded, Secp256K1Signature};
+
+    use super::*;
+
+    const SPKI: &str = "3056301006072a8648ce3d020106052b8104000a0342000479be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8";
+
+    fn credentials() -> KmsCredentials {
+        KmsCredentials::new(
+            "AKIDEXAMPLE".into(),
+            "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY".into(),
+        )
+    }
+
+    #[test]
+    fn test_authorization() {
+        // The `get-vanilla` case of the AWS Signature Version 4 test suite.
+        let authorization = authorization(
+            &credentials(),
+            "us-east-1",
+            "service",
+            "GET",
+            "/",
+            &[
+                ("Host", "example.amazonaws.com"),
+                ("X-Amz-Date", "20150830T123600Z"),
+            ],
+            &[],
+            "20150830T123600Z",
+        );
+
+        assert_eq!(
+            authorization,
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, SignedHeaders=host;x-amz-date, Signature=5fa00fa31553b73ebf1942676e86291e8372ff2a2260956d9b8aae1d763fbf31"
+        );
+    }
+
+    #[test]
+    fn test_timestamp() -> Result<()> {
+        let time = UNIX_EPOCH + std::time::Duration::from_secs(1440938160);
+        assert_eq!(timestamp(time)?, "20150830T123600Z");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sign() -> Result<()> {
+        let server = MockServer::start();
+        let get_public_key_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .header("x-amz-target", "TrentService.GetPublicKey")
+                .header_exists("authorization")
+                .json_body(serde_json::json!({ "KeyId": "alias/baker" }));
+            then.status(200)
+                .header("content-type", CONTENT_TYPE_JSON)
+                .json_body(serde_json::json!({
+                    "KeyId": "alias/baker",
+                    "KeySpec": "ECC_SECG_P256K1",
+                    "PublicKey": base64::encode(hex::decode(SPKI).unwrap()),
+                }));
+        });
+
+        let signer = KmsSigner::new_with_endpoint(
+            &server.base_url(),
+            "us-east-1",
+            credentials(),
+            "alias/baker",
+        )
+        .await?;
+        assert_eq!(signer.curve(), Curve::Secp256K1);
+        assert_eq!(
+            signer.address().await?.value(),
+            "tz2BCeQSi5ETyKJsob61pWCoQvoGtsrJBEt2"
+        );
+        get_public_key_mock.assert();
+
+        // KMS may return a high `s`, which has to be normalized
+        let r = [0x01u8; 32];
+        let low_s = [0x02u8; 32];
+        let high_s =
+            hex::decode("fdfdfdfdfdfdfdfdfdfdfdfdfdfdfdfcb8acdae4ad469e39bdd05c8ace343f3f")
+                .unwrap();
+        let der = [
+            [0x30, 0x45, 0x02, 0x20].as_slice(),
+            &r,
+            &[0x02, 0x21, 0x00],
+            &high_s,
+        ]
+        .concat();
+        let message = blake2b(&[0x05, 0x01, 0x00], MESSAGE_HASH_SIZE)?;
+        let sign_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .header("x-amz-target", "TrentService.Sign")
+                .json_body(serde_json::json!({
+                    "KeyId": "alias/baker",
+                    "Message": base64::encode(&message),
+                    "MessageType": "DIGEST",
+                    "SigningAlgorithm": "ECDSA_SHA_256",
+                }));
+            then.status(200)
+                .header("content-type", CONTENT_TYPE_JSON)
+                .json_body(serde_json::json!({
+                    "KeyId": "alias/baker",
+                    "Signature": base64::encode(&der),
+                    "SigningAlgorithm": "ECDSA_SHA_256",
+                }));
+        });
+
+        let signature = signer.sign(&[0x05], &[0x01, 0x00]).await?;
+        sign_mock.assert();
+        assert_eq!(
+            signature,
+            Secp256K1Signature::from_bytes(&[r.as_slice(), &low_s].concat())?.into()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_error() -> Result<()> {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(POST).path("/");
+            then.status(400)
+                .header("content-type", CONTENT_TYPE_JSON)
+                .json_body(serde_json::json!({
+                    "__type": "NotFoundException",
+                    "message": "Alias arn:aws:kms:us-east-1:000000000000:alias/baker is not found.",
+                }));
+        });
+
+        let result = KmsSigner::new_with_endpoint(
+            &server.base_url(),
+            "us-east-1",
+            credentials(),
+            "alias/baker",
+        )
+        .await;
+        assert!(matches!(
+            result,
+            Err(Error::Kms { description }) if description.starts_with("NotFoundException")
+        ));
+
+        Ok(())
+    }
+}
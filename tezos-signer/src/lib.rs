@@ -0,0 +1,63 @@
+//! The `tezos-signer` crate implements the signing side of the `octez-signer` remote signer protocol.
+//! It decodes the payloads it's asked to sign, enforces a signing policy and keeps high-watermarks
+//! of the signed blocks and consensus operations so that it never double signs.
+//!
+//! Keys are provided as [Signer](tezos_operation::signer::Signer) instances, e.g. an
+//! [InMemorySigner](tezos_operation::signer::InMemorySigner) using the `tezos-core` crypto providers:
+//!
+//! ```rust
+//! use tezos_operation::{operations::OperationContentTag, signer::InMemorySigner};
+//! use tezos_signer::{daemon::SignerDaemon, high_watermark::HighWatermarks, policy::SigningPolicy, request::magic_bytes};
+//!
+//! async fn example() -> tezos_signer::Result<()> {
+//!     let policy = SigningPolicy {
+//!         allowed_magic_bytes: Some(vec![magic_bytes::BLOCK, magic_bytes::PREATTESTATION, magic_bytes::ATTESTATION, magic_bytes::OPERATION]),
+//!         allowed_kinds: Some(vec![OperationContentTag::Reveal, OperationContentTag::Transaction]),
+//!         max_amount: Some(1_000_000u32.into()),
+//!         allowed_destinations: None,
+//!     };
+//!     let mut daemon = SignerDaemon::new(policy, HighWatermarks::persistent("high_watermarks.json")?);
+//!     daemon.add_signer(InMemorySigner::new(
+//!         "edskRv7VyXGVZb8EsrR7D9XKUbbAQNQGtALP6QeB16ZCD7SmmJpzyeneJVg3Mq56YLbxRA1kSdAXiswwPiaVfR3NHGMCXCziuZ".try_into()?,
+//!         "edpkttZKC51wemRqL2QxwpMnEKxWnbd35pq47Y6xsCHp5M1f7LN8NP".try_into()?,
+//!     )).await?;
+//!     Ok(())
+//! }
+//! ```
+//!
+//! ## Serve over HTTP
+//!
+//! With the `server` feature, the daemon can be served over HTTP with
+//! [serve](crate::server::serve), or run with the `tezos-signer` binary and a JSON configuration:
+//!
+//! ```shell
+//! cargo run --features server,full_crypto --bin tezos-signer -- signer.json
+//! ```
+//!
+//! ## Hardware keys
+//!
+//! With the `pkcs11` feature, `tz2` and `tz3` keys stored in a PKCS#11 token (an HSM or SoftHSM)
+//! can be used through the `Pkcs11Signer` of the `pkcs11` module. With the `kms` feature, keys stored
+//! in AWS KMS (or a compatible service like LocalStack) can be used through the `KmsSigner` of the `kms` module.
+//!
+//! ## Air-gapped signing
+//!
+//! With the `airgap` feature, operations can be sent to an offline device as an `OperationRequest`
+//! and signed back as an `OperationResponse`, both transferred as (animated) QR codes of fountain-coded URs.
+
+#[cfg(feature = "airgap")]
+pub mod airgap;
+pub mod daemon;
+pub mod ecdsa;
+mod error;
+pub mod high_watermark;
+#[cfg(feature = "kms")]
+pub mod kms;
+#[cfg(feature = "pkcs11")]
+pub mod pkcs11;
+pub mod policy;
+pub mod request;
+#[cfg(feature = "server")]
+pub mod server;
+
+pub use error::{Error, Result};
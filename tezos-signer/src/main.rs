@@ -0,0 +1,159 @@
+use std::{env, fs, net::SocketAddr, path::PathBuf, process, sync::Arc};
+
+use num_traits::FromPrimitive;
+use serde::Deserialize;
+use tezos_core::types::{
+    encoded::{Address, Encoded, PublicKey, SecretKey},
+    mutez::Mutez,
+};
+use tezos_operation::{
+    operations::OperationContentTag, signer::InMemorySigner, signing_payload::SigningPayload,
+};
+use tezos_signer::{
+    daemon::SignerDaemon, high_watermark::HighWatermarks, policy::SigningPolicy,
+    request::magic_bytes, server, Error, Result,
+};
+
+/// The signer configuration, read from the JSON file passed as the first argument:
+///
+/// ```json
+/// {
+///   "address": "127.0.0.1:6732",
+///   "keys": [{ "secret_key": "edsk...", "public_key": "edpk..." }],
+///   "high_watermarks": "high_watermarks.json",
+///   "policy": {
+///     "magic_bytes": [17, 18, 19],
+///     "kinds": ["transaction"],
+///     "max_amount": "1000000",
+///     "destinations": ["tz1..."]
+///   }
+/// }
+/// ```
+///
+/// `high_watermarks` is required unless the policy only allows magic bytes other than those of the blocks,
+/// preattestations and attestations, which could otherwise be signed twice after a restart.
+#[derive(Deserialize)]
+struct Config {
+    address: SocketAddr,
+    keys: Vec<KeyConfig>,
+    high_watermarks: Option<PathBuf>,
+    #[serde(default)]
+    policy: PolicyConfig,
+}
+
+#[derive(Deserialize)]
+struct KeyConfig {
+    secret_key: SecretKey,
+    public_key: PublicKey,
+}
+
+impl KeyConfig {
+    /// Checks that the public key matches the secret key, by verifying a signature made with the latter.
+    fn check(&self) -> Result<()> {
+        let payload = SigningPayload::Micheline(vec![]);
+        let signature = payload.sign(&self.secret_key)?;
+        if !payload.verify(&signature, &self.public_key)? {
+            return Err(Error::InvalidConfig {
+                description: format!(
+                    "public key {} doesn't match its secret key",
+                    self.public_key.value()
+                ),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Deserialize, Default)]
+struct PolicyConfig {
+    magic_bytes: Option<Vec<u8>>,
+    kinds: Option<Vec<String>>,
+    max_amount: Option<Mutez>,
+    destinations: Option<Vec<Address>>,
+}
+
+impl TryFrom<PolicyConfig> for SigningPolicy {
+    type Error = Error;
+
+    fn try_from(value: PolicyConfig) -> Result<Self> {
+        let allowed_kinds = value
+            .kinds
+            .map(|kinds| {
+                kinds
+                    .iter()
+                    .map(|kind| {
+                        (0..=u8::MAX)
+                            .filter_map(OperationContentTag::from_u8)
+                            .find(|tag| tag.name() == kind)
+                            .ok_or_else(|| Error::InvalidConfig {
+                                description: format!("unknown operation kind {}", kind),
+                            })
+                    })
+                    .collect::<Result<Vec<_>>>()
+            })
+            .transpose()?;
+
+        Ok(Self {
+            allowed_magic_bytes: value.magic_bytes,
+            allowed_kinds,
+            max_amount: value.max_amount,
+            allowed_destinations: value.destinations,
+        })
+    }
+}
+
+async fn run(config_path: &str) -> Result<()> {
+    let config: Config = serde_json::from_slice(&fs::read(config_path)?)?;
+    let policy: SigningPolicy = config.policy.try_into()?;
+    let signs_consensus = policy
+        .allowed_magic_bytes
+        .as_ref()
+        .map_or(true, |allowed_magic_bytes| {
+            [
+                magic_bytes::BLOCK,
+                magic_bytes::PREATTESTATION,
+                magic_bytes::ATTESTATION,
+            ]
+            .iter()
+            .any(|magic_byte| allowed_magic_bytes.contains(magic_byte))
+        });
+    let high_watermarks =
+        match config.high_watermarks {
+            Some(path) => HighWatermarks::persistent(path)?,
+            None if signs_consensus => return Err(Error::InvalidConfig {
+                description:
+                    "high_watermarks is required to sign blocks, preattestations or attestations"
+                        .into(),
+            }),
+            None => HighWatermarks::in_memory(),
+        };
+
+    let mut daemon = SignerDaemon::new(policy, high_watermarks);
+    for key in config.keys {
+        key.check()?;
+        let address = daemon
+            .add_signer(InMemorySigner::new(key.secret_key, key.public_key))
+            .await?;
+        println!("Serving key {}", String::from(address));
+    }
+
+    println!("Listening on {}", config.address);
+    server::serve(Arc::new(daemon), config.address).await
+}
+
+#[tokio::main]
+async fn main() {
+    let config_path = match env::args().nth(1) {
+        Some(path) => path,
+        None => {
+            eprintln!("Usage: tezos-signer <config.json>");
+            process::exit(2);
+        }
+    };
+
+    if let Err(error) = run(&config_path).await {
+        eprintln!("{}", error);
+        process::exit(1);
+    }
+}
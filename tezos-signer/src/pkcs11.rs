@@ -0,0 +1,507 @@
+//! A [Signer] backed by an ECDSA key stored in a PKCS#11 token, e.g. an HSM or SoftHSM.
+
+use std::{
+    ffi::c_void,
+    os::raw::c_ulong,
+    path::Path,
+    ptr,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use async_trait::async_trait;
+use libloading::Library;
+use tezos_core::{
+    internal::crypto::blake2b,
+    types::encoded::{PublicKey, Signature},
+};
+use tezos_operation::signer::Signer;
+
+use crate::{
+    ecdsa::{unwrap_octet_string, Curve},
+    Error, Result,
+};
+
+type Ulong = c_ulong;
+
+const CKR_OK: Ulong = 0x000;
+const CKR_USER_ALREADY_LOGGED_IN: Ulong = 0x100;
+const CKR_CRYPTOKI_ALREADY_INITIALIZED: Ulong = 0x191;
+
+const CKF_RW_SESSION: Ulong = 0x2;
+const CKF_SERIAL_SESSION: Ulong = 0x4;
+const CKF_OS_LOCKING_OK: Ulong = 0x2;
+
+const CKU_USER: Ulong = 1;
+
+const CKO_PUBLIC_KEY: Ulong = 2;
+const CKO_PRIVATE_KEY: Ulong = 3;
+
+const CKA_CLASS: Ulong = 0x000;
+const CKA_LABEL: Ulong = 0x003;
+const CKA_EC_PARAMS: Ulong = 0x180;
+const CKA_EC_POINT: Ulong = 0x181;
+
+const CKM_ECDSA: Ulong = 0x1041;
+
+const CK_UNAVAILABLE_INFORMATION: Ulong = !0;
+
+const MESSAGE_HASH_SIZE: usize = 32;
+const MAX_SIGNATURE_SIZE: usize = 256;
+const UNCOMPRESSED_POINT_SIZE: usize = 65;
+
+#[repr(C)]
+#[cfg_attr(windows, repr(packed))]
+struct Version {
+    major: u8,
+    minor: u8,
+}
+
+#[repr(C)]
+#[cfg_attr(windows, repr(packed))]
+struct Attribute {
+    kind: Ulong,
+    value: *mut c_void,
+    value_len: Ulong,
+}
+
+#[repr(C)]
+#[cfg_attr(windows, repr(packed))]
+struct Mechanism {
+    mechanism: Ulong,
+    parameter: *mut c_void,
+    parameter_len: Ulong,
+}
+
+#[repr(C)]
+#[cfg_attr(windows, repr(packed))]
+struct InitializeArgs {
+    create_mutex: *mut c_void,
+    destroy_mutex: *mut c_void,
+    lock_mutex: *mut c_void,
+    unlock_mutex: *mut c_void,
+    flags: Ulong,
+    reserved: *mut c_void,
+}
+
+type Unused = Option<unsafe extern "C" fn()>;
+
+/// The prefix of `CK_FUNCTION_LIST` up to `C_Sign`, the functions after it aren't used.
+#[repr(C)]
+#[cfg_attr(windows, repr(packed))]
+struct FunctionList {
+    version: Version,
+    initialize: Option<unsafe extern "C" fn(*mut InitializeArgs) -> Ulong>,
+    finalize: Option<unsafe extern "C" fn(*mut c_void) -> Ulong>,
+    get_info: Unused,
+    get_function_list: Unused,
+    get_slot_list: Unused,
+    get_slot_info: Unused,
+    get_token_info: Unused,
+    get_mechanism_list: Unused,
+    get_mechanism_info: Unused,
+    init_token: Unused,
+    init_pin: Unused,
+    set_pin: Unused,
+    open_session:
+        Option<unsafe extern "C" fn(Ulong, Ulong, *mut c_void, *mut c_void, *mut Ulong) -> Ulong>,
+    close_session: Option<unsafe extern "C" fn(Ulong) -> Ulong>,
+    close_all_sessions: Unused,
+    get_session_info: Unused,
+    get_operation_state: Unused,
+    set_operation_state: Unused,
+    login: Option<unsafe extern "C" fn(Ulong, Ulong, *const u8, Ulong) -> Ulong>,
+    logout: Unused,
+    create_object: Unused,
+    copy_object: Unused,
+    destroy_object: Unused,
+    get_object_size: Unused,
+    get_attribute_value: Option<unsafe extern "C" fn(Ulong, Ulong, *mut Attribute, Ulong) -> Ulong>,
+    set_attribute_value: Unused,
+    find_objects_init: Option<unsafe extern "C" fn(Ulong, *mut Attribute, Ulong) -> Ulong>,
+    find_objects: Option<unsafe extern "C" fn(Ulong, *mut Ulong, Ulong, *mut Ulong) -> Ulong>,
+    find_objects_final: Option<unsafe extern "C" fn(Ulong) -> Ulong>,
+    encrypt_init: Unused,
+    encrypt: Unused,
+    encrypt_update: Unused,
+    encrypt_final: Unused,
+    decrypt_init: Unused,
+    decrypt: Unused,
+    decrypt_update: Unused,
+    decrypt_final: Unused,
+    digest_init: Unused,
+    digest: Unused,
+    digest_update: Unused,
+    digest_key: Unused,
+    digest_final: Unused,
+    sign_init: Option<unsafe extern "C" fn(Ulong, *mut Mechanism, Ulong) -> Ulong>,
+    sign: Option<unsafe extern "C" fn(Ulong, *const u8, Ulong, *mut u8, *mut Ulong) -> Ulong>,
+}
+
+type GetFunctionList = unsafe extern "C" fn(*mut *const FunctionList) -> Ulong;
+
+macro_rules! call {
+    ($functions:expr, $name:ident ( $($arg:expr),* $(,)? )) => {{
+        let function = $functions.$name.ok_or_else(|| Error::Pkcs11 {
+            description: format!("{} is not supported by the module", stringify!($name)),
+        })?;
+        function($($arg),*)
+    }};
+}
+
+fn check(function: &str, value: Ulong) -> Result<()> {
+    if value == CKR_OK {
+        return Ok(());
+    }
+
+    Err(Error::Pkcs11 {
+        description: format!("{} failed with 0x{:08x}", function, value),
+    })
+}
+
+/// An open and logged in session with a PKCS#11 token.
+struct Session {
+    functions: *const FunctionList,
+    handle: Ulong,
+    finalize: bool,
+    _library: Library,
+}
+
+// The module is initialized with `CKF_OS_LOCKING_OK` and the session is only used behind a `Mutex`.
+unsafe impl Send for Session {}
+
+impl Session {
+    unsafe fn open(module: &Path, slot: u64, pin: &str) -> Result<Self> {
+        let library = Library::new(module)?;
+        let get_function_list: GetFunctionList = *library.get(b"C_GetFunctionList\0")?;
+        let mut functions: *const FunctionList = ptr::null();
+        check("C_GetFunctionList", get_function_list(&mut functions))?;
+        let list = functions.as_ref().ok_or_else(|| Error::Pkcs11 {
+            description: "C_GetFunctionList returned no functions".into(),
+        })?;
+
+        let mut args = InitializeArgs {
+            create_mutex: ptr::null_mut(),
+            destroy_mutex: ptr::null_mut(),
+            lock_mutex: ptr::null_mut(),
+            unlock_mutex: ptr::null_mut(),
+            flags: CKF_OS_LOCKING_OK,
+            reserved: ptr::null_mut(),
+        };
+        let finalize = match call!(list, initialize(&mut args)) {
+            CKR_CRYPTOKI_ALREADY_INITIALIZED => false,
+            value => {
+                check("C_Initialize", value)?;
+                true
+            }
+        };
+
+        let mut handle: Ulong = 0;
+        let opened = call!(
+            list,
+            open_session(
+                slot as Ulong,
+                CKF_SERIAL_SESSION | CKF_RW_SESSION,
+                ptr::null_mut(),
+                ptr::null_mut(),
+                &mut handle,
+            )
+        );
+        let session = Self {
+            functions,
+            handle,
+            finalize,
+            _library: library,
+        };
+        check("C_OpenSession", opened)?;
+
+        match call!(
+            list,
+            login(handle, CKU_USER, pin.as_ptr(), pin.len() as Ulong)
+        ) {
+            CKR_USER_ALREADY_LOGGED_IN => {}
+            value => check("C_Login", value)?,
+        }
+
+        Ok(session)
+    }
+
+    fn functions(&self) -> &FunctionList {
+        unsafe { &*self.functions }
+    }
+
+    fn find_object(&self, class: Ulong, label: &str) -> Result<Ulong> {
+        let mut class = class;
+        let mut template = [
+            Attribute {
+                kind: CKA_CLASS,
+                value: &mut class as *mut Ulong as *mut c_void,
+                value_len: std::mem::size_of::<Ulong>() as Ulong,
+            },
+            Attribute {
+                kind: CKA_LABEL,
+                value: label.as_ptr() as *mut c_void,
+                value_len: label.len() as Ulong,
+            },
+        ];
+        let mut object: Ulong = 0;
+        let mut count: Ulong = 0;
+        unsafe {
+            check(
+                "C_FindObjectsInit",
+                call!(
+                    self.functions(),
+                    find_objects_init(self.handle, template.as_mut_ptr(), template.len() as Ulong)
+                ),
+            )?;
+            let found = call!(
+                self.functions(),
+                find_objects(self.handle, &mut object, 1, &mut count)
+            );
+            check(
+                "C_FindObjectsFinal",
+                call!(self.functions(), find_objects_final(self.handle)),
+            )?;
+            check("C_FindObjects", found)?;
+        }
+        if count == 0 {
+            return Err(Error::UnknownKey);
+        }
+
+        Ok(object)
+    }
+
+    fn attribute(&self, object: Ulong, kind: Ulong) -> Result<Vec<u8>> {
+        let mut template = Attribute {
+            kind,
+            value: ptr::null_mut(),
+            value_len: 0,
+        };
+        unsafe {
+            check(
+                "C_GetAttributeValue",
+                call!(
+                    self.functions(),
+                    get_attribute_value(self.handle, object, &mut template, 1)
+                ),
+            )?;
+        }
+        let value_len = template.value_len;
+        if value_len == CK_UNAVAILABLE_INFORMATION {
+            return Err(Error::Pkcs11 {
+                description: format!("attribute 0x{:x} is not available", kind),
+            });
+        }
+
+        let mut value = vec![0u8; value_len as usize];
+        template.value = value.as_mut_ptr() as *mut c_void;
+        unsafe {
+            check(
+                "C_GetAttributeValue",
+                call!(
+                    self.functions(),
+                    get_attribute_value(self.handle, object, &mut template, 1)
+                ),
+            )?;
+        }
+        value.truncate(template.value_len as usize);
+
+        Ok(value)
+    }
+
+    fn sign(&self, key: Ulong, message: &[u8]) -> Result<Vec<u8>> {
+        let mut mechanism = Mechanism {
+            mechanism: CKM_ECDSA,
+            parameter: ptr::null_mut(),
+            parameter_len: 0,
+        };
+        let mut signature = vec![0u8; MAX_SIGNATURE_SIZE];
+        let mut signature_len = signature.len() as Ulong;
+        unsafe {
+            check(
+                "C_SignInit",
+                call!(
+                    self.functions(),
+                    sign_init(self.handle, &mut mechanism, key)
+                ),
+            )?;
+            check(
+                "C_Sign",
+                call!(
+                    self.functions(),
+                    sign(
+                        self.handle,
+                        message.as_ptr(),
+                        message.len() as Ulong,
+                        signature.as_mut_ptr(),
+                        &mut signature_len,
+                    )
+                ),
+            )?;
+        }
+        signature.truncate(signature_len as usize);
+
+        Ok(signature)
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        let functions = self.functions();
+        unsafe {
+            if let Some(close_session) = functions.close_session {
+                close_session(self.handle);
+            }
+            if self.finalize {
+                if let Some(finalize) = functions.finalize {
+                    finalize(ptr::null_mut());
+                }
+            }
+        }
+    }
+}
+
+/// A [Signer] backed by a `secp256k1` (`tz2`) or `P-256` (`tz3`) key stored in a PKCS#11 token.
+///
+/// The key pair is looked up by its label. The curve and the public key are read from the
+/// `CKA_EC_PARAMS` and `CKA_EC_POINT` attributes of the public key object, and the token
+/// signs the `blake2b` hash of the watermarked bytes with `CKM_ECDSA`.
+///
+/// ```rust,no_run
+/// use tezos_operation::signer::Signer;
+/// use tezos_signer::pkcs11::Pkcs11Signer;
+///
+/// async fn example() -> tezos_operation::Result<()> {
+///     let signer = Pkcs11Signer::new("/usr/lib/softhsm/libsofthsm2.so", 0, "1234", "baker")
+///         .expect("the PKCS#11 key should be available");
+///     let address = signer.address().await?;
+///     let signature = signer.sign(&[0x05], &[0x01, 0x00]).await?;
+///     Ok(())
+/// }
+/// ```
+pub struct Pkcs11Signer {
+    session: Arc<Mutex<Session>>,
+    private_key: Ulong,
+    curve: Curve,
+    public_key: PublicKey,
+}
+
+impl Pkcs11Signer {
+    /// Loads the PKCS#11 `module`, logs in to the token in `slot` with `pin`
+    /// and looks up the key pair labeled `label`.
+    pub fn new<P: AsRef<Path>>(module: P, slot: u64, pin: &str, label: &str) -> Result<Self> {
+        let session = unsafe { Session::open(module.as_ref(), slot, pin)? };
+        let private_key = session.find_object(CKO_PRIVATE_KEY, label)?;
+        let public_key = session.find_object(CKO_PUBLIC_KEY, label)?;
+        let curve = Curve::from_oid(&session.attribute(public_key, CKA_EC_PARAMS)?)?;
+        let point = session.attribute(public_key, CKA_EC_POINT)?;
+        let point = match unwrap_octet_string(&point) {
+            Ok(point) => point,
+            // Some tokens export the raw uncompressed point instead of wrapping it in a DER `OCTET STRING`.
+            Err(_) if point.len() == UNCOMPRESSED_POINT_SIZE && point[0] == 0x04 => &point,
+            Err(error) => return Err(error),
+        };
+        let public_key = curve.public_key(point)?;
+
+        Ok(Self {
+            session: Arc::new(Mutex::new(session)),
+            private_key,
+            curve,
+            public_key,
+        })
+    }
+
+    pub fn curve(&self) -> Curve {
+        self.curve
+    }
+
+    /// Signs the `bytes` prefixed with the `watermark` in the token, blocking the current thread.
+    pub fn sign_bytes(&self, watermark: &[u8], bytes: &[u8]) -> Result<Signature> {
+        let message = blake2b(&[watermark, bytes].concat(), MESSAGE_HASH_SIZE)?;
+        let signature = lock(&self.session)?.sign(self.private_key, &message)?;
+
+        self.curve.signature(&signature)
+    }
+
+    /// Signs the `bytes` prefixed with the `watermark` in the token, on the blocking thread pool of the runtime.
+    async fn sign_bytes_blocking(&self, watermark: &[u8], bytes: &[u8]) -> Result<Signature> {
+        let message = blake2b(&[watermark, bytes].concat(), MESSAGE_HASH_SIZE)?;
+        let session = self.session.clone();
+        let private_key = self.private_key;
+        let signature =
+            tokio::task::spawn_blocking(move || lock(&session)?.sign(private_key, &message))
+                .await
+                .map_err(|error| Error::Pkcs11 {
+                    description: error.to_string(),
+                })??;
+
+        self.curve.signature(&signature)
+    }
+}
+
+fn lock(session: &Mutex<Session>) -> Result<MutexGuard<'_, Session>> {
+    session.lock().map_err(|_| Error::Pkcs11 {
+        description: "the session is poisoned".into(),
+    })
+}
+
+#[async_trait]
+impl Signer for Pkcs11Signer {
+    async fn public_key(&self) -> tezos_operation::Result<PublicKey> {
+        Ok(self.public_key.clone())
+    }
+
+    async fn sign(&self, watermark: &[u8], bytes: &[u8]) -> tezos_operation::Result<Signature> {
+        Ok(self.sign_bytes_blocking(watermark, bytes).await?)
+    }
+}
+
+#[cfg(all(test, feature = "secp256_k1", feature = "p256"))]
+mod test {
+    use tezos_core::types::encoded::Encoded;
+    use tezos_operation::operations::{Transaction, UnsignedOperation};
+
+    use super::*;
+
+    /// Signs with a key of a local token, e.g. SoftHSM initialized with:
+    ///
+    /// ```shell
+    /// softhsm2-util --init-token --free --label tezos --pin 1234 --so-pin 1234
+    /// pkcs11-tool --module $TEZOS_SIGNER_PKCS11_MODULE --login --pin 1234 \
+    ///     --keypairgen --key-type EC:secp256k1 --label baker
+    /// ```
+    #[tokio::test]
+    #[ignore = "requires a PKCS#11 token, see TEZOS_SIGNER_PKCS11_MODULE"]
+    async fn test_sign() -> Result<()> {
+        let module = std::env::var("TEZOS_SIGNER_PKCS11_MODULE")
+            .expect("TEZOS_SIGNER_PKCS11_MODULE should be set");
+        let slot = std::env::var("TEZOS_SIGNER_PKCS11_SLOT")
+            .map(|slot| slot.parse().unwrap())
+            .unwrap_or(0);
+        let signer = Pkcs11Signer::new(module, slot, "1234", "baker")?;
+
+        let address = signer.address().await?;
+        assert!(address.value().starts_with(match signer.curve() {
+            Curve::Secp256K1 => "tz2",
+            Curve::P256 => "tz3",
+        }));
+
+        let operation = UnsignedOperation::new(
+            "BLyKu3tnc9NCuiFfCqfeVGPCoZTyW63dYh2XAYxkM7fQYKCqsju".try_into()?,
+            vec![Transaction::new(
+                address,
+                417u32.into(),
+                2336132u32.into(),
+                1527u32.into(),
+                357u32.into(),
+                1000u32.into(),
+                "tz1PwXjsrgYBi9wpe3tFhazJpt7JMTVzBp5c".try_into()?,
+                None,
+            )
+            .into()],
+        );
+        let signed_operation = operation.into_signed_operation_using(&signer).await?;
+        assert!(signed_operation.verify(&signer.public_key().await?)?);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,213 @@
+use tezos_core::types::{
+    encoded::{Address, Encoded},
+    mutez::Mutez,
+};
+use tezos_operation::operations::{OperationContent, OperationContentTag, UnsignedOperation};
+
+use crate::{request::SigningRequest, Error, Result};
+
+/// Restrictions on the payloads a [SignerDaemon](crate::daemon::SignerDaemon) accepts to sign.
+///
+/// Every restriction set to `None` is disabled, so the default policy accepts all the payloads.
+#[derive(Debug, Clone, Default)]
+pub struct SigningPolicy {
+    /// The magic bytes of the payloads that can be signed.
+    pub allowed_magic_bytes: Option<Vec<u8>>,
+    /// The kinds of operation contents that can be signed.
+    pub allowed_kinds: Option<Vec<OperationContentTag>>,
+    /// The maximum amount an operation can transfer or originate.
+    pub max_amount: Option<Mutez>,
+    /// The destinations an operation can transfer to.
+    pub allowed_destinations: Option<Vec<Address>>,
+}
+
+impl SigningPolicy {
+    /// Checks that the `request` satisfies the policy.
+    pub fn check(&self, request: &SigningRequest) -> Result<()> {
+        if let Some(allowed_magic_bytes) = &self.allowed_magic_bytes {
+            if !allowed_magic_bytes.contains(&request.magic_byte()) {
+                return Err(Error::PolicyViolation {
+                    description: format!(
+                        "magic byte 0x{:02x} is not allowed",
+                        request.magic_byte()
+                    ),
+                });
+            }
+        }
+        if let SigningRequest::Operation(operation) = request {
+            self.check_operation(operation)?;
+        }
+
+        Ok(())
+    }
+
+    fn check_operation(&self, operation: &UnsignedOperation) -> Result<()> {
+        let mut amount = Mutez::default();
+        for content in &operation.contents {
+            if let Some(allowed_kinds) = &self.allowed_kinds {
+                if !allowed_kinds.contains(&content.tag()) {
+                    return Err(Error::PolicyViolation {
+                        description: format!(
+                            "operation kind {} is not allowed",
+                            content.tag().name()
+                        ),
+                    });
+                }
+            }
+            match content {
+                OperationContent::Transaction(transaction) => {
+                    amount = Self::add_amount(amount, transaction.amount)?;
+                    if let Some(allowed_destinations) = &self.allowed_destinations {
+                        if !allowed_destinations.contains(&transaction.destination) {
+                            return Err(Error::PolicyViolation {
+                                description: format!(
+                                    "destination {} is not allowed",
+                                    transaction.destination.value()
+                                ),
+                            });
+                        }
+                    }
+                }
+                OperationContent::Origination(origination) => {
+                    amount = Self::add_amount(amount, origination.balance)?;
+                }
+                _ => {}
+            }
+        }
+        if let Some(max_amount) = self.max_amount {
+            if amount > max_amount {
+                return Err(Error::PolicyViolation {
+                    description: format!(
+                        "amount {} exceeds the maximum amount {}",
+                        amount, max_amount
+                    ),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn add_amount(amount: Mutez, other: Mutez) -> Result<Mutez> {
+        amount
+            .checked_add(other)
+            .ok_or_else(|| Error::PolicyViolation {
+                description: "the total amount of the operation overflows".into(),
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tezos_operation::operations::{Delegation, Transaction};
+
+    use super::*;
+    use crate::request::magic_bytes;
+
+    fn transaction(amount: u64, destination: &str) -> OperationContent {
+        Transaction::new(
+            "tz1PwXjsrgYBi9wpe3tFhazJpt7JMTVzBp5c".try_into().unwrap(),
+            417u32.into(),
+            2336132u32.into(),
+            1527u32.into(),
+            357u32.into(),
+            amount.try_into().unwrap(),
+            destination.try_into().unwrap(),
+            None,
+        )
+        .into()
+    }
+
+    fn operation(contents: Vec<OperationContent>) -> SigningRequest {
+        SigningRequest::Operation(UnsignedOperation::new(
+            "BLyKu3tnc9NCuiFfCqfeVGPCoZTyW63dYh2XAYxkM7fQYKCqsju"
+                .try_into()
+                .unwrap(),
+            contents,
+        ))
+    }
+
+    #[test]
+    fn test_default_policy() -> Result<()> {
+        let policy = SigningPolicy::default();
+        policy.check(&operation(vec![transaction(
+            u32::MAX.into(),
+            "tz2AjVPbMHdDF1XwHVhUrTg6ZvqY83AYhJEy",
+        )]))?;
+        policy.check(&SigningRequest::Micheline(vec![0x00]))?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_magic_bytes() -> Result<()> {
+        let policy = SigningPolicy {
+            allowed_magic_bytes: Some(vec![magic_bytes::OPERATION]),
+            ..Default::default()
+        };
+        policy.check(&operation(vec![]))?;
+        assert!(matches!(
+            policy.check(&SigningRequest::Micheline(vec![0x00])),
+            Err(Error::PolicyViolation { .. })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_operation_restrictions() -> Result<()> {
+        let policy = SigningPolicy {
+            allowed_kinds: Some(vec![OperationContentTag::Transaction]),
+            max_amount: Some(1000u16.into()),
+            allowed_destinations: Some(vec!["tz2AjVPbMHdDF1XwHVhUrTg6ZvqY83AYhJEy"
+                .try_into()
+                .unwrap()]),
+            ..Default::default()
+        };
+        policy.check(&operation(vec![
+            transaction(400, "tz2AjVPbMHdDF1XwHVhUrTg6ZvqY83AYhJEy"),
+            transaction(600, "tz2AjVPbMHdDF1XwHVhUrTg6ZvqY83AYhJEy"),
+        ]))?;
+
+        let violations = vec![
+            operation(vec![
+                transaction(400, "tz2AjVPbMHdDF1XwHVhUrTg6ZvqY83AYhJEy"),
+                transaction(601, "tz2AjVPbMHdDF1XwHVhUrTg6ZvqY83AYhJEy"),
+            ]),
+            operation(vec![transaction(1, "tz1PwXjsrgYBi9wpe3tFhazJpt7JMTVzBp5c")]),
+            operation(vec![Delegation::new(
+                "tz1PwXjsrgYBi9wpe3tFhazJpt7JMTVzBp5c".try_into().unwrap(),
+                417u32.into(),
+                2336132u32.into(),
+                1527u32.into(),
+                357u32.into(),
+                None,
+            )
+            .into()]),
+        ];
+        for violation in violations {
+            assert!(matches!(
+                policy.check(&violation),
+                Err(Error::PolicyViolation { .. })
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_amount_overflow() -> Result<()> {
+        let policy = SigningPolicy::default();
+        let near_max = i64::MAX as u64 - 1;
+
+        assert!(matches!(
+            policy.check(&operation(vec![
+                transaction(near_max, "tz2AjVPbMHdDF1XwHVhUrTg6ZvqY83AYhJEy"),
+                transaction(near_max, "tz2AjVPbMHdDF1XwHVhUrTg6ZvqY83AYhJEy"),
+            ])),
+            Err(Error::PolicyViolation { .. })
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,198 @@
+use tezos_core::types::encoded::ChainId;
+use tezos_operation::{operations::UnsignedOperation, signing_payload::SigningPayload};
+
+use crate::{Error, Result};
+
+/// The magic bytes (watermarks) prefixing the payloads sent to a signer.
+pub mod magic_bytes {
+    pub use tezos_operation::signing_payload::watermark::*;
+}
+
+/// A payload sent to a signer, decoded according to its magic byte.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SigningRequest {
+    Block(ConsensusRequest),
+    Preattestation(ConsensusRequest),
+    Attestation(ConsensusRequest),
+    Operation(UnsignedOperation),
+    Micheline(Vec<u8>),
+}
+
+/// The properties of a block or a consensus operation which are tracked to prevent double signing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConsensusRequest {
+    pub chain_id: ChainId,
+    pub level: i32,
+    pub round: i32,
+}
+
+impl SigningRequest {
+    /// Decodes the `bytes` prefixed with a magic byte, see [SigningPayload::from_bytes].
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        SigningPayload::from_bytes(bytes)
+            .map_err(|_| Error::InvalidPayload)?
+            .try_into()
+    }
+
+    pub fn magic_byte(&self) -> u8 {
+        match self {
+            Self::Block(_) => magic_bytes::BLOCK,
+            Self::Preattestation(_) => magic_bytes::PREATTESTATION,
+            Self::Attestation(_) => magic_bytes::ATTESTATION,
+            Self::Operation(_) => magic_bytes::OPERATION,
+            Self::Micheline(_) => magic_bytes::MICHELINE,
+        }
+    }
+}
+
+impl TryFrom<SigningPayload> for SigningRequest {
+    type Error = Error;
+
+    /// Fails with [Error::InvalidPayload] when the level and round of a consensus payload can't be read,
+    /// as the high watermark couldn't protect it.
+    fn try_from(value: SigningPayload) -> Result<Self> {
+        let consensus = |chain_id: ChainId| -> Result<ConsensusRequest> {
+            let (level, round) = value.level_and_round().ok_or(Error::InvalidPayload)?;
+            Ok(ConsensusRequest {
+                chain_id,
+                level,
+                round,
+            })
+        };
+        Ok(match &value {
+            SigningPayload::Block { chain_id, .. } => Self::Block(consensus(chain_id.clone())?),
+            SigningPayload::Preattestation { chain_id, .. } => {
+                Self::Preattestation(consensus(chain_id.clone())?)
+            }
+            SigningPayload::Attestation { chain_id, .. } => {
+                Self::Attestation(consensus(chain_id.clone())?)
+            }
+            SigningPayload::Operation(operation) => Self::Operation(operation.clone()),
+            SigningPayload::Micheline(bytes) => Self::Micheline(bytes.clone()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tezos_core::types::encoded::Encoded;
+    use tezos_operation::{
+        block_header::BlockHeader,
+        operations::{Endorsement, Operation, Transaction},
+    };
+
+    use super::*;
+
+    const CHAIN_ID: &str = "NetXdQprcVkpaWU";
+
+    #[test]
+    fn test_decode_attestation() -> Result<()> {
+        let chain_id: ChainId = CHAIN_ID.try_into()?;
+        let operation = UnsignedOperation::new(
+            "BLyKu3tnc9NCuiFfCqfeVGPCoZTyW63dYh2XAYxkM7fQYKCqsju".try_into()?,
+            vec![Endorsement::new(
+                1,
+                2504461,
+                2,
+                "vh32fG1tMNPtzZiKPHinfLPSAU3m2piFSgud4jBdaGSKJQH6q7Xd".try_into()?,
+            )
+            .into()],
+        );
+        let bytes = [
+            [magic_bytes::ATTESTATION].as_slice(),
+            &chain_id.to_bytes()?,
+            &operation.to_forged_bytes()?,
+        ]
+        .concat();
+
+        assert_eq!(
+            SigningRequest::decode(&bytes)?,
+            SigningRequest::Attestation(ConsensusRequest {
+                chain_id,
+                level: 2504461,
+                round: 2,
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_block() -> Result<()> {
+        let header = BlockHeader::from_forged_bytes(hex::decode("0026370d0d9444f40d9c1824d30918fa20dcbc28261a8973d9a544f33f5cc56a13830189af0000000062c28ab804d7bfc960df8ce93f0b9356181c5651bf90ebcae5b2a2776b66aeeda0000f4229000000210000000102000000040026370d0000000000000004ffffffff000000040000000083e9c04c6f3c610e6b5c65b33805c24a6c1902b58bec02a4cc482ffca828b77ac4451b4e45ee2eae5d15652c8826ee011f22915ae1387dd605766c5ad1b5cf8c00000000cb9f439e02840100000214a92861740b16b11c8bb6791abdae138bf18f18a85915896a7d5da0b59fc0c3a16494f7832b05164f63f1d5797d3103256e5c2e1f0ddf3fca57e71ab8c4a56a").unwrap())?;
+        let chain_id: ChainId = CHAIN_ID.try_into()?;
+        let bytes = [
+            [magic_bytes::BLOCK].as_slice(),
+            &chain_id.to_bytes()?,
+            &header.to_unsigned_forged_bytes()?,
+        ]
+        .concat();
+
+        assert_eq!(
+            SigningRequest::decode(&bytes)?,
+            SigningRequest::Block(ConsensusRequest {
+                chain_id,
+                level: 2504461,
+                round: 0,
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unreadable_level() -> Result<()> {
+        let payload = SigningPayload::Attestation {
+            chain_id: CHAIN_ID.try_into()?,
+            operation: UnsignedOperation::new(
+                "BLyKu3tnc9NCuiFfCqfeVGPCoZTyW63dYh2XAYxkM7fQYKCqsju".try_into()?,
+                vec![],
+            ),
+        };
+
+        assert!(matches!(
+            SigningRequest::try_from(payload),
+            Err(Error::InvalidPayload)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_operation() -> Result<()> {
+        let operation = UnsignedOperation::new(
+            "BLyKu3tnc9NCuiFfCqfeVGPCoZTyW63dYh2XAYxkM7fQYKCqsju".try_into()?,
+            vec![Transaction::new(
+                "tz1PwXjsrgYBi9wpe3tFhazJpt7JMTVzBp5c".try_into()?,
+                417u32.into(),
+                2336132u32.into(),
+                1527u32.into(),
+                357u32.into(),
+                1000u32.into(),
+                "tz2AjVPbMHdDF1XwHVhUrTg6ZvqY83AYhJEy".try_into()?,
+                None,
+            )
+            .into()],
+        );
+        let bytes = [
+            [magic_bytes::OPERATION].as_slice(),
+            &operation.to_forged_bytes()?,
+        ]
+        .concat();
+
+        assert_eq!(
+            SigningRequest::decode(&bytes)?,
+            SigningRequest::Operation(operation)
+        );
+        assert!(matches!(
+            SigningRequest::decode(&[0x42, 0x00]),
+            Err(Error::InvalidPayload)
+        ));
+        assert!(matches!(
+            SigningRequest::decode(&[]),
+            Err(Error::InvalidPayload)
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,206 @@
+use std::{convert::Infallible, future::Future, net::SocketAddr, sync::Arc};
+
+use hyper::{
+    body::HttpBody,
+    header::CONTENT_TYPE,
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use serde::Serialize;
+use tezos_core::types::encoded::{Encoded, ImplicitAddress};
+
+use crate::{daemon::SignerDaemon, Error, Result};
+
+/// The maximum size of a signing request body, forged operations are a few kilobytes at most.
+const MAX_BODY_SIZE: usize = 64 * 1024;
+
+/// Serves the `daemon` at `address` over the `octez-signer` HTTP protocol:
+///
+/// - `GET /keys/<pkh>` returns the public key of `pkh`
+/// - `POST /keys/<pkh>` signs the hex encoded payload with `pkh`
+/// - `GET /authorized_keys` returns no keys, as requests are not authenticated
+pub async fn serve(daemon: Arc<SignerDaemon>, address: SocketAddr) -> Result<()> {
+    serve_with_shutdown(daemon, address, std::future::pending()).await
+}
+
+/// Serves the `daemon` at `address` until the `shutdown` future completes.
+pub async fn serve_with_shutdown<F>(
+    daemon: Arc<SignerDaemon>,
+    address: SocketAddr,
+    shutdown: F,
+) -> Result<()>
+where
+    F: Future<Output = ()>,
+{
+    let make_service = make_service_fn(move |_| {
+        let daemon = daemon.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| {
+                let daemon = daemon.clone();
+                async move { Ok::<_, Infallible>(handle(&daemon, request).await) }
+            }))
+        }
+    });
+
+    Ok(Server::try_bind(&address)?
+        .serve(make_service)
+        .with_graceful_shutdown(shutdown)
+        .await?)
+}
+
+/// Handles a single `octez-signer` protocol request.
+pub async fn handle(daemon: &SignerDaemon, request: Request<Body>) -> Response<Body> {
+    let path = request.uri().path().to_owned();
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    let result = match (request.method(), segments.as_slice()) {
+        (&Method::GET, ["authorized_keys"]) => json(&serde_json::json!({})),
+        (&Method::GET, ["keys", public_key_hash]) => get_public_key(daemon, public_key_hash).await,
+        (&Method::POST, ["keys", public_key_hash]) => {
+            let public_key_hash = public_key_hash.to_string();
+            sign(daemon, &public_key_hash, request.into_body()).await
+        }
+        _ => Ok(text(StatusCode::NOT_FOUND, "Not found".into())),
+    };
+
+    result.unwrap_or_else(|error| text(status_code(&error), error.to_string()))
+}
+
+async fn get_public_key(daemon: &SignerDaemon, public_key_hash: &str) -> Result<Response<Body>> {
+    let public_key_hash = parse_public_key_hash(public_key_hash)?;
+    let public_key = daemon.public_key(&public_key_hash).await?;
+
+    json(&serde_json::json!({ "public_key": public_key.value() }))
+}
+
+async fn sign(daemon: &SignerDaemon, public_key_hash: &str, body: Body) -> Result<Response<Body>> {
+    let public_key_hash = parse_public_key_hash(public_key_hash)?;
+    let body = read_body(body).await?;
+    let payload: String = serde_json::from_slice(&body).map_err(|_| Error::InvalidPayload)?;
+    let bytes = hex::decode(payload).map_err(|_| Error::InvalidPayload)?;
+    let signature = daemon.sign(&public_key_hash, &bytes).await?;
+
+    json(&serde_json::json!({ "signature": signature.value() }))
+}
+
+async fn read_body(mut body: Body) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk?;
+        if bytes.len() + chunk.len() > MAX_BODY_SIZE {
+            return Err(Error::PayloadTooLarge);
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+
+    Ok(bytes)
+}
+
+fn parse_public_key_hash(value: &str) -> Result<ImplicitAddress> {
+    value.try_into().map_err(|_| Error::UnknownKey)
+}
+
+fn status_code(error: &Error) -> StatusCode {
+    match error {
+        Error::UnknownKey => StatusCode::NOT_FOUND,
+        Error::InvalidPayload => StatusCode::BAD_REQUEST,
+        Error::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+        Error::PolicyViolation { .. } | Error::DoubleSigning { .. } => StatusCode::FORBIDDEN,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+fn json<T: Serialize>(value: &T) -> Result<Response<Body>> {
+    Ok(Response::builder()
+        .header(CONTENT_TYPE, "application/json")
+        .body(serde_json::to_vec(value)?.into())
+        .unwrap())
+}
+
+fn text(status: StatusCode, message: String) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, "text/plain")
+        .body(message.into())
+        .unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use tezos_operation::signer::{InMemorySigner, Signer};
+    use tezos_rpc::remote_signer::RemoteSigner;
+
+    use super::*;
+    use crate::{high_watermark::HighWatermarks, policy::SigningPolicy, request::magic_bytes};
+
+    async fn start(daemon: SignerDaemon) -> (SocketAddr, tokio::sync::oneshot::Sender<()>) {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        drop(listener);
+
+        let (sender, receiver) = tokio::sync::oneshot::channel::<()>();
+        tokio::spawn(serve_with_shutdown(Arc::new(daemon), address, async {
+            receiver.await.ok();
+        }));
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+        (address, sender)
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_remote_signer_roundtrip() -> Result<()> {
+        let in_memory_signer = InMemorySigner::new(
+            "edskRv7VyXGVZb8EsrR7D9XKUbbAQNQGtALP6QeB16ZCD7SmmJpzyeneJVg3Mq56YLbxRA1kSdAXiswwPiaVfR3NHGMCXCziuZ".try_into()?,
+            "edpkttZKC51wemRqL2QxwpMnEKxWnbd35pq47Y6xsCHp5M1f7LN8NP".try_into()?,
+        );
+        let mut daemon = SignerDaemon::new(
+            SigningPolicy {
+                allowed_magic_bytes: Some(vec![magic_bytes::MICHELINE]),
+                ..Default::default()
+            },
+            HighWatermarks::in_memory(),
+        );
+        let address = daemon.add_signer(in_memory_signer.clone()).await?;
+        let (socket_address, shutdown) = start(daemon).await;
+
+        let remote_signer = RemoteSigner::new(format!("http://{}", socket_address), address);
+        assert_eq!(
+            Signer::public_key(&remote_signer).await?,
+            in_memory_signer.public_key().await?
+        );
+        assert_eq!(remote_signer.get_authorized_keys().await.ok(), Some(None));
+        assert!(matches!(
+            Signer::sign(&remote_signer, &[magic_bytes::OPERATION], &[]).await,
+            Err(tezos_operation::Error::Signer { .. })
+        ));
+        #[cfg(feature = "ed25519")]
+        assert_eq!(
+            Signer::sign(&remote_signer, &[magic_bytes::MICHELINE], &[0x01, 0x00]).await?,
+            in_memory_signer
+                .sign(&[magic_bytes::MICHELINE], &[0x01, 0x00])
+                .await?
+        );
+
+        let unknown_signer = RemoteSigner::new(
+            format!("http://{}", socket_address),
+            "tz1PwXjsrgYBi9wpe3tFhazJpt7JMTVzBp5c".try_into()?,
+        );
+        assert!(unknown_signer.get_public_key().await.is_err());
+
+        shutdown.send(()).ok();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_payload_too_large() {
+        let daemon = SignerDaemon::new(Default::default(), HighWatermarks::in_memory());
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("/keys/tz1PwXjsrgYBi9wpe3tFhazJpt7JMTVzBp5c")
+            .body(vec![b'0'; MAX_BODY_SIZE + 1].into())
+            .unwrap();
+
+        let response = handle(&daemon, request).await;
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+}